@@ -3,7 +3,8 @@ use crate::Occur;
 use combine::parser::char::{char, digit, letter, space, spaces, string};
 use combine::parser::Parser;
 use combine::{
-    attempt, choice, eof, many, many1, one_of, optional, parser, satisfy, skip_many1, value,
+    attempt, choice, eof, many, many1, not_followed_by, one_of, optional, parser, satisfy,
+    skip_many1, value,
 };
 use combine::{error::StringStreamError, parser::combinator::recognize};
 
@@ -91,23 +92,104 @@ fn date_time<'a>() -> impl Parser<&'a str, Output = String> {
     recognize((date, char('T'), time))
 }
 
-fn term_val<'a>() -> impl Parser<&'a str, Output = String> {
+/// Parses the optional slop of a phrase, as in `"quick fox"~2`.
+fn slop_val<'a>() -> impl Parser<&'a str, Output = u32> {
+    let slop = (char('~'), many1(digit())).and_then(|(_, slop): (char, String)| {
+        slop.parse::<u32>()
+            .map_err(|_| StringStreamError::UnexpectedParse)
+    });
+    optional(slop).map(|slop_opt| slop_opt.unwrap_or(0))
+}
+
+fn term_val<'a>() -> impl Parser<&'a str, Output = (String, u32)> {
     let phrase = char('"').with(many1(satisfy(|c| c != '"'))).skip(char('"'));
-    phrase.or(word())
+    (phrase, slop_val()).or(word().map(|word| (word, 0)))
+}
+
+/// Parses a word containing the wildcards `*` or `?`, as in `comp*` or `te?t`.
+fn wildcard_val<'a>() -> impl Parser<&'a str, Output = String> {
+    word().and_then(|word: String| {
+        if word != "*" && word.contains(['*', '?']) {
+            Ok(word)
+        } else {
+            Err(StringStreamError::UnexpectedParse)
+        }
+    })
+}
+
+fn wildcard<'a>() -> impl Parser<&'a str, Output = UserInputLeaf> {
+    (optional(attempt(field())), wildcard_val()).map(|(field_name, pattern)| {
+        UserInputLeaf::Wildcard {
+            field_name,
+            pattern,
+        }
+    })
+}
+
+/// The Levenshtein distance of `fox~`.
+const DEFAULT_FUZZY_DISTANCE: u8 = 2;
+
+/// Parses a fuzzy term, as in `fox~`, `fox~1` or, to match prefixes, `fox*~1`.
+fn fuzzy<'a>() -> impl Parser<&'a str, Output = UserInputLeaf> {
+    (optional(attempt(field())), word()).and_then(|(field_name, word)| {
+        let (term, distance) = word
+            .rsplit_once('~')
+            .ok_or(StringStreamError::UnexpectedParse)?;
+        let distance = if distance.is_empty() {
+            DEFAULT_FUZZY_DISTANCE
+        } else if distance.chars().all(|c| c.is_ascii_digit()) {
+            distance
+                .parse::<u8>()
+                .map_err(|_| StringStreamError::UnexpectedParse)?
+        } else {
+            return Err(StringStreamError::UnexpectedParse);
+        };
+        let (term, prefix) = match term.strip_suffix('*') {
+            Some(term) => (term, true),
+            None => (term, false),
+        };
+        if term.is_empty() || term.contains(['~', '*', '?']) {
+            return Err(StringStreamError::UnexpectedParse);
+        }
+        Ok(UserInputLeaf::Fuzzy {
+            field_name,
+            term: term.to_string(),
+            distance,
+            prefix,
+        })
+    })
+}
+
+/// Parses a regular expression delimited by slashes, as in `/te.t/`.
+/// Slashes within the expression are escaped, as in `/a\/b/`.
+fn regex<'a>() -> impl Parser<&'a str, Output = UserInputLeaf> {
+    let regex_char = attempt(string("\\/").map(|_| '/')).or(satisfy(|c| c != '/'));
+    let pattern = char('/')
+        .with(many1(regex_char))
+        .skip(char('/'))
+        .skip(not_followed_by(satisfy(|c: char| {
+            !c.is_whitespace() && c != ')' && c != '^'
+        })));
+    (optional(attempt(field())), pattern).map(|(field_name, pattern)| UserInputLeaf::Regex {
+        field_name,
+        pattern,
+    })
 }
 
 fn term_query<'a>() -> impl Parser<&'a str, Output = UserInputLiteral> {
-    let term_val_with_field = negative_number().or(term_val());
-    (field(), term_val_with_field).map(|(field_name, phrase)| UserInputLiteral {
+    let term_val_with_field = negative_number().map(|number| (number, 0)).or(term_val());
+    (field(), term_val_with_field).map(|(field_name, (phrase, slop))| UserInputLiteral {
         field_name: Some(field_name),
         phrase,
+        slop,
     })
 }
 
 fn literal<'a>() -> impl Parser<&'a str, Output = UserInputLeaf> {
-    let term_default_field = term_val().map(|phrase| UserInputLiteral {
+    let term_default_field = term_val().map(|(phrase, slop)| UserInputLiteral {
         field_name: None,
         phrase,
+        slop,
     });
     attempt(term_query())
         .or(term_default_field)
@@ -218,6 +300,9 @@ fn leaf<'a>() -> impl Parser<&'a str, Output = UserInputAst> {
         char('(')
             .with(ast())
             .skip(char(')'))
+            .or(attempt(regex().map(UserInputAst::from)))
+            .or(attempt(fuzzy().map(UserInputAst::from)))
+            .or(attempt(wildcard().map(UserInputAst::from)))
             .or(char('*').map(|_| UserInputAst::from(UserInputLeaf::All)))
             .or(attempt(
                 string("NOT").skip(spaces1()).with(leaf()).map(negate),
@@ -438,6 +523,50 @@ mod test {
         test_parse_query_to_ast_helper("a^1", "\"a\"");
     }
 
+    #[test]
+    fn test_parse_query_slop() {
+        test_parse_query_to_ast_helper("\"quick fox\"~2", "\"quick fox\"~2");
+        test_parse_query_to_ast_helper("\"quick fox\"~0", "\"quick fox\"");
+        test_parse_query_to_ast_helper("title:\"quick fox\"~1", "title:\"quick fox\"~1");
+        test_parse_query_to_ast_helper("\"quick fox\"~2^3", "(\"quick fox\"~2)^3");
+        test_parse_query_to_ast_helper("\"quick fox\"~2 dog", "(*\"quick fox\"~2 *\"dog\")");
+        test_is_parse_err("\"quick fox\"~a");
+    }
+
+    #[test]
+    fn test_parse_query_wildcard() {
+        test_parse_query_to_ast_helper("comp*", "comp*");
+        test_parse_query_to_ast_helper("title:comp*", "title:comp*");
+        test_parse_query_to_ast_helper("te?t", "te?t");
+        test_parse_query_to_ast_helper("*mp?t*^2", "(*mp?t*)^2");
+        test_parse_query_to_ast_helper("-title:comp* test", "(-title:comp* *\"test\")");
+        test_parse_query_to_ast_helper("\"comp*\"", "\"comp*\"");
+        test_parse_query_to_ast_helper("*", "*");
+    }
+
+    #[test]
+    fn test_parse_query_fuzzy() {
+        test_parse_query_to_ast_helper("fox~", "fox~2");
+        test_parse_query_to_ast_helper("fox~1", "fox~1");
+        test_parse_query_to_ast_helper("title:fox~0", "title:fox~0");
+        test_parse_query_to_ast_helper("title:fox*~1^2", "(title:fox*~1)^2");
+        test_parse_query_to_ast_helper("-fox~1 dog", "(-fox~1 *\"dog\")");
+        test_parse_query_to_ast_helper("fox~a", "\"fox~a\"");
+        test_parse_query_to_ast_helper("fo?~1", "fo?~1");
+        test_parse_query_to_ast_helper("~1", "\"~1\"");
+    }
+
+    #[test]
+    fn test_parse_query_regex() {
+        test_parse_query_to_ast_helper("/te.t/", "/te.t/");
+        test_parse_query_to_ast_helper("title:/te[sx]t?/", "title:/te[sx]t?/");
+        test_parse_query_to_ast_helper("title:/a b/^2", "(title:/a b/)^2");
+        test_parse_query_to_ast_helper("(/a\\/b/ c)", "(*/a/b/ *\"c\")");
+        // Facets are not regular expressions.
+        test_parse_query_to_ast_helper("facet:/a/b", "facet:\"/a/b\"");
+        test_parse_query_to_ast_helper("title:/te.t", "title:\"/te.t\"");
+    }
+
     #[test]
     fn test_parse_query_to_ast_binary_op() {
         test_parse_query_to_ast_helper("a AND b", "(+\"a\" +\"b\")");
@@ -12,6 +12,24 @@ pub enum UserInputLeaf {
         lower: UserInputBound,
         upper: UserInputBound,
     },
+    /// A pattern with the wildcards `*` and `?`, as in `comp*` or `te?t`.
+    Wildcard {
+        field_name: Option<String>,
+        pattern: String,
+    },
+    /// A term matched up to a Levenshtein distance, as in `fox~1`.
+    /// If `prefix` is true, as in `fox*~1`, the prefixes of the terms are matched.
+    Fuzzy {
+        field_name: Option<String>,
+        term: String,
+        distance: u8,
+        prefix: bool,
+    },
+    /// A regular expression, as in `/te.t/`.
+    Regex {
+        field_name: Option<String>,
+        pattern: String,
+    },
 }
 
 impl Debug for UserInputLeaf {
@@ -32,6 +50,36 @@ impl Debug for UserInputLeaf {
                 Ok(())
             }
             UserInputLeaf::All => write!(formatter, "*"),
+            UserInputLeaf::Wildcard {
+                ref field_name,
+                ref pattern,
+            } => {
+                if let Some(ref field_name) = field_name {
+                    write!(formatter, "{}:", field_name)?;
+                }
+                write!(formatter, "{}", pattern)
+            }
+            UserInputLeaf::Fuzzy {
+                ref field_name,
+                ref term,
+                distance,
+                prefix,
+            } => {
+                if let Some(ref field_name) = field_name {
+                    write!(formatter, "{}:", field_name)?;
+                }
+                let prefix_marker = if *prefix { "*" } else { "" };
+                write!(formatter, "{}{}~{}", term, prefix_marker, distance)
+            }
+            UserInputLeaf::Regex {
+                ref field_name,
+                ref pattern,
+            } => {
+                if let Some(ref field_name) = field_name {
+                    write!(formatter, "{}:", field_name)?;
+                }
+                write!(formatter, "/{}/", pattern)
+            }
         }
     }
 }
@@ -40,14 +88,19 @@ impl Debug for UserInputLeaf {
 pub struct UserInputLiteral {
     pub field_name: Option<String>,
     pub phrase: String,
+    pub slop: u32,
 }
 
 impl fmt::Debug for UserInputLiteral {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         match self.field_name {
-            Some(ref field_name) => write!(formatter, "{}:\"{}\"", field_name, self.phrase),
-            None => write!(formatter, "\"{}\"", self.phrase),
+            Some(ref field_name) => write!(formatter, "{}:\"{}\"", field_name, self.phrase)?,
+            None => write!(formatter, "\"{}\"", self.phrase)?,
+        }
+        if self.slop > 0 {
+            write!(formatter, "~{}", self.slop)?;
         }
+        Ok(())
     }
 }
 
@@ -4,7 +4,7 @@ use crate::core::IndexMeta;
 use crate::core::SegmentId;
 use crate::core::SegmentMeta;
 use crate::core::SegmentMetaInventory;
-use crate::core::META_FILEPATH;
+use crate::core::{commit_meta_filepath, META_FILEPATH};
 use crate::directory::error::OpenReadError;
 use crate::directory::ManagedDirectory;
 #[cfg(feature = "mmap")]
@@ -17,35 +17,36 @@ use crate::indexer::index_writer::{HEAP_SIZE_MIN, MAX_NUM_THREAD};
 use crate::indexer::segment_updater::save_new_metas;
 use crate::reader::IndexReader;
 use crate::reader::IndexReaderBuilder;
+use crate::schema::Cardinality;
 use crate::schema::Field;
 use crate::schema::FieldType;
 use crate::schema::Schema;
 use crate::tokenizer::{TextAnalyzer, TokenizerManager};
 use crate::IndexWriter;
+use crate::Opstamp;
 use std::collections::HashSet;
 use std::fmt;
 
-#[cfg(feature = "mmap")]
-use std::path::Path;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 fn load_metas(
     directory: &dyn Directory,
+    meta_filepath: &Path,
     inventory: &SegmentMetaInventory,
 ) -> crate::Result<IndexMeta> {
-    let meta_data = directory.atomic_read(&META_FILEPATH)?;
+    let meta_data = directory.atomic_read(meta_filepath)?;
     let meta_string = String::from_utf8(meta_data).map_err(|_utf8_err| {
         error!("Meta data is not valid utf8.");
         DataCorruption::new(
-            META_FILEPATH.to_path_buf(),
+            meta_filepath.to_path_buf(),
             "Meta file does not contain valid utf8 file.".to_string(),
         )
     })?;
     IndexMeta::deserialize(&meta_string, &inventory)
         .map_err(|e| {
             DataCorruption::new(
-                META_FILEPATH.to_path_buf(),
+                meta_filepath.to_path_buf(),
                 format!(
                     "Meta file cannot be deserialized. {:?}. Content: {:?}",
                     e, meta_string
@@ -113,9 +114,7 @@ impl IndexBuilder {
     /// This should only be used for unit tests.
     pub fn create_in_ram(self) -> Result<Index, TantivyError> {
         let ram_directory = RamDirectory::create();
-        Ok(self
-            .create(ram_directory)
-            .expect("Creating a RAMDirectory should never fail"))
+        self.create(ram_directory)
     }
     /// Creates a new index in a given filepath.
     /// The index will use the `MMapDirectory`.
@@ -166,19 +165,51 @@ impl IndexBuilder {
     ///
     /// If a directory previously existed, it will be erased.
     fn create<Dir: Directory>(self, dir: Dir) -> crate::Result<Index> {
+        validate_expiry_field(&self.get_expect_schema()?)?;
         let directory = ManagedDirectory::wrap(dir)?;
+        let mut metas = IndexMeta::with_schema(self.get_expect_schema()?);
+        metas.index_settings = self.index_settings.clone();
+        // Opening the index first validates the analyzer definitions.
+        let index = Index::open_from_metas(directory, &metas, SegmentMetaInventory::default())?;
         save_new_metas(
             self.get_expect_schema()?,
-            self.index_settings.clone(),
-            &directory,
+            self.index_settings,
+            &index.directory,
         )?;
-        let mut metas = IndexMeta::with_schema(self.get_expect_schema()?);
-        metas.index_settings = self.index_settings;
-        let index = Index::open_from_metas(directory, &metas, SegmentMetaInventory::default());
         Ok(index)
     }
 }
 
+// Checks that the fields marked as expiry field can be used as such.
+fn validate_expiry_field(schema: &Schema) -> crate::Result<()> {
+    let mut num_expiry_fields = 0;
+    for (_, field_entry) in schema.fields() {
+        let (int_options, is_date) = match field_entry.field_type() {
+            FieldType::Date(int_options) => (int_options, true),
+            FieldType::I64(int_options)
+            | FieldType::U64(int_options)
+            | FieldType::F64(int_options) => (int_options, false),
+            _ => continue,
+        };
+        if !int_options.is_expiry() {
+            continue;
+        }
+        if !is_date || int_options.get_fastfield_cardinality() != Some(Cardinality::SingleValue) {
+            return Err(TantivyError::SchemaError(format!(
+                "Expiry field {:?} must be a single-valued date fast field.",
+                field_entry.name()
+            )));
+        }
+        num_expiry_fields += 1;
+    }
+    if num_expiry_fields > 1 {
+        return Err(TantivyError::SchemaError(
+            "A schema can have at most one expiry field.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// Search Index
 #[derive(Clone)]
 pub struct Index {
@@ -188,6 +219,9 @@ pub struct Index {
     executor: Arc<Executor>,
     tokenizers: TokenizerManager,
     inventory: SegmentMetaInventory,
+    // Opstamp of the commit the index was opened at, if it is not
+    // the last commit. See `Index::open_at_commit`.
+    pinned_commit: Option<Opstamp>,
 }
 
 impl Index {
@@ -280,20 +314,34 @@ impl Index {
     }
 
     /// Creates a new index given a directory and an `IndexMeta`.
+    ///
+    /// The analyzers defined in the index settings are registered
+    /// in the `TokenizerManager` of the index.
     fn open_from_metas(
         directory: ManagedDirectory,
         metas: &IndexMeta,
         inventory: SegmentMetaInventory,
-    ) -> Index {
+    ) -> crate::Result<Index> {
         let schema = metas.schema.clone();
-        Index {
+        let tokenizers = TokenizerManager::default();
+        for (name, analyzer_definition) in &metas.index_settings.analyzers {
+            let analyzer = analyzer_definition.build().map_err(|err| {
+                TantivyError::InvalidArgument(format!(
+                    "Invalid definition of the analyzer {:?}: {}",
+                    name, err
+                ))
+            })?;
+            tokenizers.register(name, analyzer);
+        }
+        Ok(Index {
             settings: metas.index_settings.clone(),
             directory,
             schema,
-            tokenizers: TokenizerManager::default(),
+            tokenizers,
             executor: Arc::new(Executor::single_thread()),
             inventory,
-        }
+            pinned_commit: None,
+        })
     }
 
     /// Accessor for the tokenizer manager.
@@ -367,14 +415,100 @@ impl Index {
     pub fn open<D: Directory>(directory: D) -> crate::Result<Index> {
         let directory = ManagedDirectory::wrap(directory)?;
         let inventory = SegmentMetaInventory::default();
-        let metas = load_metas(&directory, &inventory)?;
-        let index = Index::open_from_metas(directory, &metas, inventory);
-        Ok(index)
+        let metas = load_metas(&directory, &META_FILEPATH, &inventory)?;
+        Index::open_from_metas(directory, &metas, inventory)
     }
 
     /// Reads the index meta file from the directory.
+    ///
+    /// If the index was opened at a specific commit, the meta
+    /// file of this commit is read instead of `meta.json`.
     pub fn load_metas(&self) -> crate::Result<IndexMeta> {
-        load_metas(self.directory(), &self.inventory)
+        match self.pinned_commit {
+            Some(opstamp) => load_metas(
+                self.directory(),
+                &commit_meta_filepath(opstamp),
+                &self.inventory,
+            ),
+            None => load_metas(self.directory(), &META_FILEPATH, &self.inventory),
+        }
+    }
+
+    /// Loads the metas of the commits listed in `index_meta.commits`.
+    ///
+    /// Commits whose meta file does not exist anymore are skipped.
+    pub(crate) fn load_commit_metas(
+        &self,
+        index_meta: &IndexMeta,
+    ) -> crate::Result<Vec<IndexMeta>> {
+        let mut commit_metas = Vec::with_capacity(index_meta.commits.len());
+        for &opstamp in &index_meta.commits {
+            if opstamp == index_meta.opstamp {
+                commit_metas.push(index_meta.clone());
+                continue;
+            }
+            let meta_filepath = commit_meta_filepath(opstamp);
+            match load_metas(self.directory(), &meta_filepath, &self.inventory) {
+                Ok(commit_meta) => commit_metas.push(commit_meta),
+                Err(TantivyError::OpenReadError(OpenReadError::FileDoesNotExist(_))) => {
+                    warn!("Meta file of commit {} is missing.", opstamp);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(commit_metas)
+    }
+
+    /// Returns the metas of the commits retained by the index, sorted from
+    /// the oldest to the most recent one.
+    ///
+    /// Which commits are retained is defined by the
+    /// [`DeletionPolicy`](./deletion_policy/trait.DeletionPolicy.html)
+    /// of the `IndexWriter`. By default, only the last commit is retained.
+    pub fn list_commits(&self) -> crate::Result<Vec<IndexMeta>> {
+        let last_commit_meta = load_metas(self.directory(), &META_FILEPATH, &self.inventory)?;
+        if last_commit_meta.commits.is_empty() {
+            // The index was created before commits were retained.
+            return Ok(vec![last_commit_meta]);
+        }
+        self.load_commit_metas(&last_commit_meta)
+    }
+
+    /// Returns a view of the index at the commit with the given opstamp.
+    ///
+    /// Readers created from the returned `Index` only see the segments of
+    /// this commit, and are not affected by later commits.
+    ///
+    /// An `IndexWriter` created from the returned `Index` starts from this
+    /// commit. Committing from it rolls the index back to this commit,
+    /// and discards the commits that happened after it.
+    ///
+    /// # Errors
+    /// Returns `TantivyError::InvalidArgument` if the commit is not
+    /// retained by the index. (See [`Index::list_commits`](#method.list_commits))
+    pub fn open_at_commit(&self, opstamp: Opstamp) -> crate::Result<Index> {
+        let last_commit_meta = load_metas(self.directory(), &META_FILEPATH, &self.inventory)?;
+        if last_commit_meta.commits.contains(&opstamp) {
+            let mut index = self.clone();
+            index.pinned_commit = Some(opstamp);
+            return Ok(index);
+        }
+        if last_commit_meta.commits.is_empty() && last_commit_meta.opstamp == opstamp {
+            // The index was created before commits were retained, only
+            // `meta.json` is available.
+            return Ok(self.unpinned());
+        }
+        Err(TantivyError::InvalidArgument(format!(
+            "Commit {} is not retained by the index.",
+            opstamp
+        )))
+    }
+
+    /// Returns a view of the index that follows its last commit.
+    pub(crate) fn unpinned(&self) -> Index {
+        let mut index = self.clone();
+        index.pinned_commit = None;
+        index
     }
 
     /// Open a new index writer. Attempts to acquire a lockfile.
@@ -535,15 +669,21 @@ impl fmt::Debug for Index {
 
 #[cfg(test)]
 mod tests {
+    use crate::collector::Count;
+    use crate::deletion_policy::KeepLastNCommits;
+    use crate::query::QueryParser;
     use crate::schema::Field;
-    use crate::schema::{Schema, INDEXED, TEXT};
+    use crate::schema::{Schema, TextFieldIndexing, TextOptions, INDEXED, TEXT};
+    use crate::tokenizer::{
+        AnalyzerDefinition, Language, TokenFilterDefinition, TokenizerDefinition,
+    };
     use crate::IndexReader;
     use crate::ReloadPolicy;
     use crate::{
         directory::{RamDirectory, WatchCallback},
         IndexSettings,
     };
-    use crate::{Directory, Index};
+    use crate::{Directory, Index, Opstamp};
 
     #[test]
     fn test_indexer_for_field() {
@@ -629,12 +769,124 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_index_settings_analyzers() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default()
+            .set_indexing_options(TextFieldIndexing::default().set_tokenizer("custom"));
+        let text_field = schema_builder.add_text_field("text", text_options);
+        let schema = schema_builder.build();
+        let mut settings = IndexSettings::default();
+        settings.analyzers.insert(
+            "custom".to_string(),
+            AnalyzerDefinition::new(TokenizerDefinition::Simple)
+                .filter(TokenFilterDefinition::Lowercase)
+                .filter(TokenFilterDefinition::Stemmer {
+                    language: Language::English,
+                }),
+        );
+        let directory = RamDirectory::create();
+        let index = Index::create(directory.clone(), schema, settings.clone())?;
+        let mut writer = index.writer_for_tests()?;
+        writer.add_document(doc!(text_field=>"Running dogs"));
+        writer.commit()?;
+
+        // The analyzer is instantiated again when the index is reopened.
+        let index = Index::open(directory)?;
+        assert!(index.settings() == &settings);
+        let analyzer = index.tokenizer_for_field(text_field)?;
+        assert_eq!(analyzer.token_stream("Running").next().unwrap().text, "run");
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![text_field]);
+        let query = query_parser.parse_query("runs")?;
+        assert_eq!(searcher.search(&query, &Count)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_settings_invalid_analyzer() {
+        let mut settings = IndexSettings::default();
+        settings.analyzers.insert(
+            "custom".to_string(),
+            AnalyzerDefinition::new(TokenizerDefinition::Regex {
+                pattern: "(".to_string(),
+                split: false,
+            }),
+        );
+        let directory = RamDirectory::create();
+        let err = Index::create(directory.clone(), throw_away_schema(), settings).unwrap_err();
+        assert!(matches!(err, crate::TantivyError::InvalidArgument(_)));
+        assert!(!Index::exists(&directory).unwrap());
+    }
+
     fn throw_away_schema() -> Schema {
         let mut schema_builder = Schema::builder();
         let _ = schema_builder.add_u64_field("num_likes", INDEXED);
         schema_builder.build()
     }
 
+    #[test]
+    fn test_list_commits_and_open_at_commit() -> crate::Result<()> {
+        let schema = throw_away_schema();
+        let field = schema.get_field("num_likes").unwrap();
+        let index = Index::create_in_ram(schema);
+        let mut writer = index.writer_for_tests()?;
+        writer.set_deletion_policy(Box::new(KeepLastNCommits::new(2)));
+        let mut opstamps = Vec::new();
+        for i in 0u64..3u64 {
+            writer.add_document(doc!(field=>i));
+            opstamps.push(writer.commit()?);
+        }
+        let commit_opstamps: Vec<Opstamp> = index
+            .list_commits()?
+            .iter()
+            .map(|commit_meta| commit_meta.opstamp)
+            .collect();
+        assert_eq!(&commit_opstamps[..], &opstamps[1..]);
+        assert!(index.open_at_commit(opstamps[0]).is_err());
+        let previous_index = index.open_at_commit(opstamps[1])?;
+        assert_eq!(previous_index.load_metas()?.opstamp, opstamps[1]);
+        let previous_reader = previous_index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        writer.wait_merging_threads()?;
+        previous_reader.reload()?;
+        assert_eq!(previous_reader.searcher().num_docs(), 2);
+        assert_eq!(index.reader()?.searcher().num_docs(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rollback_to_earlier_commit() -> crate::Result<()> {
+        let schema = throw_away_schema();
+        let field = schema.get_field("num_likes").unwrap();
+        let index = Index::create_in_ram(schema);
+        let first_opstamp = {
+            let mut writer = index.writer_for_tests()?;
+            writer.set_deletion_policy(Box::new(KeepLastNCommits::new(3)));
+            writer.add_document(doc!(field=>1u64));
+            let first_opstamp = writer.commit()?;
+            writer.add_document(doc!(field=>2u64));
+            writer.add_document(doc!(field=>3u64));
+            writer.commit()?;
+            first_opstamp
+        };
+        assert_eq!(index.reader()?.searcher().num_docs(), 3);
+        let mut writer = index.open_at_commit(first_opstamp)?.writer_for_tests()?;
+        writer.set_deletion_policy(Box::new(KeepLastNCommits::new(3)));
+        writer.add_document(doc!(field=>4u64));
+        let rollback_opstamp = writer.commit()?;
+        assert_eq!(index.reader()?.searcher().num_docs(), 2);
+        let commit_opstamps: Vec<Opstamp> = index
+            .list_commits()?
+            .iter()
+            .map(|commit_meta| commit_meta.opstamp)
+            .collect();
+        assert_eq!(commit_opstamps, vec![first_opstamp, rollback_opstamp]);
+        Ok(())
+    }
+
     #[test]
     fn test_index_on_commit_reload_policy() {
         let schema = throw_away_schema();
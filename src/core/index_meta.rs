@@ -1,9 +1,11 @@
 use super::SegmentComponent;
 use crate::schema::Schema;
+use crate::tokenizer::AnalyzerDefinition;
 use crate::Opstamp;
 use crate::{core::SegmentId, store::Compressor};
 use census::{Inventory, TrackedObject};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 use std::{collections::HashSet, sync::atomic::AtomicBool};
 use std::{fmt, sync::Arc};
@@ -35,6 +37,7 @@ impl SegmentMetaInventory {
             max_doc,
             include_temp_doc_store: Arc::new(AtomicBool::new(true)),
             deletes: None,
+            field_tokenizers: BTreeMap::new(),
         };
         SegmentMeta::from(self.inventory.track(inner))
     }
@@ -164,6 +167,15 @@ impl SegmentMeta {
             .map(|delete_meta| delete_meta.opstamp)
     }
 
+    /// Returns the names of the tokenizers used to index the documents of the segment,
+    /// by field name.
+    ///
+    /// Only the text fields selecting their tokenizer per document
+    /// (see `TextFieldIndexing::set_tokenizer_field`) are recorded.
+    pub fn field_tokenizers(&self) -> &BTreeMap<String, BTreeSet<String>> {
+        &self.tracked.field_tokenizers
+    }
+
     /// Returns true iff the segment meta contains
     /// delete information.
     pub fn has_deletes(&self) -> bool {
@@ -182,6 +194,22 @@ impl SegmentMeta {
             max_doc,
             deletes: None,
             include_temp_doc_store: Arc::new(AtomicBool::new(true)),
+            field_tokenizers: inner_meta.field_tokenizers.clone(),
+        });
+        SegmentMeta { tracked }
+    }
+
+    /// Records the tokenizers used to index the documents of the segment.
+    pub(crate) fn with_field_tokenizers(
+        self,
+        field_tokenizers: BTreeMap<String, BTreeSet<String>>,
+    ) -> SegmentMeta {
+        let tracked = self.tracked.map(move |inner_meta| InnerSegmentMeta {
+            segment_id: inner_meta.segment_id,
+            max_doc: inner_meta.max_doc,
+            deletes: inner_meta.deletes.clone(),
+            include_temp_doc_store: Arc::new(AtomicBool::new(true)),
+            field_tokenizers,
         });
         SegmentMeta { tracked }
     }
@@ -197,6 +225,7 @@ impl SegmentMeta {
             max_doc: inner_meta.max_doc,
             include_temp_doc_store: Arc::new(AtomicBool::new(true)),
             deletes: Some(delete_meta),
+            field_tokenizers: inner_meta.field_tokenizers.clone(),
         });
         SegmentMeta { tracked }
     }
@@ -212,11 +241,31 @@ struct InnerSegmentMeta {
     #[serde(skip)]
     #[serde(default = "default_temp_store")]
     pub(crate) include_temp_doc_store: Arc<AtomicBool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    field_tokenizers: BTreeMap<String, BTreeSet<String>>,
 }
 fn default_temp_store() -> Arc<AtomicBool> {
     Arc::new(AtomicBool::new(false))
 }
 
+/// Returns the union of the tokenizers recorded in the given segment metas,
+/// for the segment resulting from their merge.
+pub(crate) fn merge_field_tokenizers<'a>(
+    segment_metas: impl IntoIterator<Item = &'a SegmentMeta>,
+) -> BTreeMap<String, BTreeSet<String>> {
+    let mut field_tokenizers: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for segment_meta in segment_metas {
+        for (field_name, tokenizers) in segment_meta.field_tokenizers() {
+            field_tokenizers
+                .entry(field_name.clone())
+                .or_default()
+                .extend(tokenizers.iter().cloned());
+        }
+    }
+    field_tokenizers
+}
+
 impl InnerSegmentMeta {
     pub fn track(self, inventory: &SegmentMetaInventory) -> SegmentMeta {
         SegmentMeta {
@@ -238,6 +287,13 @@ pub struct IndexSettings {
     /// The `Compressor` used to compress the doc store.
     #[serde(default)]
     pub docstore_compression: Compressor,
+    /// Analyzers registered in the `TokenizerManager` of the index, by name.
+    ///
+    /// They are persisted in the `meta.json` file, and instantiated
+    /// automatically when the index is opened.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub analyzers: BTreeMap<String, AnalyzerDefinition>,
 }
 /// Settings to presort the documents in an index
 ///
@@ -296,6 +352,13 @@ pub struct IndexMeta {
     /// to help identify this commit.
     /// This payload is entirely unused by tantivy.
     pub payload: Option<String>,
+    /// Opstamps of the commits retained by the `DeletionPolicy`,
+    /// sorted from the oldest to the most recent one.
+    ///
+    /// Each of these commits can be opened with `Index::open_at_commit`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub commits: Vec<Opstamp>,
 }
 
 #[derive(Deserialize)]
@@ -307,6 +370,8 @@ struct UntrackedIndexMeta {
     pub opstamp: Opstamp,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<String>,
+    #[serde(default)]
+    pub commits: Vec<Opstamp>,
 }
 
 impl UntrackedIndexMeta {
@@ -321,6 +386,7 @@ impl UntrackedIndexMeta {
             schema: self.schema,
             opstamp: self.opstamp,
             payload: self.payload,
+            commits: self.commits,
         }
     }
 }
@@ -338,6 +404,7 @@ impl IndexMeta {
             schema,
             opstamp: 0u64,
             payload: None,
+            commits: Vec::new(),
         }
     }
 
@@ -390,6 +457,7 @@ mod tests {
             schema,
             opstamp: 0u64,
             payload: None,
+            commits: Vec::new(),
         };
         let json = serde_json::ser::to_string(&index_metas).expect("serialization failed");
         assert_eq!(
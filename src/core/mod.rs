@@ -10,6 +10,7 @@ mod segment_reader;
 
 pub use self::executor::Executor;
 pub use self::index::{Index, IndexBuilder};
+pub(crate) use self::index_meta::merge_field_tokenizers;
 pub use self::index_meta::{
     IndexMeta, IndexSettings, IndexSortByField, Order, SegmentMeta, SegmentMetaInventory,
 };
@@ -21,13 +22,22 @@ pub use self::segment_component::SegmentComponent;
 pub use self::segment_id::SegmentId;
 pub use self::segment_reader::SegmentReader;
 
+use crate::Opstamp;
 use once_cell::sync::Lazy;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// The meta file contains all the information about the list of segments and the schema
 /// of the index.
 pub static META_FILEPATH: Lazy<&'static Path> = Lazy::new(|| Path::new("meta.json"));
 
+/// Returns the path of the meta file associated to the commit with the given opstamp.
+///
+/// Unlike `meta.json`, which is overwritten on every commit, this file is kept for as
+/// long as the commit is retained by the `DeletionPolicy`.
+pub(crate) fn commit_meta_filepath(opstamp: Opstamp) -> PathBuf {
+    PathBuf::from(format!("meta.{}.json", opstamp))
+}
+
 /// The managed file contains a list of files that were created by the tantivy
 /// and will therefore be garbage collected when they are deemed useless by tantivy.
 ///
@@ -8,6 +8,7 @@ use crate::indexer::segment_serializer::SegmentSerializer;
 use crate::schema::Schema;
 use crate::Opstamp;
 use crate::{core::Index, indexer::doc_id_mapping::DocIdMapping};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::path::PathBuf;
 
@@ -56,6 +57,17 @@ impl Segment {
         }
     }
 
+    /// Records the tokenizers used to index the documents of the segment.
+    pub(crate) fn with_field_tokenizers(
+        self,
+        field_tokenizers: BTreeMap<String, BTreeSet<String>>,
+    ) -> Segment {
+        Segment {
+            index: self.index,
+            meta: self.meta.with_field_tokenizers(field_tokenizers),
+        }
+    }
+
     #[doc(hidden)]
     pub fn with_delete_meta(self, num_deleted_docs: u32, opstamp: Opstamp) -> Segment {
         Segment {
@@ -1,3 +1,4 @@
+use crate::common::BitSet;
 use crate::common::HasLen;
 use crate::core::InvertedIndexReader;
 use crate::core::Segment;
@@ -6,6 +7,7 @@ use crate::core::SegmentId;
 use crate::directory::FileSlice;
 use crate::fastfield::DeleteBitSet;
 use crate::fastfield::FacetReader;
+use crate::fastfield::FastFieldReader;
 use crate::fastfield::FastFieldReaders;
 use crate::fieldnorm::{FieldNormReader, FieldNormReaders};
 use crate::schema::FieldType;
@@ -15,8 +17,11 @@ use crate::space_usage::SegmentSpaceUsage;
 use crate::store::StoreReader;
 use crate::termdict::TermDictionary;
 use crate::DocId;
+use crate::Opstamp;
 use crate::{common::CompositeFile, error::DataCorruption};
+use chrono::Utc;
 use fail::fail_point;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::sync::Arc;
 use std::sync::RwLock;
@@ -52,6 +57,9 @@ pub struct SegmentReader {
     store_file: FileSlice,
     delete_bitset_opt: Option<DeleteBitSet>,
     schema: Schema,
+    field_tokenizers: Arc<BTreeMap<String, BTreeSet<String>>>,
+    delete_opstamp: Option<Opstamp>,
+    next_expiry_timestamp: Option<i64>,
 }
 
 impl SegmentReader {
@@ -149,7 +157,23 @@ impl SegmentReader {
     }
 
     /// Open a new segment for reading.
+    ///
+    /// If the schema has an expiry field, the documents that have expired
+    /// are marked as deleted.
     pub fn open(segment: &Segment) -> crate::Result<SegmentReader> {
+        let segment_reader = SegmentReader::open_without_expiry(segment)?;
+        if let Some(expiry_field) = segment_reader.schema.expiry_field() {
+            return segment_reader.expire_docs(expiry_field, Utc::now().timestamp());
+        }
+        Ok(segment_reader)
+    }
+
+    /// Opens a segment for reading, with the documents deleted in the segment
+    /// as their only deletes.
+    ///
+    /// Used to update the delete file of the segment, which must not
+    /// include the documents that have merely expired.
+    pub(crate) fn open_without_expiry(segment: &Segment) -> crate::Result<SegmentReader> {
         let termdict_file = segment.open_read(SegmentComponent::Terms)?;
         let termdict_composite = CompositeFile::open(&termdict_file)?;
 
@@ -186,7 +210,7 @@ impl SegmentReader {
             None
         };
 
-        Ok(SegmentReader {
+        let segment_reader = SegmentReader {
             inv_idx_reader_cache: Default::default(),
             max_doc: segment.meta().max_doc(),
             num_docs: segment.meta().num_docs(),
@@ -199,9 +223,67 @@ impl SegmentReader {
             delete_bitset_opt,
             positions_composite,
             schema,
+            field_tokenizers: Arc::new(segment.meta().field_tokenizers().clone()),
+            delete_opstamp: segment.meta().delete_opstamp(),
+            next_expiry_timestamp: None,
+        };
+        Ok(segment_reader)
+    }
+
+    /// Returns a `SegmentReader` in which the documents whose expiry date
+    /// is not posterior to `timestamp` are marked as deleted.
+    ///
+    /// This view is only used for searching and merging: the expired documents
+    /// are not written to the delete file, and are removed for good when
+    /// the segment gets merged.
+    fn expire_docs(self, expiry_field: Field, timestamp: i64) -> crate::Result<SegmentReader> {
+        let expiry_reader = self
+            .fast_fields()
+            .typed_fast_field_reader::<i64>(expiry_field)?;
+        if expiry_reader.min_value() > timestamp {
+            return Ok(SegmentReader {
+                next_expiry_timestamp: Some(expiry_reader.min_value()),
+                ..self
+            });
+        }
+        let mut delete_bitset = BitSet::with_max_value(self.max_doc);
+        let mut next_expiry_timestamp: Option<i64> = None;
+        for doc in 0..self.max_doc {
+            if self.is_deleted(doc) {
+                delete_bitset.insert(doc);
+                continue;
+            }
+            let expiry_timestamp = expiry_reader.get(doc);
+            if expiry_timestamp <= timestamp {
+                delete_bitset.insert(doc);
+            } else {
+                next_expiry_timestamp = Some(
+                    next_expiry_timestamp
+                        .map_or(expiry_timestamp, |next| next.min(expiry_timestamp)),
+                );
+            }
+        }
+        let delete_bitset = DeleteBitSet::from_bitset(&delete_bitset, self.max_doc);
+        Ok(SegmentReader {
+            next_expiry_timestamp,
+            ..self.with_delete_bitset(Some(delete_bitset))
         })
     }
 
+    /// Returns true iff this reader can be used in place of a reader
+    /// opened for `segment` at `timestamp`.
+    ///
+    /// This is the case if the segment and its deletes are the same, and
+    /// no document has expired since the reader was opened.
+    pub(crate) fn is_up_to_date(&self, segment: &Segment, timestamp: i64) -> bool {
+        self.segment_id == segment.id()
+            && self.delete_opstamp == segment.meta().delete_opstamp()
+            && self
+                .next_expiry_timestamp
+                .map(|next_expiry_timestamp| timestamp < next_expiry_timestamp)
+                .unwrap_or(true)
+    }
+
     /// Returns a field reader associated to the field given in argument.
     /// If the field was not present in the index during indexing time,
     /// the InvertedIndexReader is empty.
@@ -280,12 +362,40 @@ impl SegmentReader {
         self.segment_id
     }
 
+    /// Returns the tokenizers used to index the documents of the segment,
+    /// for each text field selecting its tokenizer per document.
+    ///
+    /// See `SegmentMeta::field_tokenizers`.
+    pub fn field_tokenizers(&self) -> &BTreeMap<String, BTreeSet<String>> {
+        &self.field_tokenizers
+    }
+
     /// Returns the bitset representing
     /// the documents that have been deleted.
     pub fn delete_bitset(&self) -> Option<&DeleteBitSet> {
         self.delete_bitset_opt.as_ref()
     }
 
+    /// Returns a copy of this reader, using the given delete bitset
+    /// instead of the one stored in the segment.
+    ///
+    /// The bitset needs to include the documents deleted in the segment
+    /// for these to remain hidden.
+    pub(crate) fn with_delete_bitset(
+        &self,
+        delete_bitset_opt: Option<DeleteBitSet>,
+    ) -> SegmentReader {
+        let num_deleted_docs = delete_bitset_opt
+            .as_ref()
+            .map(|delete_bitset| delete_bitset.len() as DocId)
+            .unwrap_or(0u32);
+        SegmentReader {
+            num_docs: self.max_doc - num_deleted_docs,
+            delete_bitset_opt,
+            ..self.clone()
+        }
+    }
+
     /// Returns true iff the `doc` is marked
     /// as deleted.
     pub fn is_deleted(&self, doc: DocId) -> bool {
@@ -325,9 +435,11 @@ impl fmt::Debug for SegmentReader {
 
 #[cfg(test)]
 mod test {
+    use super::SegmentReader;
     use crate::core::Index;
-    use crate::schema::{Schema, Term, STORED, TEXT};
+    use crate::schema::{IntOptions, Schema, Term, FAST, STORED, TEXT};
     use crate::DocId;
+    use chrono::{Duration, Utc};
 
     #[test]
     fn test_alive_docs_iterator() -> crate::Result<()> {
@@ -360,4 +472,33 @@ mod test {
         assert_eq!(vec![0u32, 2u32], docs);
         Ok(())
     }
+
+    #[test]
+    fn test_expiry_view() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let expiry_field =
+            schema_builder.add_date_field("expiry", FAST | IntOptions::default().set_expiry());
+        let index = Index::create_in_ram(schema_builder.build());
+        let now = Utc::now();
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(expiry_field=>now - Duration::days(1)));
+        index_writer.add_document(doc!(expiry_field=>now + Duration::days(1)));
+        index_writer.add_document(doc!(expiry_field=>now + Duration::days(2)));
+        index_writer.commit()?;
+        let segment = index.searchable_segments()?.pop().unwrap();
+
+        let segment_reader = SegmentReader::open(&segment)?;
+        assert_eq!(
+            segment_reader.doc_ids_alive().collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        let raw_segment_reader = SegmentReader::open_without_expiry(&segment)?;
+        assert_eq!(raw_segment_reader.num_docs(), 3);
+
+        // The reader remains up to date until the next document expires.
+        let tomorrow = (now + Duration::days(1)).timestamp();
+        assert!(segment_reader.is_up_to_date(&segment, tomorrow - 1));
+        assert!(!segment_reader.is_up_to_date(&segment, tomorrow));
+        Ok(())
+    }
 }
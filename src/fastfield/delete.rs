@@ -63,6 +63,28 @@ impl DeleteBitSet {
         Self::open(file).unwrap()
     }
 
+    /// Builds an in-memory delete bitset from the set of deleted `DocId`s.
+    ///
+    /// This is used to compute a delete view of a segment that is never
+    /// persisted.
+    pub(crate) fn from_bitset(delete_bitset: &BitSet, max_doc: u32) -> DeleteBitSet {
+        let mut data: Vec<u8> = Vec::with_capacity((max_doc / 8u32) as usize + 1);
+        for doc in 0..max_doc {
+            let shift = (doc & 7u32) as u8;
+            if shift == 0 {
+                data.push(0u8);
+            }
+            if delete_bitset.contains(doc) {
+                *data.last_mut().unwrap() |= 1u8 << shift;
+            }
+        }
+        let num_deleted = data.iter().map(|b| b.count_ones() as usize).sum();
+        DeleteBitSet {
+            data: OwnedBytes::new(data),
+            num_deleted,
+        }
+    }
+
     /// Opens a delete bitset given its file.
     pub fn open(file: FileSlice) -> crate::Result<DeleteBitSet> {
         let bytes = file.read_bytes()?;
@@ -106,7 +128,19 @@ impl HasLen for DeleteBitSet {
 #[cfg(test)]
 mod tests {
     use super::DeleteBitSet;
-    use crate::common::HasLen;
+    use crate::common::{BitSet, HasLen};
+
+    #[test]
+    fn test_delete_bitset_from_bitset() {
+        let mut bitset = BitSet::with_max_value(10);
+        bitset.insert(1);
+        bitset.insert(9);
+        let delete_bitset = DeleteBitSet::from_bitset(&bitset, 10);
+        for doc in 0..10 {
+            assert_eq!(delete_bitset.is_deleted(doc), doc == 1 || doc == 9);
+        }
+        assert_eq!(delete_bitset.len(), 2);
+    }
 
     #[test]
     fn test_delete_bitset_empty() {
@@ -18,8 +18,15 @@ pub struct FastFieldsWriter {
     bytes_value_writers: Vec<BytesFastFieldWriter>,
 }
 
+// Timestamp of the latest date that can be represented by a `DateTime`.
+const NO_EXPIRY_TIMESTAMP: i64 = 8_210_266_876_799;
+
 fn fast_field_default_value(field_entry: &FieldEntry) -> u64 {
     match *field_entry.field_type() {
+        // Documents without an expiry date never expire.
+        FieldType::Date(ref int_options) if int_options.is_expiry() => {
+            common::i64_to_u64(NO_EXPIRY_TIMESTAMP)
+        }
         FieldType::I64(_) | FieldType::Date(_) => common::i64_to_u64(0i64),
         FieldType::F64(_) => common::f64_to_u64(0.0f64),
         _ => 0u64,
@@ -0,0 +1,109 @@
+use crate::core::IndexMeta;
+use crate::Opstamp;
+use std::fmt::Debug;
+use std::marker;
+
+/// The `DeletionPolicy` defines which commit points should be retained.
+///
+/// Every commit is saved in its own meta file, in addition to `meta.json`.
+/// After each commit, the segment updater asks the deletion policy which
+/// of the commit points should be kept. The files of the other commits
+/// (meta file, segments and delete files that are not used anymore)
+/// are then removed by the garbage collection.
+///
+/// Retained commits can be listed with
+/// [`Index::list_commits()`](../struct.Index.html#method.list_commits)
+/// and opened with
+/// [`Index::open_at_commit()`](../struct.Index.html#method.open_at_commit).
+pub trait DeletionPolicy: marker::Send + marker::Sync + Debug {
+    /// Given the list of commits, sorted from the oldest to the most recent one,
+    /// returns the opstamps of the commits that should be retained.
+    ///
+    /// The most recent commit is always retained, regardless of the returned value.
+    fn retained_commits(&self, commits: &[IndexMeta]) -> Vec<Opstamp>;
+}
+
+/// Only retains the last commit.
+///
+/// This is the default deletion policy.
+#[derive(Debug, Clone, Default)]
+pub struct KeepOnlyLastCommit;
+
+impl DeletionPolicy for KeepOnlyLastCommit {
+    fn retained_commits(&self, commits: &[IndexMeta]) -> Vec<Opstamp> {
+        commits
+            .last()
+            .map(|index_meta| vec![index_meta.opstamp])
+            .unwrap_or_default()
+    }
+}
+
+/// Retains the last `num_commits` commits.
+#[derive(Debug, Clone)]
+pub struct KeepLastNCommits {
+    num_commits: usize,
+}
+
+impl KeepLastNCommits {
+    /// Creates a new `KeepLastNCommits` retaining at most `num_commits`
+    /// commit points.
+    ///
+    /// # Panics
+    /// If `num_commits` is 0.
+    pub fn new(num_commits: usize) -> KeepLastNCommits {
+        assert!(num_commits > 0, "At least one commit must be retained.");
+        KeepLastNCommits { num_commits }
+    }
+}
+
+impl DeletionPolicy for KeepLastNCommits {
+    fn retained_commits(&self, commits: &[IndexMeta]) -> Vec<Opstamp> {
+        let num_skipped = commits.len().saturating_sub(self.num_commits);
+        commits[num_skipped..]
+            .iter()
+            .map(|index_meta| index_meta.opstamp)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+
+    fn commits(opstamps: &[Opstamp]) -> Vec<IndexMeta> {
+        opstamps
+            .iter()
+            .map(|&opstamp| {
+                let mut index_meta = IndexMeta::with_schema(Schema::builder().build());
+                index_meta.opstamp = opstamp;
+                index_meta
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_keep_only_last_commit() {
+        assert!(KeepOnlyLastCommit.retained_commits(&[]).is_empty());
+        assert_eq!(
+            KeepOnlyLastCommit.retained_commits(&commits(&[1, 3, 7])),
+            vec![7]
+        );
+    }
+
+    #[test]
+    fn test_keep_last_n_commits() {
+        let deletion_policy = KeepLastNCommits::new(2);
+        assert_eq!(deletion_policy.retained_commits(&commits(&[1])), vec![1]);
+        assert_eq!(
+            deletion_policy.retained_commits(&commits(&[1, 3, 7])),
+            vec![3, 7]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_keep_last_n_commits_zero() {
+        KeepLastNCommits::new(0);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::schema::Document;
+use std::fmt::Debug;
+use std::marker;
+use std::mem;
+use std::sync::Arc;
+
+/// A `DocumentProcessor` transforms documents right before they get indexed.
+///
+/// Processors are registered on the `IndexWriter` via
+/// [`IndexWriter::add_document_processor()`](./struct.IndexWriter.html#method.add_document_processor),
+/// and run in the indexing worker threads. This makes them a good fit for
+/// CPU-heavy enrichment or normalization, that would otherwise
+/// be done on the thread feeding the `IndexWriter`.
+///
+/// A processor may modify the document, drop it, or split it into
+/// several documents. All of the resulting documents share the opstamp
+/// of the original document.
+pub trait DocumentProcessor: marker::Send + marker::Sync + Debug {
+    /// Processes a document, and pushes the documents that should
+    /// be indexed in its place to `output`.
+    ///
+    /// Pushing no document drops the original document.
+    fn process(&self, document: Document, output: &mut Vec<Document>);
+}
+
+// Runs the processors in sequence over `document`.
+//
+// The resulting documents are appended to `output`.
+pub(crate) fn process_document(
+    processors: &[Arc<dyn DocumentProcessor>],
+    document: Document,
+    output: &mut Vec<Document>,
+) {
+    let (last_processor, processors) = match processors.split_last() {
+        Some(split) => split,
+        None => {
+            output.push(document);
+            return;
+        }
+    };
+    let mut documents = vec![document];
+    let mut processed_documents = Vec::new();
+    for processor in processors {
+        for document in documents.drain(..) {
+            processor.process(document, &mut processed_documents);
+        }
+        mem::swap(&mut documents, &mut processed_documents);
+    }
+    for document in documents {
+        last_processor.process(document, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{Schema, TEXT};
+
+    #[derive(Debug)]
+    struct SplitWords;
+
+    impl DocumentProcessor for SplitWords {
+        fn process(&self, document: Document, output: &mut Vec<Document>) {
+            for field_value in document.field_values() {
+                if let Some(text) = field_value.value().text() {
+                    for word in text.split_whitespace() {
+                        let mut doc = Document::default();
+                        doc.add_text(field_value.field(), word);
+                        output.push(doc);
+                    }
+                }
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    struct DropShortWords;
+
+    impl DocumentProcessor for DropShortWords {
+        fn process(&self, document: Document, output: &mut Vec<Document>) {
+            let is_short = document
+                .field_values()
+                .iter()
+                .filter_map(|field_value| field_value.value().text())
+                .all(|text| text.len() < 3);
+            if !is_short {
+                output.push(document);
+            }
+        }
+    }
+
+    #[test]
+    fn test_process_document() {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let doc = doc!(text=>"a quick fox");
+
+        let mut output = Vec::new();
+        process_document(&[], doc.clone(), &mut output);
+        assert_eq!(output, vec![doc.clone()]);
+
+        let processors: Vec<Arc<dyn DocumentProcessor>> =
+            vec![Arc::new(SplitWords), Arc::new(DropShortWords)];
+        let mut output = Vec::new();
+        process_document(&processors, doc, &mut output);
+        assert_eq!(output, vec![doc!(text=>"quick"), doc!(text=>"fox")]);
+    }
+}
@@ -0,0 +1,363 @@
+use crate::common::BitSet;
+use crate::core::merge_field_tokenizers;
+use crate::core::Index;
+use crate::core::IndexMeta;
+use crate::core::SegmentId;
+use crate::core::SegmentReader;
+use crate::core::SerializableSegment;
+use crate::directory::Directory;
+use crate::fastfield::{DeleteBitSet, DynamicFastFieldReader, FastFieldReader};
+use crate::indexer::merger::IndexMerger;
+use crate::indexer::segment_updater::save_metas;
+use crate::indexer::SegmentSerializer;
+use crate::schema::{Cardinality, Field, FieldType, Term, Value};
+use crate::store::StoreReader;
+use crate::DocId;
+use murmurhash32::murmurhash2;
+
+/// Advanced: Splits the documents of an index into several new indices.
+///
+/// `assign_shard` is called for every alive document of `index`, and
+/// returns the ordinal of the shard the document should be moved to.
+/// The new indices are created in `output_directories`, one per shard, and
+/// returned in the same order. Each of them contains at most one segment.
+///
+/// Documents are not re-analyzed. Their postings, fast fields, field norms and
+/// stored fields are rewritten by the `IndexMerger`, which gets to see only
+/// the documents of the shard.
+///
+/// Returns `TantivyError::InvalidArgument` if `output_directories` is empty
+/// or if `assign_shard` returns an ordinal greater or equal than the
+/// number of shards.
+///
+/// # Warning
+/// This function does NOT check or take the `IndexWriter` is running. It is not
+/// meant to work if you have an IndexWriter running for the origin index, or
+/// the destination indices.
+pub fn split_index<Dir, F>(
+    index: &Index,
+    output_directories: Vec<Dir>,
+    mut assign_shard: F,
+) -> crate::Result<Vec<Index>>
+where
+    Dir: Directory,
+    F: FnMut(&SegmentReader, DocId) -> crate::Result<usize>,
+{
+    let num_shards = output_directories.len();
+    if num_shards == 0 {
+        return Err(crate::TantivyError::InvalidArgument(
+            "No output directories given to split the index into".to_string(),
+        ));
+    }
+    let readers: Vec<SegmentReader> = index
+        .searchable_segments()?
+        .iter()
+        .map(SegmentReader::open)
+        .collect::<crate::Result<_>>()?;
+
+    // For each shard, the delete bitsets hiding the documents of the
+    // segments that do not belong to the shard.
+    let mut shard_delete_bitsets: Vec<Vec<BitSet>> = (0..num_shards)
+        .map(|_| {
+            readers
+                .iter()
+                .map(|reader| BitSet::with_max_value(reader.max_doc()))
+                .collect()
+        })
+        .collect();
+    for (segment_ord, reader) in readers.iter().enumerate() {
+        for doc in 0..reader.max_doc() {
+            let doc_shard_opt = if reader.is_deleted(doc) {
+                None
+            } else {
+                let shard = assign_shard(reader, doc)?;
+                if shard >= num_shards {
+                    return Err(crate::TantivyError::InvalidArgument(format!(
+                        "Shard {} is out of range, the index is split into {} shards",
+                        shard, num_shards
+                    )));
+                }
+                Some(shard)
+            };
+            for (shard, delete_bitsets) in shard_delete_bitsets.iter_mut().enumerate() {
+                if doc_shard_opt != Some(shard) {
+                    delete_bitsets[segment_ord].insert(doc);
+                }
+            }
+        }
+    }
+
+    let schema = index.schema();
+    let settings = index.settings().clone();
+    let metas = index.load_metas()?;
+    let payload = metas.payload;
+    let field_tokenizers = merge_field_tokenizers(&metas.segments);
+    let mut shard_indices = Vec::with_capacity(num_shards);
+    for (output_directory, delete_bitsets) in
+        output_directories.into_iter().zip(shard_delete_bitsets)
+    {
+        let shard_readers: Vec<SegmentReader> = readers
+            .iter()
+            .zip(delete_bitsets.iter())
+            .map(|(reader, delete_bitset)| {
+                let delete_bitset = DeleteBitSet::from_bitset(delete_bitset, reader.max_doc());
+                reader.with_delete_bitset(Some(delete_bitset))
+            })
+            .collect();
+        let num_docs: u32 = shard_readers.iter().map(SegmentReader::num_docs).sum();
+
+        let mut shard_index = Index::create(output_directory, schema.clone(), settings.clone())?;
+        let mut segments = Vec::new();
+        if num_docs > 0 {
+            let shard_segment = shard_index.new_segment();
+            let shard_segment_id = shard_segment.id();
+            let merger =
+                IndexMerger::open_readers(schema.clone(), settings.clone(), shard_readers)?;
+            let segment_serializer = SegmentSerializer::for_segment(shard_segment, true)?;
+            let num_docs = merger.write(segment_serializer, None)?;
+            let segment_meta = shard_index
+                .new_segment_meta(shard_segment_id, num_docs)
+                .with_field_tokenizers(field_tokenizers.clone());
+            segments.push(segment_meta);
+        }
+        let index_meta = IndexMeta {
+            index_settings: settings.clone(),
+            segments,
+            schema: schema.clone(),
+            opstamp: 0u64,
+            payload: payload.clone(),
+            commits: Vec::new(),
+        };
+        save_metas(&index_meta, shard_index.directory_mut())?;
+        shard_indices.push(shard_index);
+    }
+    Ok(shard_indices)
+}
+
+/// Advanced: Splits the documents of an index into several new indices,
+/// based on the hash of the value of `field`.
+///
+/// Two documents with the same value for `field` always end up in the same shard.
+/// `field` is required to be either a single-valued fast field, or a stored field.
+/// In the latter case, only its first value is considered, and documents without
+/// any value are assigned to the first shard.
+///
+/// See [`split_index`](./fn.split_index.html).
+pub fn split_index_by_field<Dir: Directory>(
+    index: &Index,
+    field: Field,
+    output_directories: Vec<Dir>,
+) -> crate::Result<Vec<Index>> {
+    let num_shards = output_directories.len();
+    let field_entry = index.schema().get_field_entry(field).clone();
+    let is_single_value_fast_field = match field_entry.field_type() {
+        FieldType::U64(options)
+        | FieldType::I64(options)
+        | FieldType::F64(options)
+        | FieldType::Date(options) => {
+            options.get_fastfield_cardinality() == Some(Cardinality::SingleValue)
+        }
+        _ => false,
+    };
+    if !is_single_value_fast_field && !field_entry.is_stored() {
+        return Err(crate::TantivyError::SchemaError(format!(
+            "Field {:?} is neither a single-valued fast field nor a stored field.",
+            field_entry.name()
+        )));
+    }
+    // Accessors are opened once per segment.
+    let mut fast_field_reader_opt: Option<(SegmentId, DynamicFastFieldReader<u64>)> = None;
+    let mut store_reader_opt: Option<(SegmentId, StoreReader)> = None;
+    split_index(index, output_directories, |reader, doc| {
+        let segment_id = reader.segment_id();
+        let hash = if is_single_value_fast_field {
+            if fast_field_reader_opt.as_ref().map(|(id, _)| *id) != Some(segment_id) {
+                fast_field_reader_opt =
+                    Some((segment_id, reader.fast_fields().u64_lenient(field)?));
+            }
+            let (_, fast_field_reader) = fast_field_reader_opt.as_ref().unwrap();
+            // The fast field holds the same u64 representation as the term.
+            murmurhash2(&fast_field_reader.get(doc).to_be_bytes())
+        } else {
+            if store_reader_opt.as_ref().map(|(id, _)| *id) != Some(segment_id) {
+                store_reader_opt = Some((segment_id, reader.get_store_reader()?));
+            }
+            let (_, store_reader) = store_reader_opt.as_ref().unwrap();
+            let document = store_reader.get(doc)?;
+            match document.get_first(field) {
+                Some(value) => murmurhash2(value_term(field, value).value_bytes()),
+                None => 0u32,
+            }
+        };
+        Ok(hash as usize % num_shards)
+    })
+}
+
+fn value_term(field: Field, value: &Value) -> Term {
+    match value {
+        Value::Str(text) => Term::from_field_text(field, text),
+        Value::PreTokStr(tokenized_text) => Term::from_field_text(field, &tokenized_text.text),
+        Value::U64(val) => Term::from_field_u64(field, *val),
+        Value::I64(val) => Term::from_field_i64(field, *val),
+        Value::F64(val) => Term::from_field_f64(field, *val),
+        Value::Date(date) => Term::from_field_date(field, date),
+        Value::Facet(facet) => Term::from_facet(field, facet),
+        Value::Bytes(bytes) => Term::from_field_bytes(field, bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_index, split_index_by_field};
+    use crate::collector::Count;
+    use crate::directory::RamDirectory;
+    use crate::fastfield::FastFieldReader;
+    use crate::query::TermQuery;
+    use crate::schema::{IndexRecordOption, Schema, FAST, STORED, STRING, TEXT};
+    use crate::{DocAddress, Index, IndexSettings, IndexSortByField, Order, Term};
+
+    #[test]
+    fn test_split_index_by_predicate() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT | STORED);
+        let num_field = schema_builder.add_u64_field("num", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_for_tests()?;
+            for i in 0u64..10u64 {
+                let text = if i % 2 == 0 { "even" } else { "odd" };
+                index_writer.add_document(doc!(text_field=>text, num_field=>i));
+            }
+            index_writer.commit()?;
+            index_writer.delete_term(Term::from_field_text(text_field, "odd"));
+            index_writer.add_document(doc!(text_field=>"odd", num_field=>11u64));
+            index_writer.commit()?;
+        }
+        let shards = split_index(
+            &index,
+            vec![RamDirectory::create(), RamDirectory::create()],
+            |reader, doc| {
+                let num = reader.fast_fields().u64(num_field)?.get(doc);
+                Ok(if num < 5 { 0 } else { 1 })
+            },
+        )?;
+        assert_eq!(shards.len(), 2);
+        let query = TermQuery::new(
+            Term::from_field_text(text_field, "even"),
+            IndexRecordOption::Basic,
+        );
+        let searcher = shards[0].reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 3);
+        assert_eq!(searcher.search(&query, &Count)?, 3);
+        let searcher = shards[1].reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 3);
+        assert_eq!(searcher.search(&query, &Count)?, 2);
+        let doc = searcher.doc(DocAddress {
+            segment_ord: 0,
+            doc_id: 0,
+        })?;
+        assert!(doc.get_first(text_field).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_sorted_index() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let num_field = schema_builder.add_u64_field("num", FAST | STORED);
+        let settings = IndexSettings {
+            sort_by_field: Some(IndexSortByField {
+                field: "num".to_string(),
+                order: Order::Desc,
+            }),
+            ..Default::default()
+        };
+        let index = Index::builder()
+            .schema(schema_builder.build())
+            .settings(settings)
+            .create_in_ram()?;
+        {
+            let mut index_writer = index.writer_for_tests()?;
+            for i in [1u64, 5, 2, 8] {
+                index_writer.add_document(doc!(num_field=>i));
+            }
+            index_writer.commit()?;
+            for i in [7u64, 3, 6] {
+                index_writer.add_document(doc!(num_field=>i));
+            }
+            index_writer.commit()?;
+        }
+        let shards = split_index(
+            &index,
+            vec![RamDirectory::create(), RamDirectory::create()],
+            |reader, doc| Ok((reader.fast_fields().u64(num_field)?.get(doc) % 2) as usize),
+        )?;
+        let searcher = shards[0].reader()?.searcher();
+        let fast_field_reader = searcher.segment_reader(0).fast_fields().u64(num_field)?;
+        let vals: Vec<u64> = (0..searcher.num_docs() as u32)
+            .map(|doc| fast_field_reader.get(doc))
+            .collect();
+        assert_eq!(vals, vec![8, 6, 2]);
+        let stored_vals: Vec<u64> = (0..3u32)
+            .map(|doc_id| {
+                let doc = searcher.doc(DocAddress {
+                    segment_ord: 0,
+                    doc_id,
+                })?;
+                Ok(doc.get_first(num_field).unwrap().u64_value().unwrap())
+            })
+            .collect::<crate::Result<_>>()?;
+        assert_eq!(stored_vals, vec![8, 6, 2]);
+        assert_eq!(shards[1].reader()?.searcher().num_docs(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_index_by_field() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id_field = schema_builder.add_text_field("id", STRING | STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_for_tests()?;
+            for i in 0u64..100u64 {
+                index_writer.add_document(doc!(id_field=>format!("id{}", i % 20)));
+            }
+            index_writer.commit()?;
+        }
+        let output_directories = (0..3).map(|_| RamDirectory::create()).collect();
+        let shards = split_index_by_field(&index, id_field, output_directories)?;
+        let searchers: Vec<_> = shards
+            .iter()
+            .map(|shard| shard.reader().map(|reader| reader.searcher()))
+            .collect::<crate::Result<_>>()?;
+        let num_docs: u64 = searchers.iter().map(|searcher| searcher.num_docs()).sum();
+        assert_eq!(num_docs, 100);
+        for i in 0..20 {
+            let query = TermQuery::new(
+                Term::from_field_text(id_field, &format!("id{}", i)),
+                IndexRecordOption::Basic,
+            );
+            let counts = searchers
+                .iter()
+                .map(|searcher| searcher.search(&query, &Count))
+                .collect::<crate::Result<Vec<usize>>>()?;
+            assert_eq!(counts.iter().filter(|&&count| count > 0).count(), 1);
+            assert_eq!(counts.iter().sum::<usize>(), 5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_index_invalid_shard() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_for_tests()?;
+            index_writer.add_document(doc!(text_field=>"hello"));
+            index_writer.commit()?;
+        }
+        assert!(split_index(&index, vec![RamDirectory::create()], |_, _| Ok(1)).is_err());
+        assert!(split_index::<RamDirectory, _>(&index, vec![], |_, _| Ok(0)).is_err());
+        Ok(())
+    }
+}
@@ -8,13 +8,16 @@ use crate::core::SegmentComponent;
 use crate::core::SegmentId;
 use crate::core::SegmentMeta;
 use crate::core::SegmentReader;
+use crate::directory::error::OpenReadError;
 use crate::directory::TerminatingWrite;
 use crate::directory::{DirectoryLock, GarbageCollectionResult};
 use crate::docset::{DocSet, TERMINATED};
 use crate::error::TantivyError;
 use crate::fastfield::write_delete_bitset;
 use crate::indexer::delete_queue::{DeleteCursor, DeleteQueue};
+use crate::indexer::deletion_policy::DeletionPolicy;
 use crate::indexer::doc_opstamp_mapping::DocToOpstampMapping;
+use crate::indexer::document_processor::{process_document, DocumentProcessor};
 use crate::indexer::operation::DeleteOperation;
 use crate::indexer::stamper::Stamper;
 use crate::indexer::MergePolicy;
@@ -29,9 +32,10 @@ use futures::executor::block_on;
 use futures::future::Future;
 use smallvec::smallvec;
 use smallvec::SmallVec;
+use std::io::Write;
 use std::mem;
 use std::ops::Range;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::thread;
 use std::thread::JoinHandle;
 
@@ -90,6 +94,8 @@ pub struct IndexWriter {
 
     stamper: Stamper,
     committed_opstamp: Opstamp,
+
+    document_processors: Arc<RwLock<Vec<Arc<dyn DocumentProcessor>>>>,
 }
 
 fn compute_deleted_bitset(
@@ -150,7 +156,7 @@ pub(crate) fn advance_deletes(
         return Ok(());
     }
 
-    let segment_reader = SegmentReader::open(&segment)?;
+    let segment_reader = SegmentReader::open_without_expiry(&segment)?;
 
     let max_doc = segment_reader.max_doc();
     let mut delete_bitset: BitSet = match segment_entry.delete_bitset() {
@@ -192,19 +198,77 @@ pub(crate) fn advance_deletes(
     Ok(())
 }
 
+/// Copies the files of a segment belonging to another index into a new segment of `index`.
+///
+/// The deleted documents of the source segment are not written right away.
+/// They are returned as a bitset, and will be written with the opstamp of the
+/// next commit when deletes get advanced.
+fn import_segment(
+    index: &Index,
+    source_segment: &Segment,
+) -> crate::Result<(SegmentMeta, Option<BitSet>)> {
+    let source_meta = source_segment.meta();
+    let max_doc = source_meta.max_doc();
+    let segment_meta = index
+        .new_segment_meta(SegmentId::generate_random(), max_doc)
+        .with_field_tokenizers(source_meta.field_tokenizers().clone());
+    segment_meta.untrack_temp_docstore();
+    let mut segment = index.segment(segment_meta.clone());
+    for &component in SegmentComponent::iterator() {
+        if let SegmentComponent::Delete | SegmentComponent::TempStore = component {
+            continue;
+        }
+        let file_slice = match source_segment.open_read(component) {
+            Ok(file_slice) => file_slice,
+            Err(OpenReadError::FileDoesNotExist(_)) => continue,
+            Err(err) => return Err(err.into()),
+        };
+        let mut write = segment.open_write(component)?;
+        write.write_all(file_slice.read_bytes()?.as_slice())?;
+        write.terminate()?;
+    }
+    if !source_meta.has_deletes() {
+        return Ok((segment_meta, None));
+    }
+    let source_reader = SegmentReader::open(source_segment)?;
+    let mut delete_bitset = BitSet::with_max_value(max_doc);
+    if let Some(source_delete_bitset) = source_reader.delete_bitset() {
+        for doc in 0u32..max_doc {
+            if source_delete_bitset.is_deleted(doc) {
+                delete_bitset.insert(doc);
+            }
+        }
+    }
+    Ok((segment_meta, Some(delete_bitset)))
+}
+
 fn index_documents(
     memory_budget: usize,
     segment: Segment,
     grouped_document_iterator: &mut dyn Iterator<Item = OperationGroup>,
     segment_updater: &mut SegmentUpdater,
+    document_processors: &[Arc<dyn DocumentProcessor>],
     mut delete_cursor: DeleteCursor,
 ) -> crate::Result<bool> {
     let schema = segment.schema();
 
     let mut segment_writer = SegmentWriter::for_segment(memory_budget, segment.clone(), &schema)?;
+    let mut processed_documents = Vec::new();
     for document_group in grouped_document_iterator {
-        for doc in document_group {
-            segment_writer.add_document(doc, &schema)?;
+        for add_operation in document_group {
+            if document_processors.is_empty() {
+                segment_writer.add_document(add_operation, &schema)?;
+                continue;
+            }
+            let opstamp = add_operation.opstamp;
+            process_document(
+                document_processors,
+                add_operation.document,
+                &mut processed_documents,
+            );
+            for document in processed_documents.drain(..) {
+                segment_writer.add_document(AddOperation { opstamp, document }, &schema)?;
+            }
         }
         let mem_usage = segment_writer.mem_usage();
         if mem_usage >= memory_budget - MARGIN_IN_BYTES {
@@ -222,13 +286,18 @@ fn index_documents(
 
     let max_doc = segment_writer.max_doc();
 
-    // this is ensured by the call to peek before starting
-    // the worker thread.
-    assert!(max_doc > 0);
+    if max_doc == 0 {
+        // All of the documents have been dropped by the document processors.
+        // The files of the segment will be removed by the garbage collection.
+        return Ok(true);
+    }
 
+    let field_tokenizers = segment_writer.field_tokenizers();
     let doc_opstamps: Vec<Opstamp> = segment_writer.finalize()?;
 
-    let segment_with_max_doc = segment.with_max_doc(max_doc);
+    let segment_with_max_doc = segment
+        .with_max_doc(max_doc)
+        .with_field_tokenizers(field_tokenizers);
 
     let last_docstamp: Opstamp = *(doc_opstamps.last().unwrap());
 
@@ -340,6 +409,8 @@ impl IndexWriter {
             committed_opstamp: current_opstamp,
             stamper,
 
+            document_processors: Arc::default(),
+
             worker_id: 0,
         };
         index_writer.start_workers()?;
@@ -387,6 +458,47 @@ impl IndexWriter {
         block_on(self.segment_updater.schedule_add_segment(segment_entry))
     }
 
+    /// Imports all of the searchable segments of the given indexes.
+    ///
+    /// The indexes must have the same schema and the same index settings
+    /// as the index being written to. The files of their segments are
+    /// copied into this index under new segment ids, and their deleted
+    /// documents remain deleted.
+    ///
+    /// Just like for added documents, the imported segments only become
+    /// visible after the next commit, and delete operations issued
+    /// before the call to `add_indexes` do not apply to them.
+    ///
+    /// Returns the opstamp of the import operation.
+    pub fn add_indexes(&self, indexes: &[Index]) -> crate::Result<Opstamp> {
+        let schema = self.index.schema();
+        if indexes.iter().any(|index| index.schema() != schema) {
+            return Err(TantivyError::InvalidArgument(
+                "Attempt to add indexes with a different schema".to_string(),
+            ));
+        }
+        if indexes
+            .iter()
+            .any(|index| index.settings() != self.index.settings())
+        {
+            return Err(TantivyError::InvalidArgument(
+                "Attempt to add indexes with different index_settings".to_string(),
+            ));
+        }
+        let opstamp = self.stamper.stamp();
+        let mut delete_cursor = self.delete_queue.cursor();
+        delete_cursor.skip_to(opstamp);
+        for index in indexes {
+            for segment in index.searchable_segments()? {
+                let (segment_meta, delete_bitset_opt) = import_segment(&self.index, &segment)?;
+                let segment_entry =
+                    SegmentEntry::new(segment_meta, delete_cursor.clone(), delete_bitset_opt);
+                block_on(self.segment_updater.schedule_add_segment(segment_entry))?;
+            }
+        }
+        Ok(opstamp)
+    }
+
     /// Creates a new segment.
     ///
     /// This method is useful only for users trying to do complex
@@ -409,6 +521,7 @@ impl IndexWriter {
 
         let mem_budget = self.heap_size_in_bytes_per_thread;
         let index = self.index.clone();
+        let document_processors = self.document_processors.clone();
         let join_handle: JoinHandle<crate::Result<()>> = thread::Builder::new()
             .name(format!("thrd-tantivy-index{}", self.worker_id))
             .spawn(move || {
@@ -436,11 +549,16 @@ impl IndexWriter {
                         return Ok(());
                     }
                     let segment = index.new_segment();
+                    let document_processors = document_processors
+                        .read()
+                        .expect("Failed to acquire read lock on document processors")
+                        .clone();
                     index_documents(
                         mem_budget,
                         segment,
                         &mut document_iterator,
                         &mut segment_updater,
+                        &document_processors,
                         delete_cursor.clone(),
                     )?;
                 }
@@ -450,6 +568,21 @@ impl IndexWriter {
         Ok(())
     }
 
+    /// Registers a document processor.
+    ///
+    /// Document processors run in the indexing worker threads, in
+    /// the order they were registered.
+    /// See [`DocumentProcessor`](./trait.DocumentProcessor.html).
+    ///
+    /// Processors should be registered before adding documents: documents
+    /// that were added before the registration may or may not be processed.
+    pub fn add_document_processor(&self, document_processor: Box<dyn DocumentProcessor>) {
+        self.document_processors
+            .write()
+            .expect("Failed to acquire write lock on document processors")
+            .push(Arc::from(document_processor));
+    }
+
     /// Accessor to the merge policy.
     pub fn get_merge_policy(&self) -> Arc<dyn MergePolicy> {
         self.segment_updater.get_merge_policy()
@@ -460,6 +593,20 @@ impl IndexWriter {
         self.segment_updater.set_merge_policy(merge_policy);
     }
 
+    /// Accessor to the deletion policy.
+    pub fn get_deletion_policy(&self) -> Arc<dyn DeletionPolicy> {
+        self.segment_updater.get_deletion_policy()
+    }
+
+    /// Setter for the deletion policy.
+    ///
+    /// The deletion policy defines which commits are retained, and can
+    /// therefore be opened with `Index::open_at_commit`.
+    pub fn set_deletion_policy(&self, deletion_policy: Box<dyn DeletionPolicy>) {
+        self.segment_updater
+            .set_deletion_policy(Arc::from(deletion_policy));
+    }
+
     fn start_workers(&mut self) -> crate::Result<()> {
         for _ in 0..self.num_threads {
             self.add_indexing_worker()?;
@@ -574,6 +721,20 @@ impl IndexWriter {
             directory_lock,
         )?;
 
+        // The document processors and the deletion policy are carried over.
+        // The workers of the new index writer share its list of document processors.
+        *new_index_writer
+            .document_processors
+            .write()
+            .expect("Failed to acquire write lock on document processors") = self
+            .document_processors
+            .read()
+            .expect("Failed to acquire read lock on document processors")
+            .clone();
+        new_index_writer
+            .segment_updater
+            .set_deletion_policy(self.get_deletion_policy());
+
         // the current `self` is dropped right away because of this call.
         //
         // This will drop the document queue, and the thread
@@ -666,6 +827,14 @@ impl IndexWriter {
         &self.segment_updater
     }
 
+    /// Makes the writer follow the last commit of the index.
+    ///
+    /// This is called after each commit, as an `IndexWriter` opened on an
+    /// earlier commit should rollback to its own commits from then on.
+    pub(crate) fn unpin_commit(&mut self) {
+        self.index = self.index.unpinned();
+    }
+
     /// Delete all documents containing a given term.
     ///
     /// Delete operation only affects documents that
@@ -786,12 +955,13 @@ impl Drop for IndexWriter {
 mod tests {
 
     use super::super::operation::UserOperation;
-    use crate::collector::TopDocs;
+    use crate::collector::{Count, TopDocs};
     use crate::directory::error::LockError;
     use crate::error::*;
-    use crate::indexer::NoMergePolicy;
+    use crate::indexer::deletion_policy::KeepLastNCommits;
+    use crate::indexer::{DocumentProcessor, NoMergePolicy};
     use crate::query::TermQuery;
-    use crate::schema::{self, IndexRecordOption, STRING};
+    use crate::schema::{self, Document, IndexRecordOption, STRING};
     use crate::Index;
     use crate::ReloadPolicy;
     use crate::Term;
@@ -1293,4 +1463,170 @@ mod tests {
         let commit = index_writer.commit();
         assert!(commit.is_ok());
     }
+
+    #[test]
+    fn test_add_indexes() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", schema::TEXT | schema::STORED);
+        let schema = schema_builder.build();
+
+        let other_index = Index::create_in_ram(schema.clone());
+        {
+            let mut other_writer = other_index.writer_for_tests()?;
+            other_writer.add_document(doc!(text_field=>"a b"));
+            other_writer.add_document(doc!(text_field=>"a c"));
+            other_writer.add_document(doc!(text_field=>"b"));
+            other_writer.commit()?;
+            other_writer.delete_term(Term::from_field_text(text_field, "c"));
+            other_writer.commit()?;
+        }
+
+        let index = Index::create_in_ram(schema);
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let num_docs_containing = |s: &str| {
+            let term = Term::from_field_text(text_field, s);
+            reader
+                .searcher()
+                .search(&TermQuery::new(term, IndexRecordOption::Basic), &Count)
+                .unwrap()
+        };
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(text_field=>"a"));
+        index_writer.delete_term(Term::from_field_text(text_field, "b"));
+        index_writer.add_indexes(&[other_index.clone(), other_index])?;
+        reader.reload()?;
+        assert_eq!(reader.searcher().num_docs(), 0);
+
+        index_writer.commit()?;
+        reader.reload()?;
+        assert_eq!(reader.searcher().segment_readers().len(), 3);
+        assert_eq!(reader.searcher().num_docs(), 5);
+        assert_eq!(num_docs_containing("a"), 3);
+        assert_eq!(num_docs_containing("b"), 4);
+        assert_eq!(num_docs_containing("c"), 0);
+        let top_docs = reader.searcher().search(
+            &TermQuery::new(
+                Term::from_field_text(text_field, "b"),
+                IndexRecordOption::Basic,
+            ),
+            &TopDocs::with_limit(1),
+        )?;
+        let doc = reader.searcher().doc(top_docs[0].1)?;
+        assert_eq!(doc.get_first(text_field).unwrap().text(), Some("a b"));
+
+        // Deletes issued after the import apply to the imported documents.
+        index_writer.delete_term(Term::from_field_text(text_field, "b"));
+        index_writer.commit()?;
+        reader.reload()?;
+        assert_eq!(reader.searcher().num_docs(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_indexes_different_schema() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        schema_builder.add_text_field("text", schema::TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut other_schema_builder = schema::Schema::builder();
+        other_schema_builder.add_text_field("title", schema::TEXT);
+        let other_index = Index::create_in_ram(other_schema_builder.build());
+        let index_writer = index.writer_for_tests()?;
+        assert!(matches!(
+            index_writer.add_indexes(&[other_index]),
+            Err(TantivyError::InvalidArgument(_))
+        ));
+        Ok(())
+    }
+
+    #[derive(Debug)]
+    struct LowercaseAndSplit {
+        text_field: schema::Field,
+    }
+
+    impl DocumentProcessor for LowercaseAndSplit {
+        fn process(&self, document: Document, output: &mut Vec<Document>) {
+            let text = match document
+                .get_first(self.text_field)
+                .and_then(|val| val.text())
+            {
+                Some(text) => text.to_lowercase(),
+                None => return,
+            };
+            for part in text.split(',') {
+                output.push(doc!(self.text_field=>part));
+            }
+        }
+    }
+
+    #[test]
+    fn test_document_processor() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let other_field = schema_builder.add_text_field("other", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document_processor(Box::new(LowercaseAndSplit { text_field }));
+        index_writer.add_document(doc!(text_field=>"A,B"));
+        index_writer.add_document(doc!(other_field=>"dropped"));
+        index_writer.add_document(doc!(text_field=>"C"));
+        index_writer.delete_term(Term::from_field_text(text_field, "a"));
+        index_writer.add_document(doc!(text_field=>"A"));
+        index_writer.commit()?;
+
+        let reader = index.reader()?;
+        let searcher = reader.searcher();
+        assert_eq!(searcher.num_docs(), 3);
+        let num_docs_containing = |field: schema::Field, s: &str| {
+            let term = Term::from_field_text(field, s);
+            searcher
+                .search(&TermQuery::new(term, IndexRecordOption::Basic), &Count)
+                .unwrap()
+        };
+        assert_eq!(num_docs_containing(text_field, "a"), 1);
+        assert_eq!(num_docs_containing(text_field, "b"), 1);
+        assert_eq!(num_docs_containing(text_field, "c"), 1);
+        assert_eq!(num_docs_containing(text_field, "A"), 0);
+        assert_eq!(num_docs_containing(other_field, "dropped"), 0);
+
+        // A batch of documents that are all dropped does not create any segment.
+        index_writer.add_document(doc!(other_field=>"dropped"));
+        index_writer.commit()?;
+        assert_eq!(index.searchable_segment_ids()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rollback_keeps_document_processors_and_deletion_policy() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document_processor(Box::new(LowercaseAndSplit { text_field }));
+        index_writer.set_deletion_policy(Box::new(KeepLastNCommits::new(2)));
+        index_writer.add_document(doc!(text_field=>"A"));
+        index_writer.rollback()?;
+        index_writer.add_document(doc!(text_field=>"B,C"));
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 2);
+        let num_docs_containing = |s: &str| {
+            let term = Term::from_field_text(text_field, s);
+            searcher
+                .search(&TermQuery::new(term, IndexRecordOption::Basic), &Count)
+                .unwrap()
+        };
+        assert_eq!(num_docs_containing("b"), 1);
+        assert_eq!(num_docs_containing("c"), 1);
+        assert_eq!(num_docs_containing("B,C"), 0);
+        assert_eq!(
+            format!("{:?}", index_writer.get_deletion_policy()),
+            "KeepLastNCommits { num_commits: 2 }"
+        );
+        Ok(())
+    }
 }
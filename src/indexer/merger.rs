@@ -177,14 +177,30 @@ impl IndexMerger {
         segments: &[Segment],
     ) -> crate::Result<IndexMerger> {
         let mut readers = vec![];
-        let mut max_doc: u32 = 0u32;
         for segment in segments {
             if segment.meta().num_docs() > 0 {
                 let reader = SegmentReader::open(segment)?;
-                max_doc += reader.num_docs();
                 readers.push(reader);
             }
         }
+        IndexMerger::open_readers(schema, index_settings, readers)
+    }
+
+    /// Creates an `IndexMerger` over already opened segment readers.
+    ///
+    /// The documents marked as deleted in the readers are dropped, which makes
+    /// it possible to rewrite a subset of the documents of a segment by
+    /// giving its reader a computed delete bitset.
+    pub(crate) fn open_readers(
+        schema: Schema,
+        index_settings: IndexSettings,
+        readers: Vec<SegmentReader>,
+    ) -> crate::Result<IndexMerger> {
+        let mut readers: Vec<SegmentReader> = readers
+            .into_iter()
+            .filter(|reader| reader.num_docs() > 0)
+            .collect();
+        let max_doc: u32 = readers.iter().map(SegmentReader::num_docs).sum();
         if let Some(sort_by_field) = index_settings.sort_by_field.as_ref() {
             readers = Self::sort_readers_by_min_sort_field(readers, sort_by_field)?;
         }
@@ -1,7 +1,10 @@
 pub mod delete_queue;
+pub mod deletion_policy;
 
 pub mod doc_id_mapping;
 mod doc_opstamp_mapping;
+mod document_processor;
+pub mod index_splitter;
 pub mod index_writer;
 mod log_merge_policy;
 mod merge_operation;
@@ -18,6 +21,8 @@ pub mod segment_updater;
 mod segment_writer;
 mod stamper;
 
+pub use self::document_processor::DocumentProcessor;
+pub use self::index_splitter::{split_index, split_index_by_field};
 pub use self::index_writer::IndexWriter;
 pub use self::log_merge_policy::LogMergePolicy;
 pub use self::merge_operation::MergeOperation;
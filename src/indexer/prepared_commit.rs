@@ -37,6 +37,7 @@ impl<'a> PreparedCommit<'a> {
                 .segment_updater()
                 .schedule_commit(self.opstamp, self.payload),
         );
+        self.index_writer.unpin_commit();
         Ok(self.opstamp)
     }
 }
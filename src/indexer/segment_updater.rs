@@ -1,14 +1,19 @@
 use super::segment_manager::{get_mergeable_segments, SegmentManager};
+use crate::common::CompositeFile;
+use crate::core::merge_field_tokenizers;
 use crate::core::Index;
 use crate::core::IndexMeta;
 use crate::core::IndexSettings;
 use crate::core::Segment;
+use crate::core::SegmentComponent;
 use crate::core::SegmentId;
 use crate::core::SegmentMeta;
 use crate::core::SerializableSegment;
-use crate::core::META_FILEPATH;
+use crate::core::{commit_meta_filepath, META_FILEPATH};
 use crate::directory::{Directory, DirectoryClone, GarbageCollectionResult};
+use crate::fastfield::{FastFieldReader, FastFieldReaders};
 use crate::indexer::delete_queue::DeleteCursor;
+use crate::indexer::deletion_policy::{DeletionPolicy, KeepOnlyLastCommit};
 use crate::indexer::index_writer::advance_deletes;
 use crate::indexer::merge_operation::MergeOperationInventory;
 use crate::indexer::merger::IndexMerger;
@@ -18,8 +23,9 @@ use crate::indexer::SegmentEntry;
 use crate::indexer::SegmentSerializer;
 use crate::indexer::{DefaultMergePolicy, MergePolicy};
 use crate::indexer::{MergeCandidate, MergeOperation};
-use crate::schema::Schema;
+use crate::schema::{Field, Schema};
 use crate::Opstamp;
+use chrono::Utc;
 use futures::channel::oneshot;
 use futures::executor::{ThreadPool, ThreadPoolBuilder};
 use futures::future::Future;
@@ -56,6 +62,7 @@ pub fn save_new_metas(
             schema,
             opstamp: 0u64,
             payload: None,
+            commits: Vec::new(),
         },
         directory,
     )
@@ -69,12 +76,18 @@ pub fn save_new_metas(
 /// - it success, and `meta.json` is written
 /// and flushed.
 ///
+/// If the commit is listed in `metas.commits`, its own meta file
+/// is written before `meta.json`.
+///
 /// This method is not part of tantivy's public API
-fn save_metas(metas: &IndexMeta, directory: &dyn Directory) -> crate::Result<()> {
+pub(crate) fn save_metas(metas: &IndexMeta, directory: &dyn Directory) -> crate::Result<()> {
     info!("save metas");
     let mut buffer = serde_json::to_vec_pretty(metas)?;
     // Just adding a new line at the end of the buffer.
     writeln!(&mut buffer)?;
+    if metas.commits.contains(&metas.opstamp) {
+        directory.atomic_write(&commit_meta_filepath(metas.opstamp), &buffer[..])?;
+    }
     directory.atomic_write(&META_FILEPATH, &buffer[..])?;
     debug!("Saved metas {:?}", serde_json::to_string_pretty(&metas));
     Ok(())
@@ -144,10 +157,22 @@ fn merge(
 
     let merged_segment_id = merged_segment.id();
 
-    let segment_meta = index.new_segment_meta(merged_segment_id, num_docs);
+    let field_tokenizers = merge_field_tokenizers(segments.iter().map(Segment::meta));
+    let segment_meta = index
+        .new_segment_meta(merged_segment_id, num_docs)
+        .with_field_tokenizers(field_tokenizers);
     Ok(SegmentEntry::new(segment_meta, delete_cursor, None))
 }
 
+// Returns the latest expiry date of the documents of the segment.
+fn max_expiry_timestamp(segment: &Segment, expiry_field: Field) -> crate::Result<i64> {
+    let fast_fields_data = segment.open_read(SegmentComponent::FastFields)?;
+    let fast_field_readers =
+        FastFieldReaders::new(segment.schema(), CompositeFile::open(&fast_fields_data)?);
+    let expiry_reader = fast_field_readers.typed_fast_field_reader::<i64>(expiry_field)?;
+    Ok(expiry_reader.max_value())
+}
+
 /// Advanced: Merges a list of segments from different indices in a new index.
 ///
 /// Returns `TantivyError` if the the indices list is empty or their
@@ -211,7 +236,10 @@ pub fn merge_segments<Dir: Directory>(
     let segment_serializer = SegmentSerializer::for_segment(merged_segment, true)?;
     let num_docs = merger.write(segment_serializer, None)?;
 
-    let segment_meta = merged_index.new_segment_meta(merged_segment_id, num_docs);
+    let field_tokenizers = merge_field_tokenizers(segments.iter().map(Segment::meta));
+    let segment_meta = merged_index
+        .new_segment_meta(merged_segment_id, num_docs)
+        .with_field_tokenizers(field_tokenizers);
 
     let stats = format!(
         "Segments Merge: [{}]",
@@ -231,6 +259,7 @@ pub fn merge_segments<Dir: Directory>(
         schema: target_schema,
         opstamp: 0u64,
         payload: Some(stats),
+        commits: Vec::new(),
     };
 
     // save the meta.json
@@ -247,12 +276,19 @@ pub(crate) struct InnerSegmentUpdater {
     // This should be up to date as all update happen through
     // the unique active `SegmentUpdater`.
     active_index_meta: RwLock<Arc<IndexMeta>>,
+    // Metas of the commits retained by the deletion policy, sorted
+    // from the oldest to the most recent one.
+    //
+    // Keeping them alive prevents the garbage collection of
+    // their segment files.
+    retained_commits: RwLock<Vec<IndexMeta>>,
     pool: ThreadPool,
     merge_thread_pool: ThreadPool,
 
     index: Index,
     segment_manager: SegmentManager,
     merge_policy: RwLock<Arc<dyn MergePolicy>>,
+    deletion_policy: RwLock<Arc<dyn DeletionPolicy>>,
     killed: AtomicBool,
     stamper: Stamper,
     merge_operations: MergeOperationInventory,
@@ -285,13 +321,16 @@ impl SegmentUpdater {
                 )
             })?;
         let index_meta = index.load_metas()?;
+        let retained_commits = index.load_commit_metas(&index_meta)?;
         Ok(SegmentUpdater(Arc::new(InnerSegmentUpdater {
             active_index_meta: RwLock::new(Arc::new(index_meta)),
+            retained_commits: RwLock::new(retained_commits),
             pool,
             merge_thread_pool,
             index,
             segment_manager,
             merge_policy: RwLock::new(Arc::new(DefaultMergePolicy::default())),
+            deletion_policy: RwLock::new(Arc::new(KeepOnlyLastCommit)),
             killed: AtomicBool::new(false),
             stamper,
             merge_operations: Default::default(),
@@ -307,6 +346,14 @@ impl SegmentUpdater {
         *self.merge_policy.write().unwrap() = arc_merge_policy;
     }
 
+    pub fn get_deletion_policy(&self) -> Arc<dyn DeletionPolicy> {
+        self.deletion_policy.read().unwrap().clone()
+    }
+
+    pub fn set_deletion_policy(&self, deletion_policy: Arc<dyn DeletionPolicy>) {
+        *self.deletion_policy.write().unwrap() = deletion_policy;
+    }
+
     fn schedule_future<T: 'static + Send, F: Future<Output = crate::Result<T>> + 'static + Send>(
         &self,
         f: F,
@@ -366,6 +413,34 @@ impl SegmentUpdater {
         Ok(segment_entries)
     }
 
+    // Removes the segments in which every document has expired.
+    //
+    // Segments that are being merged are kept: their documents will
+    // be dropped by the merge.
+    fn remove_expired_segments(
+        &self,
+        segment_entries: Vec<SegmentEntry>,
+    ) -> crate::Result<Vec<SegmentEntry>> {
+        let expiry_field = match self.index.schema().expiry_field() {
+            Some(expiry_field) => expiry_field,
+            None => return Ok(segment_entries),
+        };
+        let now = Utc::now().timestamp();
+        let segments_in_merge = self.merge_operations.segment_in_merge();
+        let mut alive_segment_entries = Vec::with_capacity(segment_entries.len());
+        for segment_entry in segment_entries {
+            if !segments_in_merge.contains(&segment_entry.segment_id()) {
+                let segment = self.index.segment(segment_entry.meta().clone());
+                if max_expiry_timestamp(&segment, expiry_field)? <= now {
+                    info!("removing expired segment {:?}", segment_entry.segment_id());
+                    continue;
+                }
+            }
+            alive_segment_entries.push(segment_entry);
+        }
+        Ok(alive_segment_entries)
+    }
+
     pub fn save_metas(
         &self,
         opstamp: Opstamp,
@@ -390,20 +465,57 @@ impl SegmentUpdater {
             //
             // Segment 1 from disk 1, Segment 1 from disk 2, etc.
             commited_segment_metas.sort_by_key(|segment_meta| -(segment_meta.max_doc() as i32));
-            let index_meta = IndexMeta {
+            let mut index_meta = IndexMeta {
                 index_settings: index.settings().clone(),
                 segments: commited_segment_metas,
                 schema: index.schema(),
                 opstamp,
                 payload: commit_message,
+                commits: Vec::new(),
             };
+            let retained_commits = self.compute_retained_commits(&index_meta);
+            index_meta.commits = retained_commits
+                .iter()
+                .map(|commit_meta| commit_meta.opstamp)
+                .collect();
             // TODO add context to the error.
             save_metas(&index_meta, directory.box_clone().borrow_mut())?;
             self.store_meta(&index_meta);
+            *self.retained_commits.write().unwrap() = retained_commits;
         }
         Ok(())
     }
 
+    /// Returns the commits that should be retained once `index_meta` is saved,
+    /// as decided by the deletion policy.
+    ///
+    /// The new commit replaces a previously retained commit with the same opstamp
+    /// (e.g. after a merge), and commits more recent than the new commit are
+    /// dropped. This happens when the writer was opened on an earlier commit.
+    fn compute_retained_commits(&self, index_meta: &IndexMeta) -> Vec<IndexMeta> {
+        let mut commits: Vec<IndexMeta> = self
+            .retained_commits
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|commit_meta| commit_meta.opstamp < index_meta.opstamp)
+            .cloned()
+            .collect();
+        commits.push(index_meta.clone());
+        let retained_opstamps: HashSet<Opstamp> = self
+            .get_deletion_policy()
+            .retained_commits(&commits)
+            .into_iter()
+            .collect();
+        commits
+            .into_iter()
+            .filter(|commit_meta| {
+                commit_meta.opstamp == index_meta.opstamp
+                    || retained_opstamps.contains(&commit_meta.opstamp)
+            })
+            .collect()
+    }
+
     pub fn schedule_garbage_collect(
         &self,
     ) -> impl Future<Output = crate::Result<GarbageCollectionResult>> {
@@ -423,6 +535,9 @@ impl SegmentUpdater {
             .flat_map(|segment_meta| segment_meta.list_files())
             .collect();
         files.insert(META_FILEPATH.to_path_buf());
+        for commit_meta in self.retained_commits.read().unwrap().iter() {
+            files.insert(commit_meta_filepath(commit_meta.opstamp));
+        }
         files
     }
 
@@ -434,6 +549,7 @@ impl SegmentUpdater {
         let segment_updater: SegmentUpdater = self.clone();
         self.schedule_future(async move {
             let segment_entries = segment_updater.purge_deletes(opstamp)?;
+            let segment_entries = segment_updater.remove_expired_segments(segment_entries)?;
             segment_updater.segment_manager.commit(segment_entries);
             segment_updater.save_metas(opstamp, payload)?;
             let _ = garbage_collect_files(segment_updater.clone()).await;
@@ -644,10 +760,15 @@ impl SegmentUpdater {
 #[cfg(test)]
 mod tests {
     use super::merge_segments;
+    use crate::collector::Count;
     use crate::directory::RamDirectory;
     use crate::indexer::merge_policy::tests::MergeWheneverPossible;
+    use crate::indexer::NoMergePolicy;
+    use crate::query::TermQuery;
     use crate::schema::*;
     use crate::Index;
+    use chrono::{Duration, Utc};
+    use futures::executor::block_on;
 
     #[test]
     fn test_delete_during_merge() -> crate::Result<()> {
@@ -743,6 +864,87 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_expired_documents() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let expiry_field =
+            schema_builder.add_date_field("expiry", FAST | IntOptions::default().set_expiry());
+        let index = Index::create_in_ram(schema_builder.build());
+        let past = Utc::now() - Duration::days(1);
+        let future = Utc::now() + Duration::days(1);
+
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(text_field=>"a", expiry_field=>past));
+        index_writer.add_document(doc!(text_field=>"b", expiry_field=>future));
+        index_writer.add_document(doc!(text_field=>"c"));
+        index_writer.commit()?;
+
+        let reader = index.reader()?;
+        let searcher = reader.searcher();
+        assert_eq!(searcher.num_docs(), 2);
+        let num_docs_containing = |s: &str| {
+            let term = Term::from_field_text(text_field, s);
+            searcher
+                .search(&TermQuery::new(term, IndexRecordOption::Basic), &Count)
+                .unwrap()
+        };
+        assert_eq!(num_docs_containing("a"), 0);
+        assert_eq!(num_docs_containing("b"), 1);
+        assert_eq!(num_docs_containing("c"), 1);
+
+        // Expired documents are not written to the delete file.
+        index_writer.delete_term(Term::from_field_text(text_field, "c"));
+        index_writer.commit()?;
+        let segment_metas = index.searchable_segment_metas()?;
+        assert_eq!(segment_metas.len(), 1);
+        assert_eq!(segment_metas[0].num_deleted_docs(), 1);
+
+        // A segment in which every document has expired is removed on commit.
+        index_writer.add_document(doc!(text_field=>"d", expiry_field=>past));
+        index_writer.add_document(doc!(text_field=>"e", expiry_field=>past));
+        index_writer.commit()?;
+        assert_eq!(index.searchable_segment_ids()?.len(), 1);
+
+        // Expired documents are dropped when merging.
+        index_writer.add_document(doc!(text_field=>"f", expiry_field=>past));
+        index_writer.add_document(doc!(text_field=>"g", expiry_field=>future));
+        index_writer.commit()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        assert_eq!(segment_ids.len(), 2);
+        block_on(index_writer.merge(&segment_ids))?;
+        reader.reload()?;
+        let searcher = reader.searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        assert_eq!(searcher.segment_reader(0).max_doc(), 2);
+        assert_eq!(searcher.num_docs(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_expiry_field() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_u64_field("expiry", FAST | IntOptions::default().set_expiry());
+        let index_res = Index::builder()
+            .schema(schema_builder.build())
+            .create_in_ram();
+        assert!(matches!(
+            index_res,
+            Err(crate::TantivyError::SchemaError(_))
+        ));
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_date_field("expiry", IntOptions::default().set_expiry());
+        let index_res = Index::builder()
+            .schema(schema_builder.build())
+            .create_in_ram();
+        assert!(matches!(
+            index_res,
+            Err(crate::TantivyError::SchemaError(_))
+        ));
+    }
+
     #[test]
     fn test_remove_all_segments() -> crate::Result<()> {
         let mut schema_builder = Schema::builder();
@@ -14,11 +14,12 @@ use crate::schema::Value;
 use crate::schema::{Field, FieldEntry};
 use crate::tokenizer::{BoxTokenStream, PreTokenizedStream};
 use crate::tokenizer::{FacetTokenizer, TextAnalyzer};
-use crate::tokenizer::{TokenStreamChain, Tokenizer};
+use crate::tokenizer::{TokenStreamChain, Tokenizer, TokenizerManager};
 use crate::Opstamp;
 use crate::{core::Segment, store::StoreWriter};
 use crate::{core::SerializableSegment, store::StoreReader};
 use crate::{DocId, SegmentComponent};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 /// Computes the initial size of the hash table.
 ///
@@ -49,6 +50,13 @@ pub struct SegmentWriter {
     pub(crate) fieldnorms_writer: FieldNormsWriter,
     pub(crate) doc_opstamps: Vec<Opstamp>,
     tokenizers: Vec<Option<TextAnalyzer>>,
+    // For each field, the field selecting the tokenizer of each document, if any.
+    tokenizer_fields: Vec<Option<Field>>,
+    tokenizer_manager: TokenizerManager,
+    // Tokenizers selected by the documents, by name.
+    document_tokenizers: HashMap<String, Option<TextAnalyzer>>,
+    // Names of the tokenizers selected by the documents, for each field.
+    field_tokenizers: HashMap<Field, BTreeSet<String>>,
     term_buffer: Term,
 }
 
@@ -85,6 +93,17 @@ impl SegmentWriter {
                 },
             )
             .collect();
+        // The fields selecting the tokenizers are checked when building the schema.
+        let tokenizer_fields = schema
+            .fields()
+            .map(|(_, field_entry)| match field_entry.field_type() {
+                FieldType::Str(ref text_options) => text_options
+                    .get_indexing_options()
+                    .and_then(|text_index_option| text_index_option.tokenizer_field())
+                    .and_then(|field_name| schema.get_field(field_name)),
+                _ => None,
+            })
+            .collect();
         Ok(SegmentWriter {
             max_doc: 0,
             multifield_postings,
@@ -93,6 +112,10 @@ impl SegmentWriter {
             fast_field_writers: FastFieldsWriter::from_schema(schema),
             doc_opstamps: Vec::with_capacity(1_000),
             tokenizers,
+            tokenizer_fields,
+            tokenizer_manager,
+            document_tokenizers: HashMap::new(),
+            field_tokenizers: HashMap::new(),
             term_buffer: Term::new(),
         })
     }
@@ -122,6 +145,17 @@ impl SegmentWriter {
         Ok(self.doc_opstamps)
     }
 
+    /// Returns the names of the tokenizers selected by the documents, by field name.
+    pub(crate) fn field_tokenizers(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let schema = self.segment_serializer.segment().schema();
+        self.field_tokenizers
+            .iter()
+            .map(|(&field, tokenizers)| {
+                (schema.get_field_name(field).to_string(), tokenizers.clone())
+            })
+            .collect()
+    }
+
     pub fn mem_usage(&self) -> usize {
         self.multifield_postings.mem_usage()
             + self.fieldnorms_writer.mem_usage()
@@ -188,6 +222,33 @@ impl SegmentWriter {
                     }
                 }
                 FieldType::Str(_) => {
+                    let document_tokenizer_name = self.tokenizer_fields[field.field_id() as usize]
+                        .and_then(|tokenizer_field| doc.get_first(tokenizer_field))
+                        .and_then(Value::text);
+                    let mut document_tokenizer = None;
+                    if let Some(tokenizer_name) = document_tokenizer_name {
+                        if !self.document_tokenizers.contains_key(tokenizer_name) {
+                            let tokenizer_opt = self.tokenizer_manager.get(tokenizer_name);
+                            if tokenizer_opt.is_none() {
+                                warn!(
+                                    "Unknown tokenizer {:?} selected for field {:?}, falling back to the tokenizer of the field.",
+                                    tokenizer_name,
+                                    field_entry.name()
+                                );
+                            }
+                            self.document_tokenizers
+                                .insert(tokenizer_name.to_string(), tokenizer_opt);
+                        }
+                        document_tokenizer = self.document_tokenizers[tokenizer_name].as_ref();
+                        if document_tokenizer.is_some() {
+                            let tokenizers = self.field_tokenizers.entry(field).or_default();
+                            if !tokenizers.contains(tokenizer_name) {
+                                tokenizers.insert(tokenizer_name.to_string());
+                            }
+                        }
+                    }
+                    let tokenizer_opt =
+                        document_tokenizer.or(self.tokenizers[field.field_id() as usize].as_ref());
                     let mut token_streams: Vec<BoxTokenStream> = vec![];
                     let mut offsets = vec![];
                     let mut total_offset = 0;
@@ -203,9 +264,7 @@ impl SegmentWriter {
                                     .push(PreTokenizedStream::from(tok_str.clone()).into());
                             }
                             Value::Str(ref text) => {
-                                if let Some(ref mut tokenizer) =
-                                    self.tokenizers[field.field_id() as usize]
-                                {
+                                if let Some(tokenizer) = tokenizer_opt {
                                     offsets.push(total_offset);
                                     total_offset += text.len();
                                     token_streams.push(tokenizer.token_stream(text));
@@ -386,6 +445,8 @@ impl SerializableSegment for SegmentWriter {
 #[cfg(test)]
 mod tests {
     use super::initial_table_size;
+    use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions, STRING};
+    use crate::{Index, Term};
 
     #[test]
     fn test_hashmap_size() {
@@ -394,4 +455,35 @@ mod tests {
         assert_eq!(initial_table_size(10_000_000).unwrap(), 17);
         assert_eq!(initial_table_size(1_000_000_000).unwrap(), 19);
     }
+
+    #[test]
+    fn test_unknown_document_tokenizer() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field_indexing = TextFieldIndexing::default()
+            .set_tokenizer("raw")
+            .set_tokenizer_field("lang")
+            .set_index_option(IndexRecordOption::Basic);
+        let text_options = TextOptions::default().set_indexing_options(text_field_indexing);
+        let body = schema_builder.add_text_field("body", text_options);
+        let lang = schema_builder.add_text_field("lang", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(body=>"Hello World", lang=>"default"));
+        index_writer.add_document(doc!(body=>"Hello World", lang=>"unknown"));
+        index_writer.commit()?;
+        let segment_metas = index.searchable_segment_metas()?;
+        let tokenizers: Vec<&str> = segment_metas[0].field_tokenizers()["body"]
+            .iter()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(tokenizers, vec!["default"]);
+        // The document selecting an unknown tokenizer is processed by the tokenizer of the field.
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.doc_freq(&Term::from_field_text(body, "hello"))?, 1);
+        assert_eq!(
+            searcher.doc_freq(&Term::from_field_text(body, "Hello World"))?,
+            1
+        );
+        Ok(())
+    }
 }
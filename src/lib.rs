@@ -168,7 +168,9 @@ pub use crate::core::{InvertedIndexReader, SegmentReader};
 pub use crate::directory::Directory;
 pub use crate::indexer::merge_segments;
 pub use crate::indexer::operation::UserOperation;
+pub use crate::indexer::DocumentProcessor;
 pub use crate::indexer::IndexWriter;
+pub use crate::indexer::{split_index, split_index_by_field};
 pub use crate::postings::Postings;
 pub use crate::reader::LeasedItem;
 pub use crate::schema::{Document, Term};
@@ -233,6 +235,13 @@ pub mod merge_policy {
     pub use crate::indexer::NoMergePolicy;
 }
 
+/// Defines which commits are retained by the index
+pub mod deletion_policy {
+    pub use crate::indexer::deletion_policy::DeletionPolicy;
+    pub use crate::indexer::deletion_policy::KeepLastNCommits;
+    pub use crate::indexer::deletion_policy::KeepOnlyLastCommit;
+}
+
 /// A `u32` identifying a document within a segment.
 /// Documents have their `DocId` assigned incrementally,
 /// as they are added in the segment.
@@ -125,20 +125,34 @@ impl Bm25Weight {
         self.score(255u8, 2_013_265_944)
     }
 
+    /// Computes the score given a fractional frequency, such as
+    /// the sloppy frequency of a phrase.
+    #[inline]
+    pub(crate) fn score_freq(&self, fieldnorm_id: u8, freq: Score) -> Score {
+        self.weight * self.tf_factor_freq(fieldnorm_id, freq)
+    }
+
     #[inline]
     pub(crate) fn tf_factor(&self, fieldnorm_id: u8, term_freq: u32) -> Score {
-        let term_freq = term_freq as Score;
+        self.tf_factor_freq(fieldnorm_id, term_freq as Score)
+    }
+
+    #[inline]
+    fn tf_factor_freq(&self, fieldnorm_id: u8, freq: Score) -> Score {
         let norm = self.cache[fieldnorm_id as usize];
-        term_freq / (term_freq + norm)
+        freq / (freq + norm)
     }
 
     pub fn explain(&self, fieldnorm_id: u8, term_freq: u32) -> Explanation {
+        self.explain_freq(fieldnorm_id, term_freq as Score)
+    }
+
+    pub(crate) fn explain_freq(&self, fieldnorm_id: u8, term_freq: Score) -> Explanation {
         // The explain format is directly copied from Lucene's.
         // (So, Kudos to Lucene)
-        let score = self.score(fieldnorm_id, term_freq);
+        let score = self.score_freq(fieldnorm_id, term_freq);
 
         let norm = self.cache[fieldnorm_id as usize];
-        let term_freq = term_freq as Score;
         let right_factor = term_freq / (term_freq + norm);
 
         let mut tf_explanation = Explanation::new(
@@ -164,6 +164,17 @@ impl Query for BooleanQuery {
             subquery.query_terms(terms);
         }
     }
+
+    fn query_terms_with_searcher(
+        &self,
+        searcher: &Searcher,
+        terms: &mut BTreeMap<Term, bool>,
+    ) -> crate::Result<()> {
+        for (_occur, subquery) in &self.subqueries {
+            subquery.query_terms_with_searcher(searcher, terms)?;
+        }
+        Ok(())
+    }
 }
 
 impl BooleanQuery {
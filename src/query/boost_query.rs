@@ -51,6 +51,14 @@ impl Query for BoostQuery {
     fn query_terms(&self, terms: &mut BTreeMap<Term, bool>) {
         self.query.query_terms(terms)
     }
+
+    fn query_terms_with_searcher(
+        &self,
+        searcher: &Searcher,
+        terms: &mut BTreeMap<Term, bool>,
+    ) -> crate::Result<()> {
+        self.query.query_terms_with_searcher(searcher, terms)
+    }
 }
 
 pub(crate) struct BoostWeight {
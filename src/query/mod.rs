@@ -13,15 +13,18 @@ mod fuzzy_query;
 mod intersection;
 mod more_like_this;
 mod phrase_query;
+mod prefix_query;
 mod query;
 mod query_parser;
 mod range_query;
 mod regex_query;
 mod reqopt_scorer;
 mod scorer;
+mod span_query;
 mod term_query;
 mod union;
 mod weight;
+mod wildcard_query;
 
 #[cfg(test)]
 mod vec_docset;
@@ -47,7 +50,8 @@ pub(crate) use self::fuzzy_query::DfaWrapper;
 pub use self::fuzzy_query::FuzzyTermQuery;
 pub use self::intersection::intersect_scorers;
 pub use self::more_like_this::{MoreLikeThisQuery, MoreLikeThisQueryBuilder};
-pub use self::phrase_query::PhraseQuery;
+pub use self::phrase_query::{MultiPhraseQuery, PhrasePrefixQuery, PhraseQuery};
+pub use self::prefix_query::PrefixQuery;
 pub use self::query::{Query, QueryClone};
 pub use self::query_parser::QueryParser;
 pub use self::query_parser::QueryParserError;
@@ -56,8 +60,13 @@ pub use self::regex_query::RegexQuery;
 pub use self::reqopt_scorer::RequiredOptionalScorer;
 pub use self::scorer::ConstScorer;
 pub use self::scorer::Scorer;
+pub use self::span_query::{
+    Span, SpanContainingQuery, SpanFirstQuery, SpanNearQuery, SpanNotQuery, SpanOrQuery, SpanQuery,
+    SpanQueryClone, SpanQueryWeight, SpanScorer, SpanTermQuery, SpanWeight, Spans,
+};
 pub use self::term_query::TermQuery;
 pub use self::weight::Weight;
+pub use self::wildcard_query::WildcardQuery;
 pub use tantivy_query_grammar::Occur;
 
 #[cfg(test)]
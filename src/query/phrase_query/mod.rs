@@ -1,7 +1,14 @@
+mod multi_phrase_query;
+mod multi_phrase_weight;
+mod phrase_prefix_query;
 mod phrase_query;
 mod phrase_scorer;
 mod phrase_weight;
+mod union_postings;
 
+pub use self::multi_phrase_query::MultiPhraseQuery;
+pub use self::multi_phrase_weight::MultiPhraseWeight;
+pub use self::phrase_prefix_query::PhrasePrefixQuery;
 pub use self::phrase_query::PhraseQuery;
 pub use self::phrase_scorer::PhraseScorer;
 pub use self::phrase_weight::PhraseWeight;
@@ -12,9 +19,12 @@ pub mod tests {
     use super::*;
     use crate::assert_nearly_equals;
     use crate::collector::tests::{TEST_COLLECTOR_WITHOUT_SCORE, TEST_COLLECTOR_WITH_SCORE};
+    use crate::collector::Count;
     use crate::core::Index;
+    use crate::query::QueryParser;
     use crate::query::Weight;
-    use crate::schema::{Schema, Term, TEXT};
+    use crate::schema::{IndexRecordOption, Schema, Term, TextFieldIndexing, TextOptions, TEXT};
+    use crate::tokenizer::{SimpleTokenizer, SynonymFilter, SynonymMap, TextAnalyzer};
     use crate::DocId;
     use crate::{DocAddress, TERMINATED};
 
@@ -179,6 +189,65 @@ pub mod tests {
         assert_nearly_equals!(scores[1], 0.46844664);
     }
 
+    #[test]
+    pub fn test_phrase_query_slop() {
+        let index = create_index(&["a b c", "a x b", "a x y b", "b a", "a", "a a"]);
+        let text_field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let test_query = |texts: Vec<&str>, slop: u32, scoring_enabled: bool| {
+            let terms: Vec<Term> = texts
+                .iter()
+                .map(|text| Term::from_field_text(text_field, text))
+                .collect();
+            let mut phrase_query = PhraseQuery::new(terms);
+            phrase_query.set_slop(slop);
+            let collector = if scoring_enabled {
+                TEST_COLLECTOR_WITH_SCORE
+            } else {
+                TEST_COLLECTOR_WITHOUT_SCORE
+            };
+            searcher
+                .search(&phrase_query, &collector)
+                .expect("search should succeed")
+                .docs()
+                .iter()
+                .map(|doc_address| doc_address.doc_id)
+                .collect::<Vec<DocId>>()
+        };
+        for &scoring_enabled in &[true, false] {
+            assert_eq!(test_query(vec!["a", "b"], 0, scoring_enabled), vec![0]);
+            assert_eq!(test_query(vec!["a", "b"], 1, scoring_enabled), vec![0, 1]);
+            // Transposing two terms requires a slop of 2.
+            assert_eq!(
+                test_query(vec!["a", "b"], 2, scoring_enabled),
+                vec![0, 1, 2, 3]
+            );
+            assert_eq!(test_query(vec!["a", "b", "c"], 3, scoring_enabled), vec![0]);
+            // The terms of the phrase must match distinct positions.
+            assert_eq!(test_query(vec!["a", "a"], 2, scoring_enabled), vec![5]);
+        }
+    }
+
+    #[test]
+    pub fn test_phrase_score_slop() {
+        let index = create_index(&["a b x", "a x b", "x a b"]);
+        let text_field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let mut phrase_query = PhraseQuery::new(vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "b"),
+        ]);
+        phrase_query.set_slop(1);
+        let scores = searcher
+            .search(&phrase_query, &TEST_COLLECTOR_WITH_SCORE)
+            .expect("search should succeed")
+            .scores()
+            .to_vec();
+        assert_eq!(scores.len(), 3);
+        assert_nearly_equals!(scores[0], scores[2]);
+        assert!(scores[1] < scores[0]);
+    }
+
     #[test] // motivated by #234
     pub fn test_phrase_query_docfreq_order() {
         let mut schema_builder = Schema::builder();
@@ -246,4 +315,49 @@ pub mod tests {
         assert!(test_query(vec![(0, "a"), (2, "d")]).is_empty());
         assert_eq!(test_query(vec![(1, "a"), (3, "c")]), vec![0]);
     }
+
+    #[test]
+    pub fn test_phrase_query_index_time_synonyms() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("synonyms")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+        let text_field = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let synonym_map = SynonymMap::from_solr("ny, new york", true)?;
+        index.tokenizers().register(
+            "synonyms",
+            TextAnalyzer::from(SimpleTokenizer).filter(SynonymFilter::new(synonym_map)),
+        );
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text_field=>"ny city"));
+        index_writer.add_document(doc!(text_field=>"new york city"));
+        index_writer.add_document(doc!(text_field=>"york"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let test_query = |texts: Vec<(usize, &str)>| {
+            let terms: Vec<(usize, Term)> = texts
+                .iter()
+                .map(|(offset, text)| (*offset, Term::from_field_text(text_field, text)))
+                .collect();
+            searcher
+                .search(&PhraseQuery::new_with_offset(terms), &Count)
+                .unwrap()
+        };
+        // The synonyms are indexed as a graph of positions: `ny` spans the
+        // positions of `new` and `york`.
+        assert_eq!(test_query(vec![(0, "ny"), (2, "city")]), 2);
+        assert_eq!(test_query(vec![(0, "new"), (1, "york"), (2, "city")]), 2);
+        assert_eq!(test_query(vec![(0, "ny"), (0, "new"), (1, "york")]), 2);
+        assert_eq!(test_query(vec![(0, "ny"), (1, "city")]), 0);
+        let query_parser = QueryParser::for_index(&index, vec![text_field]);
+        let count =
+            |query: &str| searcher.search(&query_parser.parse_query(query).unwrap(), &Count);
+        assert_eq!(count("\"ny city\"")?, 2);
+        assert_eq!(count("\"new york city\"")?, 2);
+        assert_eq!(count("\"york city\"")?, 2);
+        Ok(())
+    }
 }
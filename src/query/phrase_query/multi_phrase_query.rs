@@ -0,0 +1,238 @@
+use std::collections::BTreeMap;
+
+use super::MultiPhraseWeight;
+use crate::core::searcher::Searcher;
+use crate::query::bm25::Bm25Weight;
+use crate::query::{Query, Weight};
+use crate::schema::IndexRecordOption;
+use crate::schema::{Field, Term};
+
+/// `MultiPhraseQuery` matches a phrase, each position of which accepts a set of terms.
+///
+/// For instance, the multi phrase query `"(quick|fast) brown fox"` matches
+/// both `quick brown fox` and `fast brown fox`. It is typically used to expand
+/// synonyms within a phrase at query time.
+///
+/// Like for a `PhraseQuery`, a slop can be set (see [`set_slop`](#method.set_slop)),
+/// and the field must have positions indexed.
+///
+/// ```rust
+/// use tantivy::collector::Count;
+/// use tantivy::query::MultiPhraseQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index, Term};
+///
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let schema = schema_builder.build();
+/// let index = Index::create_in_ram(schema);
+/// {
+///     let mut index_writer = index.writer(3_000_000)?;
+///     index_writer.add_document(doc!(title => "The quick brown fox"));
+///     index_writer.add_document(doc!(title => "A fast brown fox"));
+///     index_writer.add_document(doc!(title => "A fast red fox"));
+///     index_writer.commit()?;
+/// }
+/// let searcher = index.reader()?.searcher();
+/// let query = MultiPhraseQuery::new(vec![
+///     vec![
+///         Term::from_field_text(title, "quick"),
+///         Term::from_field_text(title, "fast"),
+///     ],
+///     vec![Term::from_field_text(title, "brown")],
+///     vec![Term::from_field_text(title, "fox")],
+/// ]);
+/// assert_eq!(searcher.search(&query, &Count)?, 2);
+/// # Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiPhraseQuery {
+    field: Field,
+    phrase_terms: Vec<(usize, Vec<Term>)>,
+    slop: u32,
+}
+
+impl MultiPhraseQuery {
+    /// Creates a new `MultiPhraseQuery` given the set of terms of each position.
+    ///
+    /// There must be at least two positions, none of them empty,
+    /// and all terms must belong to the same field.
+    pub fn new(terms: Vec<Vec<Term>>) -> MultiPhraseQuery {
+        let terms_with_offset = terms.into_iter().enumerate().collect();
+        MultiPhraseQuery::new_with_offset(terms_with_offset)
+    }
+
+    /// Creates a new `MultiPhraseQuery` given sets of terms and their offsets.
+    pub fn new_with_offset(mut terms: Vec<(usize, Vec<Term>)>) -> MultiPhraseQuery {
+        assert!(
+            terms.len() > 1,
+            "A multi phrase query is required to have strictly more than one position."
+        );
+        assert!(
+            terms.iter().all(|(_, terms)| !terms.is_empty()),
+            "Each position of a multi phrase query is required to have at least one term."
+        );
+        terms.sort_by_key(|&(offset, _)| offset);
+        let field = terms[0].1[0].field();
+        assert!(
+            terms
+                .iter()
+                .flat_map(|(_, terms)| terms)
+                .all(|term| term.field() == field),
+            "All terms from a multi phrase query must belong to the same field"
+        );
+        MultiPhraseQuery {
+            field,
+            phrase_terms: terms,
+            slop: 0,
+        }
+    }
+
+    /// Sets the slop of the phrase. See [`PhraseQuery::set_slop`](struct.PhraseQuery.html#method.set_slop).
+    pub fn set_slop(&mut self, slop: u32) {
+        self.slop = slop;
+    }
+
+    /// The slop of the phrase.
+    pub fn slop(&self) -> u32 {
+        self.slop
+    }
+
+    /// The `Field` this `MultiPhraseQuery` is targeting.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// The sets of `Term`s of the phrase, without the associated offsets.
+    pub fn phrase_terms(&self) -> Vec<Vec<Term>> {
+        self.phrase_terms
+            .iter()
+            .map(|(_, terms)| terms.clone())
+            .collect()
+    }
+
+    pub(crate) fn multi_phrase_weight(
+        &self,
+        searcher: &Searcher,
+        scoring_enabled: bool,
+    ) -> crate::Result<MultiPhraseWeight> {
+        let field_entry = searcher.schema().get_field_entry(self.field);
+        let has_positions = field_entry
+            .field_type()
+            .get_index_record_option()
+            .map(IndexRecordOption::has_positions)
+            .unwrap_or(false);
+        if !has_positions {
+            let field_name = field_entry.name();
+            return Err(crate::TantivyError::SchemaError(format!(
+                "Applied multi phrase query on field {:?}, which does not have positions indexed",
+                field_name
+            )));
+        }
+        let terms: Vec<Term> = self
+            .phrase_terms
+            .iter()
+            .flat_map(|(_, terms)| terms.iter().cloned())
+            .collect();
+        let bm25_weight = Bm25Weight::for_terms(searcher, &terms)?;
+        Ok(MultiPhraseWeight::new(
+            self.field,
+            self.phrase_terms.clone(),
+            bm25_weight,
+            scoring_enabled,
+            self.slop,
+        ))
+    }
+}
+
+impl Query for MultiPhraseQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        let multi_phrase_weight = self.multi_phrase_weight(searcher, scoring_enabled)?;
+        Ok(Box::new(multi_phrase_weight))
+    }
+
+    fn query_terms(&self, terms: &mut BTreeMap<Term, bool>) {
+        for (_, phrase_terms) in &self.phrase_terms {
+            for term in phrase_terms {
+                terms.insert(term.clone(), true);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::create_index;
+    use super::MultiPhraseQuery;
+    use crate::collector::{Count, TopDocs};
+    use crate::query::Query;
+    use crate::schema::{Schema, STRING};
+    use crate::{DocAddress, Index, Term};
+
+    fn multi_phrase_query(index: &Index, texts: &[&[&str]]) -> MultiPhraseQuery {
+        let text_field = index.schema().get_field("text").unwrap();
+        MultiPhraseQuery::new(
+            texts
+                .iter()
+                .map(|alternatives| {
+                    alternatives
+                        .iter()
+                        .map(|text| Term::from_field_text(text_field, text))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    pub fn test_multi_phrase_query() {
+        let index = create_index(&[
+            "the quick brown fox",
+            "a fast brown fox",
+            "a fast red fox",
+            "brown quick fox",
+            "quick fast brown fox",
+        ]);
+        let searcher = index.reader().unwrap().searcher();
+        let docs = |query: &MultiPhraseQuery| -> Vec<u32> {
+            let top_docs = searcher.search(query, &TopDocs::with_limit(10)).unwrap();
+            let mut docs: Vec<u32> = top_docs.iter().map(|(_, doc)| doc.doc_id).collect();
+            docs.sort_unstable();
+            docs
+        };
+        let query = multi_phrase_query(&index, &[&["quick", "fast"], &["brown"], &["fox"]]);
+        assert_eq!(docs(&query), vec![0, 1, 4]);
+        let query = multi_phrase_query(&index, &[&["quick", "fast"], &["brown", "red"], &["fox"]]);
+        assert_eq!(docs(&query), vec![0, 1, 2, 4]);
+        // Terms absent from the index do not prevent the others from matching.
+        let query = multi_phrase_query(&index, &[&["speedy", "fast"], &["red"]]);
+        assert_eq!(docs(&query), vec![2]);
+        let query = multi_phrase_query(&index, &[&["speedy"], &["red"]]);
+        assert!(docs(&query).is_empty());
+        let mut query = multi_phrase_query(&index, &[&["quick", "fast"], &["fox"]]);
+        assert_eq!(docs(&query), vec![3]);
+        query.set_slop(1);
+        assert_eq!(docs(&query), vec![0, 1, 2, 3, 4]);
+        let explanation = query.explain(&searcher, DocAddress::new(0, 1)).unwrap();
+        assert!(explanation.value() > 0.0);
+    }
+
+    #[test]
+    pub fn test_multi_phrase_query_no_positions() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let searcher = index.reader().unwrap().searcher();
+        let query = MultiPhraseQuery::new(vec![
+            vec![Term::from_field_text(text_field, "quick")],
+            vec![Term::from_field_text(text_field, "fox")],
+        ]);
+        assert!(matches!(
+            searcher.search(&query, &Count),
+            Err(crate::TantivyError::SchemaError(_))
+        ));
+    }
+}
@@ -0,0 +1,109 @@
+use super::union_postings::UnionPostings;
+use super::PhraseScorer;
+use crate::core::SegmentReader;
+use crate::fieldnorm::FieldNormReader;
+use crate::postings::SegmentPostings;
+use crate::query::bm25::Bm25Weight;
+use crate::query::explanation::does_not_match;
+use crate::query::{EmptyScorer, Explanation, Scorer, Weight};
+use crate::schema::{Field, IndexRecordOption, Term};
+use crate::Score;
+use crate::{DocId, DocSet};
+
+/// The `Weight` of a `MultiPhraseQuery`.
+pub struct MultiPhraseWeight {
+    field: Field,
+    phrase_terms: Vec<(usize, Vec<Term>)>,
+    similarity_weight: Bm25Weight,
+    scoring_enabled: bool,
+    slop: u32,
+}
+
+impl MultiPhraseWeight {
+    /// Creates a new multi phrase weight.
+    pub fn new(
+        field: Field,
+        phrase_terms: Vec<(usize, Vec<Term>)>,
+        similarity_weight: Bm25Weight,
+        scoring_enabled: bool,
+        slop: u32,
+    ) -> MultiPhraseWeight {
+        MultiPhraseWeight {
+            field,
+            phrase_terms,
+            similarity_weight,
+            scoring_enabled,
+            slop,
+        }
+    }
+
+    fn fieldnorm_reader(&self, reader: &SegmentReader) -> crate::Result<FieldNormReader> {
+        if self.scoring_enabled {
+            reader.get_fieldnorms_reader(self.field)
+        } else {
+            Ok(FieldNormReader::constant(reader.max_doc(), 1))
+        }
+    }
+
+    fn phrase_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: Score,
+    ) -> crate::Result<Option<PhraseScorer<UnionPostings<SegmentPostings>>>> {
+        let similarity_weight = self.similarity_weight.boost_by(boost);
+        let fieldnorm_reader = self.fieldnorm_reader(reader)?;
+        let inverted_index = reader.inverted_index(self.field)?;
+        let mut term_postings_list = Vec::with_capacity(self.phrase_terms.len());
+        for (offset, terms) in &self.phrase_terms {
+            let mut postings_list = Vec::with_capacity(terms.len());
+            for term in terms {
+                // Terms absent from the segment are skipped: any other term
+                // of the same position may still match.
+                let postings_opt = if reader.has_deletes() {
+                    inverted_index.read_postings(term, IndexRecordOption::WithFreqsAndPositions)?
+                } else {
+                    inverted_index
+                        .read_postings_no_deletes(term, IndexRecordOption::WithFreqsAndPositions)?
+                };
+                postings_list.extend(postings_opt);
+            }
+            if postings_list.is_empty() {
+                return Ok(None);
+            }
+            term_postings_list.push((*offset, UnionPostings::new(postings_list)));
+        }
+        Ok(Some(PhraseScorer::new(
+            term_postings_list,
+            similarity_weight,
+            fieldnorm_reader,
+            self.scoring_enabled,
+            self.slop,
+        )))
+    }
+}
+
+impl Weight for MultiPhraseWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        if let Some(scorer) = self.phrase_scorer(reader, boost)? {
+            Ok(Box::new(scorer))
+        } else {
+            Ok(Box::new(EmptyScorer))
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self
+            .phrase_scorer(reader, 1.0)?
+            .ok_or_else(|| does_not_match(doc))?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        let fieldnorm_id = self.fieldnorm_reader(reader)?.fieldnorm_id(doc);
+        let mut explanation = Explanation::new("Multi Phrase Scorer", scorer.score());
+        explanation.add_detail(
+            self.similarity_weight
+                .explain_freq(fieldnorm_id, scorer.phrase_freq()),
+        );
+        Ok(explanation)
+    }
+}
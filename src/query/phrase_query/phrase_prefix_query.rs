@@ -0,0 +1,263 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::MultiPhraseQuery;
+use crate::core::searcher::Searcher;
+use crate::query::{EmptyQuery, Query, Weight};
+use crate::schema::IndexRecordOption;
+use crate::schema::{Field, Term};
+
+/// Default maximum number of terms a `PhrasePrefixQuery` prefix expands to.
+const DEFAULT_MAX_EXPANSIONS: usize = 50;
+
+/// `PhrasePrefixQuery` matches a phrase whose last term is a prefix.
+///
+/// For instance, the phrase prefix `"new yor"` matches the documents
+/// containing the phrases `"new york"` or `"new yorker"`. It is typically
+/// used to search as the user types.
+///
+/// The prefix is expanded, at search time, to the terms of the term dictionary
+/// starting with it. Only the first `max_expansions` of them, in lexicographic
+/// order, are searched, as the alternatives of the last position of a
+/// [`MultiPhraseQuery`](./struct.MultiPhraseQuery.html).
+///
+/// The field must have positions indexed.
+#[derive(Clone, Debug)]
+pub struct PhrasePrefixQuery {
+    field: Field,
+    phrase_terms: Vec<(usize, Term)>,
+    prefix: (usize, Term),
+    max_expansions: usize,
+}
+
+impl PhrasePrefixQuery {
+    /// Creates a new `PhrasePrefixQuery` given a list of terms,
+    /// the last of which is the prefix.
+    ///
+    /// There must be at least two terms, and all terms
+    /// must belong to the same field.
+    pub fn new(mut terms: Vec<Term>) -> PhrasePrefixQuery {
+        assert!(
+            terms.len() > 1,
+            "A phrase prefix query is required to have strictly more than one term."
+        );
+        let prefix = terms.pop().unwrap();
+        let field = prefix.field();
+        assert!(
+            terms.iter().all(|term| term.field() == field),
+            "All terms from a phrase prefix query must belong to the same field"
+        );
+        PhrasePrefixQuery {
+            field,
+            prefix: (terms.len(), prefix),
+            phrase_terms: terms.into_iter().enumerate().collect(),
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+        }
+    }
+
+    /// Sets the maximum number of terms the prefix expands to. Defaults to 50.
+    pub fn set_max_expansions(&mut self, max_expansions: usize) {
+        self.max_expansions = max_expansions;
+    }
+
+    /// Maximum number of terms the prefix expands to.
+    pub fn max_expansions(&self) -> usize {
+        self.max_expansions
+    }
+
+    /// The `Field` this `PhrasePrefixQuery` is targeting.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// The prefix of the last term of the phrase.
+    pub fn prefix(&self) -> &Term {
+        &self.prefix.1
+    }
+
+    /// The terms of the phrase, preceding the prefix.
+    pub fn phrase_terms(&self) -> Vec<Term> {
+        self.phrase_terms
+            .iter()
+            .map(|(_, term)| term.clone())
+            .collect::<Vec<Term>>()
+    }
+
+    /// Returns the terms starting with the prefix, among the first `max_expansions`
+    /// ones in the term dictionaries of the segments.
+    fn expand_prefix(&self, searcher: &Searcher) -> crate::Result<Vec<Term>> {
+        let prefix_bytes = self.prefix.1.value_bytes();
+        let mut expansions = BTreeSet::new();
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(self.field)?;
+            let mut term_stream = inverted_index
+                .terms()
+                .range()
+                .ge(prefix_bytes)
+                .into_stream()?;
+            let mut num_expansions = 0;
+            while num_expansions < self.max_expansions && term_stream.advance() {
+                let key = term_stream.key();
+                if !key.starts_with(prefix_bytes) {
+                    break;
+                }
+                expansions.insert(key.to_vec());
+                num_expansions += 1;
+            }
+        }
+        Ok(expansions
+            .into_iter()
+            .take(self.max_expansions)
+            .map(|key| Term::from_field_bytes(self.field, &key))
+            .collect())
+    }
+
+    /// Returns the `MultiPhraseQuery` searching the expansions of the prefix,
+    /// or `None` if the prefix does not expand to any term.
+    fn multi_phrase_query(&self, searcher: &Searcher) -> crate::Result<Option<MultiPhraseQuery>> {
+        let expansions = self.expand_prefix(searcher)?;
+        if expansions.is_empty() {
+            return Ok(None);
+        }
+        let mut terms: Vec<(usize, Vec<Term>)> = self
+            .phrase_terms
+            .iter()
+            .map(|(offset, term)| (*offset, vec![term.clone()]))
+            .collect();
+        terms.push((self.prefix.0, expansions));
+        Ok(Some(MultiPhraseQuery::new_with_offset(terms)))
+    }
+}
+
+impl Query for PhrasePrefixQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        let field_entry = searcher.schema().get_field_entry(self.field);
+        let has_positions = field_entry
+            .field_type()
+            .get_index_record_option()
+            .map(IndexRecordOption::has_positions)
+            .unwrap_or(false);
+        if !has_positions {
+            let field_name = field_entry.name();
+            return Err(crate::TantivyError::SchemaError(format!(
+                "Applied phrase prefix query on field {:?}, which does not have positions indexed",
+                field_name
+            )));
+        }
+        match self.multi_phrase_query(searcher)? {
+            Some(multi_phrase_query) => multi_phrase_query.weight(searcher, scoring_enabled),
+            None => EmptyQuery.weight(searcher, scoring_enabled),
+        }
+    }
+
+    fn query_terms(&self, terms: &mut BTreeMap<Term, bool>) {
+        for (_, term) in &self.phrase_terms {
+            terms.insert(term.clone(), true);
+        }
+    }
+
+    fn query_terms_with_searcher(
+        &self,
+        searcher: &Searcher,
+        terms: &mut BTreeMap<Term, bool>,
+    ) -> crate::Result<()> {
+        match self.multi_phrase_query(searcher)? {
+            Some(multi_phrase_query) => multi_phrase_query.query_terms(terms),
+            None => self.query_terms(terms),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::create_index;
+    use super::PhrasePrefixQuery;
+    use crate::collector::{Count, TopDocs};
+    use crate::query::Query;
+    use crate::schema::{Schema, STRING};
+    use crate::{DocAddress, Index, SnippetGenerator, Term};
+    use std::collections::BTreeMap;
+
+    fn phrase_prefix_query(index: &Index, texts: &[&str]) -> PhrasePrefixQuery {
+        let text_field = index.schema().get_field("text").unwrap();
+        PhrasePrefixQuery::new(
+            texts
+                .iter()
+                .map(|text| Term::from_field_text(text_field, text))
+                .collect(),
+        )
+    }
+
+    #[test]
+    pub fn test_phrase_prefix_query() {
+        let index = create_index(&[
+            "new york",
+            "a new yorker",
+            "new jersey",
+            "yorkshire new",
+            "new young york",
+        ]);
+        let searcher = index.reader().unwrap().searcher();
+        let docs = |query: &PhrasePrefixQuery| -> Vec<u32> {
+            let top_docs = searcher.search(query, &TopDocs::with_limit(10)).unwrap();
+            let mut docs: Vec<u32> = top_docs.iter().map(|(_, doc)| doc.doc_id).collect();
+            docs.sort_unstable();
+            docs
+        };
+        assert_eq!(
+            docs(&phrase_prefix_query(&index, &["new", "yor"])),
+            vec![0, 1]
+        );
+        assert_eq!(
+            docs(&phrase_prefix_query(&index, &["new", "yo"])),
+            vec![0, 1, 4]
+        );
+        assert_eq!(
+            docs(&phrase_prefix_query(&index, &["new", "york"])),
+            vec![0, 1]
+        );
+        assert!(docs(&phrase_prefix_query(&index, &["new", "yorkers"])).is_empty());
+        assert!(docs(&phrase_prefix_query(&index, &["old", "yor"])).is_empty());
+        // With a single expansion, only "york" is searched.
+        let mut query = phrase_prefix_query(&index, &["new", "yor"]);
+        query.set_max_expansions(1);
+        assert_eq!(docs(&query), vec![0]);
+        let explanation = query.explain(&searcher, DocAddress::new(0, 0)).unwrap();
+        assert!(explanation.value() > 0.0);
+    }
+
+    #[test]
+    pub fn test_phrase_prefix_query_terms() -> crate::Result<()> {
+        let index = create_index(&["new york", "a new yorker", "yorkshire"]);
+        let text_field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader()?.searcher();
+        let query = phrase_prefix_query(&index, &["new", "yor"]);
+        let mut terms = BTreeMap::new();
+        query.query_terms_with_searcher(&searcher, &mut terms)?;
+        let terms: Vec<&str> = terms.keys().map(Term::text).collect();
+        assert_eq!(terms, vec!["new", "york", "yorker", "yorkshire"]);
+        // The expansions are highlighted in the snippets.
+        let snippet_generator = SnippetGenerator::create(&searcher, &query, text_field)?;
+        assert_eq!(
+            snippet_generator.snippet("a new yorker").to_html(),
+            "a <b>new</b> <b>yorker</b>"
+        );
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_phrase_prefix_query_no_positions() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let searcher = index.reader().unwrap().searcher();
+        let query = PhrasePrefixQuery::new(vec![
+            Term::from_field_text(text_field, "new"),
+            Term::from_field_text(text_field, "yor"),
+        ]);
+        assert!(matches!(
+            searcher.search(&query, &Count),
+            Err(crate::TantivyError::SchemaError(_))
+        ));
+    }
+}
@@ -19,6 +19,9 @@ use crate::schema::{Field, Term};
 ///
 /// **This is my favorite part of the job.**
 ///
+/// A slop can be set to match phrases whose terms are not exactly at their expected
+/// positions (see [`set_slop`](#method.set_slop)).
+///
 /// Using a `PhraseQuery` on a field requires positions
 /// to be indexed for this field.
 ///
@@ -26,6 +29,7 @@ use crate::schema::{Field, Term};
 pub struct PhraseQuery {
     field: Field,
     phrase_terms: Vec<(usize, Term)>,
+    slop: u32,
 }
 
 impl PhraseQuery {
@@ -42,6 +46,8 @@ impl PhraseQuery {
     /// Creates a new `PhraseQuery` given a list of terms and their offsets.
     ///
     /// Can be used to provide custom offset for each term.
+    /// Several terms may share the same offset, in which case
+    /// all of them must be found at the corresponding position.
     pub fn new_with_offset(mut terms: Vec<(usize, Term)>) -> PhraseQuery {
         assert!(
             terms.len() > 1,
@@ -56,9 +62,27 @@ impl PhraseQuery {
         PhraseQuery {
             field,
             phrase_terms: terms,
+            slop: 0,
         }
     }
 
+    /// Sets the slop of the phrase, i.e. the maximum number of position moves
+    /// needed to turn a match into the exact phrase.
+    ///
+    /// For instance, with a slop of 1, `"quick fox"` matches `quick brown fox`.
+    /// Transposing two terms requires a slop of 2: `"fox quick"~2` matches `quick fox`.
+    ///
+    /// The closer the terms of a match are to the exact phrase, the higher its score.
+    /// The slop defaults to 0, matching the exact phrase only.
+    pub fn set_slop(&mut self, slop: u32) {
+        self.slop = slop;
+    }
+
+    /// The slop of the phrase.
+    pub fn slop(&self) -> u32 {
+        self.slop
+    }
+
     /// The `Field` this `PhraseQuery` is targeting.
     pub fn field(&self) -> Field {
         self.field
@@ -101,6 +125,7 @@ impl PhraseQuery {
             self.phrase_terms.clone(),
             bm25_weight,
             scoring_enabled,
+            self.slop,
         ))
     }
 }
@@ -47,7 +47,13 @@ pub struct PhraseScorer<TPostings: Postings> {
     num_terms: usize,
     left: Vec<u32>,
     right: Vec<u32>,
+    slop: u32,
+    // Positions and offsets of each term, for sloppy phrases.
+    positions: Vec<Vec<u32>>,
+    offsets: Vec<u32>,
+    cursors: Vec<usize>,
     phrase_count: u32,
+    phrase_freq: Score,
     fieldnorm_reader: FieldNormReader,
     similarity_weight: Bm25Weight,
     scoring_enabled: bool,
@@ -99,6 +105,93 @@ fn intersection_count(left: &[u32], right: &[u32]) -> usize {
     count
 }
 
+/// Returns the number of sloppy matches of a phrase, and its sloppy frequency.
+///
+/// `positions` contains the sorted positions of each term, shifted by `offsets` so that
+/// the terms of an exact match share the same shifted position. The match length of a
+/// set of positions, one per term, is the difference between its largest and smallest
+/// shifted positions: a term moved by one position, or two transposed terms, have a match
+/// length of 1 and 2 respectively. Terms at different offsets must match distinct positions.
+///
+/// Each match whose length is at most `slop` contributes `1 / (1 + match_length)` to the
+/// sloppy frequency, as in Lucene's `SloppyPhraseScorer`.
+fn sloppy_phrase_freq(
+    positions: &[Vec<u32>],
+    offsets: &[u32],
+    slop: u32,
+    cursors: &mut Vec<usize>,
+) -> (u32, Score) {
+    let num_terms = positions.len();
+    let position = |cursors: &[usize], ord: usize| positions[ord][cursors[ord]];
+    let collides = |cursors: &[usize], ord: usize| {
+        let unshifted_position = position(cursors, ord) - offsets[ord];
+        (0..num_terms).any(|other| {
+            offsets[other] != offsets[ord]
+                && cursors[other] < positions[other].len()
+                && position(cursors, other) - offsets[other] == unshifted_position
+        })
+    };
+    // Moves the cursor of a term to its next position not colliding with another term.
+    let advance = |cursors: &mut Vec<usize>, ord: usize| loop {
+        cursors[ord] += 1;
+        if cursors[ord] >= positions[ord].len() {
+            return false;
+        }
+        if !collides(cursors, ord) {
+            return true;
+        }
+    };
+    cursors.clear();
+    cursors.resize(num_terms, 0);
+    for ord in 0..num_terms {
+        if collides(cursors, ord) && !advance(cursors, ord) {
+            return (0, 0.0);
+        }
+    }
+    let min_term = |cursors: &[usize]| {
+        (0..num_terms)
+            .min_by_key(|&ord| position(cursors, ord))
+            .unwrap()
+    };
+    let slop_factor = |match_length: u32| 1.0 / (1.0 + match_length as Score);
+    let mut end = (0..num_terms)
+        .map(|ord| position(cursors, ord))
+        .max()
+        .unwrap_or(0);
+    let mut count = 0u32;
+    let mut freq: Score = 0.0;
+    let mut first = min_term(cursors);
+    let mut match_length = end - position(cursors, first);
+    loop {
+        let next = (0..num_terms)
+            .filter(|&ord| ord != first)
+            .map(|ord| position(cursors, ord))
+            .min()
+            .unwrap_or(u32::MAX);
+        if !advance(cursors, first) {
+            break;
+        }
+        let first_position = position(cursors, first);
+        end = end.max(first_position);
+        if first_position > next {
+            // Another term is now the first one of the match.
+            if match_length <= slop {
+                count += 1;
+                freq += slop_factor(match_length);
+            }
+            first = min_term(cursors);
+            match_length = end - position(cursors, first);
+        } else {
+            match_length = match_length.min(end - first_position);
+        }
+    }
+    if match_length <= slop {
+        count += 1;
+        freq += slop_factor(match_length);
+    }
+    (count, freq)
+}
+
 /// Intersect twos sorted arrays `left` and `right` and outputs the
 /// resulting array in left.
 ///
@@ -136,6 +229,7 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
         similarity_weight: Bm25Weight,
         fieldnorm_reader: FieldNormReader,
         scoring_enabled: bool,
+        slop: u32,
     ) -> PhraseScorer<TPostings> {
         let max_offset = term_postings
             .iter()
@@ -149,12 +243,21 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
                 PostingsWithOffset::new(postings, (max_offset - offset) as u32)
             })
             .collect::<Vec<_>>();
+        let mut intersection_docset = Intersection::new(postings_with_offsets);
+        let offsets = (0..num_docsets)
+            .map(|ord| intersection_docset.docset_mut_specialized(ord).offset)
+            .collect();
         let mut scorer = PhraseScorer {
-            intersection_docset: Intersection::new(postings_with_offsets),
+            intersection_docset,
             num_terms: num_docsets,
             left: Vec::with_capacity(100),
             right: Vec::with_capacity(100),
+            slop,
+            positions: vec![Vec::new(); num_docsets],
+            offsets,
+            cursors: Vec::with_capacity(num_docsets),
             phrase_count: 0u32,
+            phrase_freq: 0.0,
             similarity_weight,
             fieldnorm_reader,
             scoring_enabled,
@@ -165,14 +268,30 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
         scorer
     }
 
-    pub fn phrase_count(&self) -> u32 {
+    /// Returns the number of matches of the phrase in the current document.
+    #[cfg(test)]
+    pub(crate) fn phrase_count(&self) -> u32 {
         self.phrase_count
     }
 
+    /// Returns the frequency of the phrase used for scoring the current document.
+    ///
+    /// For an exact phrase, this is the number of matches. For a sloppy phrase,
+    /// each match is weighted by the inverse of its length.
+    pub fn phrase_freq(&self) -> Score {
+        self.phrase_freq
+    }
+
     fn phrase_match(&mut self) -> bool {
-        if self.scoring_enabled {
+        if self.slop > 0 {
+            let (count, freq) = self.compute_sloppy_phrase_freq();
+            self.phrase_count = count;
+            self.phrase_freq = freq;
+            count > 0u32
+        } else if self.scoring_enabled {
             let count = self.compute_phrase_count();
             self.phrase_count = count;
+            self.phrase_freq = count as Score;
             count > 0u32
         } else {
             self.phrase_exists()
@@ -202,6 +321,15 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
         intersection_exists(&self.left[..intersection_len], &self.right[..])
     }
 
+    fn compute_sloppy_phrase_freq(&mut self) -> (u32, Score) {
+        for (ord, positions) in self.positions.iter_mut().enumerate() {
+            self.intersection_docset
+                .docset_mut_specialized(ord)
+                .positions(positions);
+        }
+        sloppy_phrase_freq(&self.positions, &self.offsets, self.slop, &mut self.cursors)
+    }
+
     fn compute_phrase_count(&mut self) -> u32 {
         {
             self.intersection_docset
@@ -261,7 +389,7 @@ impl<TPostings: Postings> Scorer for PhraseScorer<TPostings> {
         let doc = self.doc();
         let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(doc);
         self.similarity_weight
-            .score(fieldnorm_id, self.phrase_count)
+            .score_freq(fieldnorm_id, self.phrase_freq)
     }
 }
 
@@ -16,6 +16,7 @@ pub struct PhraseWeight {
     phrase_terms: Vec<(usize, Term)>,
     similarity_weight: Bm25Weight,
     scoring_enabled: bool,
+    slop: u32,
 }
 
 impl PhraseWeight {
@@ -24,11 +25,13 @@ impl PhraseWeight {
         phrase_terms: Vec<(usize, Term)>,
         similarity_weight: Bm25Weight,
         scoring_enabled: bool,
+        slop: u32,
     ) -> PhraseWeight {
         PhraseWeight {
             phrase_terms,
             similarity_weight,
             scoring_enabled,
+            slop,
         }
     }
 
@@ -65,6 +68,7 @@ impl PhraseWeight {
                 similarity_weight,
                 fieldnorm_reader,
                 self.scoring_enabled,
+                self.slop,
             )))
         } else {
             let mut term_postings_list = Vec::new();
@@ -83,6 +87,7 @@ impl PhraseWeight {
                 similarity_weight,
                 fieldnorm_reader,
                 self.scoring_enabled,
+                self.slop,
             )))
         }
     }
@@ -108,9 +113,12 @@ impl Weight for PhraseWeight {
         }
         let fieldnorm_reader = self.fieldnorm_reader(reader)?;
         let fieldnorm_id = fieldnorm_reader.fieldnorm_id(doc);
-        let phrase_count = scorer.phrase_count();
+        let phrase_freq = scorer.phrase_freq();
         let mut explanation = Explanation::new("Phrase Scorer", scorer.score());
-        explanation.add_detail(self.similarity_weight.explain(fieldnorm_id, phrase_count));
+        explanation.add_detail(
+            self.similarity_weight
+                .explain_freq(fieldnorm_id, phrase_freq),
+        );
         Ok(explanation)
     }
 }
@@ -118,6 +126,7 @@ impl Weight for PhraseWeight {
 #[cfg(test)]
 mod tests {
     use super::super::tests::create_index;
+    use crate::assert_nearly_equals;
     use crate::docset::TERMINATED;
     use crate::query::PhraseQuery;
     use crate::{DocSet, Term};
@@ -144,4 +153,33 @@ mod tests {
         assert_eq!(phrase_scorer.phrase_count(), 1);
         assert_eq!(phrase_scorer.advance(), TERMINATED);
     }
+
+    #[test]
+    pub fn test_phrase_freq_slop() {
+        let index = create_index(&["a c b", "a b d a c c b", "b a"]);
+        let schema = index.schema();
+        let text_field = schema.get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let mut phrase_query = PhraseQuery::new(vec![
+            Term::from_field_text(text_field, "a"),
+            Term::from_field_text(text_field, "b"),
+        ]);
+        phrase_query.set_slop(2);
+        let phrase_weight = phrase_query.phrase_weight(&searcher, true).unwrap();
+        let mut phrase_scorer = phrase_weight
+            .phrase_scorer(searcher.segment_reader(0u32), 1.0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(phrase_scorer.doc(), 0);
+        assert_eq!(phrase_scorer.phrase_count(), 1);
+        assert_nearly_equals!(phrase_scorer.phrase_freq(), 0.5);
+        assert_eq!(phrase_scorer.advance(), 1);
+        // `a b` and `a c c b`, whose match length is 2.
+        assert_eq!(phrase_scorer.phrase_count(), 2);
+        assert_nearly_equals!(phrase_scorer.phrase_freq(), 1.0 + 1.0 / 3.0);
+        assert_eq!(phrase_scorer.advance(), 2);
+        assert_eq!(phrase_scorer.phrase_count(), 1);
+        assert_nearly_equals!(phrase_scorer.phrase_freq(), 1.0 / 3.0);
+        assert_eq!(phrase_scorer.advance(), TERMINATED);
+    }
 }
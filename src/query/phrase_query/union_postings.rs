@@ -0,0 +1,95 @@
+use crate::docset::{DocSet, TERMINATED};
+use crate::postings::Postings;
+use crate::DocId;
+
+/// Union of the postings of several terms, searched at the same position of a phrase.
+///
+/// A document is matched if any of the terms appears in it, and its positions
+/// are the merged positions of these terms.
+pub(crate) struct UnionPostings<TPostings: Postings> {
+    postings: Vec<TPostings>,
+    doc: DocId,
+    buffer: Vec<u32>,
+}
+
+impl<TPostings: Postings> UnionPostings<TPostings> {
+    pub(crate) fn new(postings: Vec<TPostings>) -> UnionPostings<TPostings> {
+        let mut union = UnionPostings {
+            postings,
+            doc: TERMINATED,
+            buffer: Vec::new(),
+        };
+        union.update_doc();
+        union
+    }
+
+    fn update_doc(&mut self) -> DocId {
+        self.doc = self
+            .postings
+            .iter()
+            .map(|postings| postings.doc())
+            .min()
+            .unwrap_or(TERMINATED);
+        self.doc
+    }
+}
+
+impl<TPostings: Postings> DocSet for UnionPostings<TPostings> {
+    fn advance(&mut self) -> DocId {
+        let doc = self.doc;
+        for postings in &mut self.postings {
+            if postings.doc() == doc {
+                postings.advance();
+            }
+        }
+        self.update_doc()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        for postings in &mut self.postings {
+            if postings.doc() < target {
+                postings.seek(target);
+            }
+        }
+        self.update_doc()
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings
+            .iter()
+            .map(|postings| postings.size_hint())
+            .sum()
+    }
+}
+
+impl<TPostings: Postings> Postings for UnionPostings<TPostings> {
+    /// Returns the sum of the term frequencies of the terms in the current document.
+    fn term_freq(&self) -> u32 {
+        let doc = self.doc;
+        self.postings
+            .iter()
+            .filter(|postings| postings.doc() == doc)
+            .map(|postings| postings.term_freq())
+            .sum()
+    }
+
+    /// Returns the sorted positions of the terms in the current document.
+    ///
+    /// A position shared by several terms is only returned once.
+    fn positions_with_offset(&mut self, offset: u32, output: &mut Vec<u32>) {
+        output.clear();
+        let doc = self.doc;
+        for postings in &mut self.postings {
+            if postings.doc() == doc {
+                postings.positions_with_offset(offset, &mut self.buffer);
+                output.extend_from_slice(&self.buffer);
+            }
+        }
+        output.sort_unstable();
+        output.dedup();
+    }
+}
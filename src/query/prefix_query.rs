@@ -0,0 +1,155 @@
+use crate::query::{AutomatonWeight, Query, Weight};
+use crate::schema::{Field, Term};
+use crate::Searcher;
+use tantivy_fst::Automaton;
+
+/// Automaton matching the keys starting with a given prefix.
+///
+/// Its state is the length of the prefix matched so far,
+/// or `None` once a byte departed from the prefix.
+#[derive(Debug)]
+pub(crate) struct PrefixAutomaton {
+    prefix: Vec<u8>,
+}
+
+impl PrefixAutomaton {
+    pub(crate) fn new(prefix: Vec<u8>) -> PrefixAutomaton {
+        PrefixAutomaton { prefix }
+    }
+}
+
+impl Automaton for PrefixAutomaton {
+    type State = Option<usize>;
+
+    fn start(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn is_match(&self, state: &Option<usize>) -> bool {
+        *state == Some(self.prefix.len())
+    }
+
+    fn can_match(&self, state: &Option<usize>) -> bool {
+        state.is_some()
+    }
+
+    fn will_always_match(&self, state: &Option<usize>) -> bool {
+        self.is_match(state)
+    }
+
+    fn accept(&self, state: &Option<usize>, byte: u8) -> Option<usize> {
+        match *state {
+            Some(len) if len == self.prefix.len() => Some(len),
+            Some(len) if self.prefix[len] == byte => Some(len + 1),
+            _ => None,
+        }
+    }
+}
+
+/// A Prefix Query matches all of the documents
+/// containing a term starting with a given prefix.
+///
+/// Like the `RegexQuery`, all of the matching documents get a score of 1.0.
+///
+/// ```rust
+/// use tantivy::collector::Count;
+/// use tantivy::query::PrefixQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index, Term};
+///
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let schema = schema_builder.build();
+/// let index = Index::create_in_ram(schema);
+/// {
+///     let mut index_writer = index.writer(3_000_000)?;
+///     index_writer.add_document(doc!(title => "Computer Science"));
+///     index_writer.add_document(doc!(title => "Compilers"));
+///     index_writer.add_document(doc!(title => "Company Law"));
+///     index_writer.add_document(doc!(title => "Cooking"));
+///     index_writer.commit()?;
+/// }
+/// let searcher = index.reader()?.searcher();
+/// let query = PrefixQuery::new(Term::from_field_text(title, "comp"));
+/// assert_eq!(searcher.search(&query, &Count)?, 3);
+/// # Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct PrefixQuery {
+    prefix: Term,
+}
+
+impl PrefixQuery {
+    /// Creates a new `PrefixQuery` given the prefix of the terms to match.
+    pub fn new(prefix: Term) -> PrefixQuery {
+        PrefixQuery { prefix }
+    }
+
+    /// Returns the prefix of the terms to match.
+    pub fn prefix(&self) -> &Term {
+        &self.prefix
+    }
+
+    /// Returns the field of the query.
+    pub fn field(&self) -> Field {
+        self.prefix.field()
+    }
+
+    fn specialized_weight(&self) -> AutomatonWeight<PrefixAutomaton> {
+        let automaton = PrefixAutomaton::new(self.prefix.value_bytes().to_vec());
+        AutomatonWeight::new(self.prefix.field(), automaton)
+    }
+}
+
+impl Query for PrefixQuery {
+    fn weight(
+        &self,
+        _searcher: &Searcher,
+        _scoring_enabled: bool,
+    ) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(self.specialized_weight()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PrefixQuery;
+    use crate::assert_nearly_equals;
+    use crate::collector::TopDocs;
+    use crate::schema::{Schema, STRING, TEXT};
+    use crate::{DocAddress, Index, Term};
+
+    #[test]
+    pub fn test_prefix_query() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let country = schema_builder.add_text_field("country", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_for_tests().unwrap();
+            index_writer.add_document(doc!(title => "Computer", country => "Japan"));
+            index_writer.add_document(doc!(title => "Compact", country => "Jamaica"));
+            index_writer.add_document(doc!(title => "Cooking", country => "Korea"));
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let search = |term: Term| {
+            let query = PrefixQuery::new(term);
+            searcher.search(&query, &TopDocs::with_limit(3)).unwrap()
+        };
+        let top_docs = search(Term::from_field_text(title, "comp"));
+        assert_eq!(top_docs.len(), 2);
+        assert_nearly_equals!(top_docs[0].0, 1.0);
+        assert_eq!(search(Term::from_field_text(title, "co")).len(), 3);
+        assert!(search(Term::from_field_text(title, "computers")).is_empty());
+        // The prefix is matched against the terms, which are lowercased by `TEXT`.
+        assert!(search(Term::from_field_text(title, "Comp")).is_empty());
+        let top_docs = search(Term::from_field_text(country, "Jap"));
+        assert_eq!(top_docs, vec![(1.0, DocAddress::new(0, 0))]);
+        // The empty prefix matches all of the documents containing the field.
+        assert_eq!(search(Term::from_field_text(country, "")).len(), 3);
+    }
+}
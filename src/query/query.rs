@@ -72,6 +72,21 @@ pub trait Query: QueryClone + Send + Sync + downcast_rs::Downcast + fmt::Debug {
     /// Each term is associated with a boolean indicating whether
     /// Positions are required or not.
     fn query_terms(&self, _term_set: &mut BTreeMap<Term, bool>) {}
+
+    /// Extract all of the terms associated to the query, given the index of
+    /// the searcher, and insert them in the term set given in arguments.
+    ///
+    /// This is the same as `query_terms`, except for the queries whose terms
+    /// depend on the index, e.g. the `PhrasePrefixQuery` also inserts the
+    /// terms its prefix expands to.
+    fn query_terms_with_searcher(
+        &self,
+        _searcher: &Searcher,
+        term_set: &mut BTreeMap<Term, bool>,
+    ) -> crate::Result<()> {
+        self.query_terms(term_set);
+        Ok(())
+    }
 }
 
 /// Implements `box_clone`.
@@ -101,6 +116,14 @@ impl Query for Box<dyn Query> {
     fn query_terms(&self, terms: &mut BTreeMap<Term, bool>) {
         self.as_ref().query_terms(terms);
     }
+
+    fn query_terms_with_searcher(
+        &self,
+        searcher: &Searcher,
+        terms: &mut BTreeMap<Term, bool>,
+    ) -> crate::Result<()> {
+        self.as_ref().query_terms_with_searcher(searcher, terms)
+    }
 }
 
 impl QueryClone for Box<dyn Query> {
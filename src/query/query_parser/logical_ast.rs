@@ -1,21 +1,36 @@
-use crate::query::Occur;
+use crate::query::{Occur, WildcardQuery};
 use crate::schema::Field;
 use crate::schema::Term;
 use crate::schema::Type;
 use crate::Score;
 use std::fmt;
 use std::ops::Bound;
+use std::sync::Arc;
+use tantivy_fst::Regex;
 
 #[derive(Clone)]
 pub enum LogicalLiteral {
     Term(Term),
-    Phrase(Vec<(usize, Term)>),
+    Phrase(Vec<(usize, Term)>, u32),
+    MultiPhrase(Vec<(usize, Vec<Term>)>, u32),
     Range {
         field: Field,
         value_type: Type,
         lower: Bound<Term>,
         upper: Bound<Term>,
     },
+    Prefix(Term),
+    Wildcard(WildcardQuery),
+    Fuzzy {
+        term: Term,
+        distance: u8,
+        prefix: bool,
+    },
+    Regex {
+        field: Field,
+        pattern: String,
+        regex: Arc<Regex>,
+    },
     All,
 }
 
@@ -75,12 +90,48 @@ impl fmt::Debug for LogicalLiteral {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         match *self {
             LogicalLiteral::Term(ref term) => write!(formatter, "{:?}", term),
-            LogicalLiteral::Phrase(ref terms) => write!(formatter, "\"{:?}\"", terms),
+            LogicalLiteral::Phrase(ref terms, slop) => {
+                write!(formatter, "\"{:?}\"", terms)?;
+                if slop > 0 {
+                    write!(formatter, "~{}", slop)?;
+                }
+                Ok(())
+            }
+            LogicalLiteral::MultiPhrase(ref terms, slop) => {
+                write!(formatter, "\"{:?}\"", terms)?;
+                if slop > 0 {
+                    write!(formatter, "~{}", slop)?;
+                }
+                Ok(())
+            }
             LogicalLiteral::Range {
                 ref lower,
                 ref upper,
                 ..
             } => write!(formatter, "({:?} TO {:?})", lower, upper),
+            LogicalLiteral::Prefix(ref prefix) => write!(formatter, "{:?}*", prefix),
+            LogicalLiteral::Wildcard(ref query) => write!(
+                formatter,
+                "Wildcard(field={},pattern={:?})",
+                query.field().field_id(),
+                query.pattern()
+            ),
+            LogicalLiteral::Fuzzy {
+                ref term,
+                distance,
+                prefix,
+            } => {
+                let prefix_marker = if prefix { "*" } else { "" };
+                write!(formatter, "{:?}{}~{}", term, prefix_marker, distance)
+            }
+            LogicalLiteral::Regex {
+                field, ref pattern, ..
+            } => write!(
+                formatter,
+                "Regex(field={},pattern={:?})",
+                field.field_id(),
+                pattern
+            ),
             LogicalLiteral::All => write!(formatter, "*"),
         }
     }
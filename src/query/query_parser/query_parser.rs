@@ -1,23 +1,30 @@
 use super::logical_ast::*;
-use crate::core::Index;
+use crate::core::{Index, Searcher};
 use crate::query::BooleanQuery;
 use crate::query::EmptyQuery;
+use crate::query::FuzzyTermQuery;
+use crate::query::MultiPhraseQuery;
 use crate::query::Occur;
 use crate::query::PhraseQuery;
+use crate::query::PrefixQuery;
 use crate::query::Query;
 use crate::query::RangeQuery;
+use crate::query::RegexQuery;
 use crate::query::TermQuery;
+use crate::query::WildcardQuery;
 use crate::query::{AllQuery, BoostQuery};
 use crate::schema::{Facet, FacetParseError, IndexRecordOption};
 use crate::schema::{Field, Schema};
 use crate::schema::{FieldType, Term};
-use crate::tokenizer::TokenizerManager;
+use crate::tokenizer::{TextAnalyzer, TokenizerManager};
 use crate::Score;
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::num::{ParseFloatError, ParseIntError};
 use std::ops::Bound;
 use std::str::FromStr;
+use std::sync::Arc;
+use tantivy_fst::Regex;
 use tantivy_query_grammar::{UserInputAst, UserInputBound, UserInputLeaf};
 
 /// Possible error that may happen when parsing a query.
@@ -71,6 +78,16 @@ pub enum QueryParserError {
     /// The format for the facet field is invalid.
     #[error("The facet field is malformed: {0}")]
     FacetFormatError(FacetParseError),
+    /// A wildcard, fuzzy or regex query was requested for a field that is not a text field.
+    #[error("The field '{0:?}' is not a text field")]
+    FieldNotText(String),
+    /// The Levenshtein distance of a fuzzy term is greater than 2.
+    #[error("The Levenshtein distance of a fuzzy term must be at most 2, got {0}")]
+    InvalidFuzzyDistance(u8),
+    /// The regular expression or the wildcard pattern of the query is invalid,
+    /// or too complex.
+    #[error("Invalid regular expression: '{0:?}'")]
+    InvalidRegex(String),
 }
 
 impl From<ParseIntError> for QueryParserError {
@@ -160,12 +177,28 @@ fn trim_ast(logical_ast: LogicalAst) -> Option<LogicalAst> {
 /// * phrase terms: Quoted terms become phrase searches on fields that have positions indexed.
 ///   e.g., `title:"Barack Obama"` will only find documents that have "barack" immediately followed
 ///   by "obama".
+///   A slop can be appended to a phrase to allow its terms to be moved by a number of positions.
+///   e.g., `title:"Barack Obama"~1` also matches "Barack Hussein Obama".
 ///
 /// * range terms: Range searches can be done by specifying the start and end bound. These can be
 ///   inclusive or exclusive. e.g., `title:[a TO c}` will find all documents whose title contains
 ///   a word lexicographically between `a` and `c` (inclusive lower bound, exclusive upper bound).
 ///   Inclusive bounds are `[]`, exclusive are `{}`.
 ///
+/// * wildcard terms: On text fields, `*` matches any sequence of characters and `?` any single
+///   character. e.g., `title:comp*` finds "computer" and "compact", and `te?t` finds "test"
+///   and "text". Wildcard terms are not analyzed: they are matched nearly as typed against the
+///   indexed terms, their letters being only lowercased if the analyzer of the field lowercases
+///   them. e.g., with a stemming analyzer, `title:runn*` does not find "running", indexed as "run".
+///
+/// * fuzzy terms: On text fields, a term followed by `~` matches the terms within a Levenshtein
+///   distance of 2, or of the distance following the `~`. e.g., `title:fox~1` finds "fox" and "fix".
+///   `title:fox*~1` matches the prefixes of the terms instead, and finds "foxes" as well.
+///
+/// * regex terms: On text fields, a regular expression delimited by slashes matches the terms
+///   it fully matches. e.g., `title:/fox(es)?/` finds "fox" and "foxes".
+///   Slashes within the regular expression are escaped as `\/`.
+///
 /// * date values: The query parser supports rfc3339 formatted dates. For example `"2002-10-02T15:00:00.05Z"`
 ///   or `some_date_field:[2002-10-02T15:00:00Z TO 2002-10-02T18:00:00Z}`
 ///
@@ -185,6 +218,7 @@ pub struct QueryParser {
     conjunction_by_default: bool,
     tokenizer_manager: TokenizerManager,
     boost: HashMap<Field, Score>,
+    field_tokenizers: HashMap<Field, Vec<String>>,
 }
 
 fn all_negative(ast: &LogicalAst) -> bool {
@@ -213,6 +247,7 @@ impl QueryParser {
             tokenizer_manager,
             conjunction_by_default: false,
             boost: Default::default(),
+            field_tokenizers: Default::default(),
         }
     }
 
@@ -220,10 +255,47 @@ impl QueryParser {
     ///  * an index
     ///  * a set of default - fields used to search if no field is specifically defined
     ///   in the query.
+    ///
+    /// The text fields selecting their tokenizer per document are only searched
+    /// with their default tokenizer, until their tokenizers are set with
+    /// `set_field_tokenizers` or `set_field_tokenizers_from_searcher`.
     pub fn for_index(index: &Index, default_fields: Vec<Field>) -> QueryParser {
         QueryParser::new(index.schema(), default_fields, index.tokenizers().clone())
     }
 
+    /// Sets the tokenizers used to index the documents of a field selecting its
+    /// tokenizer per document.
+    ///
+    /// The queries on the field are processed with each of these tokenizers,
+    /// in addition to its search tokenizer.
+    pub fn set_field_tokenizers(&mut self, field: Field, tokenizer_names: Vec<String>) {
+        self.field_tokenizers.insert(field, tokenizer_names);
+    }
+
+    /// Sets the tokenizers of the fields selecting their tokenizer per document
+    /// to the tokenizers used by the segments of the searcher.
+    ///
+    /// The tokenizers are not updated as new segments get committed: this method
+    /// needs to be called again with a searcher of the reloaded reader for the
+    /// queries to search them.
+    pub fn set_field_tokenizers_from_searcher(&mut self, searcher: &Searcher) {
+        let mut field_tokenizers: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for segment_reader in searcher.segment_readers() {
+            for (field_name, tokenizers) in segment_reader.field_tokenizers() {
+                field_tokenizers
+                    .entry(field_name)
+                    .or_default()
+                    .extend(tokenizers.iter().map(String::as_str));
+            }
+        }
+        for (field_name, tokenizers) in field_tokenizers {
+            if let Some(field) = self.schema.get_field(field_name) {
+                let tokenizer_names = tokenizers.into_iter().map(String::from).collect();
+                self.set_field_tokenizers(field, tokenizer_names);
+            }
+        }
+    }
+
     /// Set the default way to compose queries to a conjunction.
     ///
     /// By default, the query `happy tax payer` is equivalent to the query
@@ -292,7 +364,7 @@ impl QueryParser {
         &self,
         field: Field,
         phrase: &str,
-    ) -> Result<Vec<(usize, Term)>, QueryParserError> {
+    ) -> Result<Vec<(usize, usize, Term)>, QueryParserError> {
         let field_entry = self.schema.get_field_entry(field);
         let field_type = field_entry.field_type();
         if !field_type.is_indexed() {
@@ -303,16 +375,17 @@ impl QueryParser {
             FieldType::I64(_) => {
                 let val: i64 = i64::from_str(phrase)?;
                 let term = Term::from_field_i64(field, val);
-                Ok(vec![(0, term)])
+                Ok(vec![(0, 1, term)])
             }
             FieldType::F64(_) => {
                 let val: f64 = f64::from_str(phrase)?;
                 let term = Term::from_field_f64(field, val);
-                Ok(vec![(0, term)])
+                Ok(vec![(0, 1, term)])
             }
             FieldType::Date(_) => match chrono::DateTime::parse_from_rfc3339(phrase) {
                 Ok(x) => Ok(vec![(
                     0,
+                    1,
                     Term::from_field_date(field, &x.with_timezone(&chrono::Utc)),
                 )]),
                 Err(e) => Err(QueryParserError::DateFormatError(e)),
@@ -320,46 +393,11 @@ impl QueryParser {
             FieldType::U64(_) => {
                 let val: u64 = u64::from_str(phrase)?;
                 let term = Term::from_field_u64(field, val);
-                Ok(vec![(0, term)])
+                Ok(vec![(0, 1, term)])
             }
             FieldType::Str(ref str_options) => {
                 if let Some(option) = str_options.get_indexing_options() {
-                    let tokenizer =
-                        self.tokenizer_manager
-                            .get(option.tokenizer())
-                            .ok_or_else(|| {
-                                QueryParserError::UnknownTokenizer(
-                                    field_entry.name().to_string(),
-                                    option.tokenizer().to_string(),
-                                )
-                            })?;
-                    let mut terms: Vec<(usize, Term)> = Vec::new();
-                    let mut token_stream = tokenizer.token_stream(phrase);
-                    token_stream.process(&mut |token| {
-                        let term = Term::from_field_text(field, &token.text);
-                        terms.push((token.position, term));
-                    });
-                    if terms.is_empty() {
-                        Ok(vec![])
-                    } else if terms.len() == 1 {
-                        Ok(terms)
-                    } else {
-                        let field_entry = self.schema.get_field_entry(field);
-                        let field_type = field_entry.field_type();
-                        if let Some(index_record_option) = field_type.get_index_record_option() {
-                            if index_record_option.has_positions() {
-                                Ok(terms)
-                            } else {
-                                let fieldname = self.schema.get_field_name(field).to_string();
-                                Err(QueryParserError::FieldDoesNotHavePositionsIndexed(
-                                    fieldname,
-                                ))
-                            }
-                        } else {
-                            let fieldname = self.schema.get_field_name(field).to_string();
-                            Err(QueryParserError::FieldNotIndexed(fieldname))
-                        }
-                    }
+                    self.compute_terms_for_text(field, option.search_tokenizer(), phrase)
                 } else {
                     // This should have been seen earlier really.
                     Err(QueryParserError::FieldNotIndexed(
@@ -368,13 +406,62 @@ impl QueryParser {
                 }
             }
             FieldType::HierarchicalFacet(_) => match Facet::from_text(phrase) {
-                Ok(facet) => Ok(vec![(0, Term::from_field_text(field, facet.encoded_str()))]),
+                Ok(facet) => Ok(vec![(
+                    0,
+                    1,
+                    Term::from_field_text(field, facet.encoded_str()),
+                )]),
                 Err(e) => Err(QueryParserError::from(e)),
             },
             FieldType::Bytes(_) => {
                 let bytes = base64::decode(phrase).map_err(QueryParserError::ExpectedBase64)?;
                 let term = Term::from_field_bytes(field, &bytes);
-                Ok(vec![(0, term)])
+                Ok(vec![(0, 1, term)])
+            }
+        }
+    }
+
+    fn compute_terms_for_text(
+        &self,
+        field: Field,
+        tokenizer_name: &str,
+        phrase: &str,
+    ) -> Result<Vec<(usize, usize, Term)>, QueryParserError> {
+        let tokenizer = self.tokenizer_manager.get(tokenizer_name).ok_or_else(|| {
+            QueryParserError::UnknownTokenizer(
+                self.schema.get_field_name(field).to_string(),
+                tokenizer_name.to_string(),
+            )
+        })?;
+        let mut terms: Vec<(usize, usize, Term)> = Vec::new();
+        let mut token_stream = tokenizer.token_stream(phrase);
+        token_stream.process(&mut |token| {
+            let term = Term::from_field_text(field, &token.text);
+            terms.push((token.position, token.position_length, term));
+        });
+        // Alternatives of a single token can be searched without positions.
+        let is_single_position = terms
+            .iter()
+            .all(|(position, position_length, _)| *position == terms[0].0 && *position_length <= 1);
+        if terms.is_empty() {
+            Ok(vec![])
+        } else if is_single_position {
+            Ok(terms)
+        } else {
+            let field_entry = self.schema.get_field_entry(field);
+            let field_type = field_entry.field_type();
+            if let Some(index_record_option) = field_type.get_index_record_option() {
+                if index_record_option.has_positions() {
+                    Ok(terms)
+                } else {
+                    let fieldname = self.schema.get_field_name(field).to_string();
+                    Err(QueryParserError::FieldDoesNotHavePositionsIndexed(
+                        fieldname,
+                    ))
+                }
+            } else {
+                let fieldname = self.schema.get_field_name(field).to_string();
+                Err(QueryParserError::FieldNotIndexed(fieldname))
             }
         }
     }
@@ -383,13 +470,134 @@ impl QueryParser {
         &self,
         field: Field,
         phrase: &str,
-    ) -> Result<Option<LogicalLiteral>, QueryParserError> {
+        slop: u32,
+    ) -> Result<Option<LogicalAst>, QueryParserError> {
         let terms = self.compute_terms_for_string(field, phrase)?;
-        match &terms[..] {
-            [] => Ok(None),
-            [(_, term)] => Ok(Some(LogicalLiteral::Term(term.clone()))),
-            _ => Ok(Some(LogicalLiteral::Phrase(terms.clone()))),
+        let tokenizer_names = match self.field_tokenizers.get(&field) {
+            Some(tokenizer_names) => tokenizer_names,
+            None => return Ok(logical_ast_for_terms(terms, slop)),
+        };
+        // The field is searched with each of the tokenizers used by its documents.
+        let mut terms_alternatives = vec![terms];
+        for tokenizer_name in tokenizer_names {
+            let terms = self.compute_terms_for_text(field, tokenizer_name, phrase)?;
+            if !terms_alternatives.contains(&terms) {
+                terms_alternatives.push(terms);
+            }
         }
+        let mut asts: Vec<LogicalAst> = terms_alternatives
+            .into_iter()
+            .flat_map(|terms| logical_ast_for_terms(terms, slop))
+            .collect();
+        if asts.len() <= 1 {
+            Ok(asts.pop())
+        } else {
+            Ok(Some(LogicalAst::Clause(
+                asts.into_iter().map(|ast| (Occur::Should, ast)).collect(),
+            )))
+        }
+    }
+
+    /// Returns the analyzer used to search the given text field.
+    fn search_analyzer(&self, field: Field) -> Result<TextAnalyzer, QueryParserError> {
+        let field_entry = self.schema.get_field_entry(field);
+        let tokenizer_name = match field_entry.field_type() {
+            FieldType::Str(ref str_options) => str_options
+                .get_indexing_options()
+                .map(|option| option.search_tokenizer().to_string())
+                .ok_or_else(|| QueryParserError::FieldNotIndexed(field_entry.name().to_string()))?,
+            _ => {
+                return Err(QueryParserError::FieldNotText(
+                    field_entry.name().to_string(),
+                ))
+            }
+        };
+        self.tokenizer_manager.get(&tokenizer_name).ok_or_else(|| {
+            QueryParserError::UnknownTokenizer(field_entry.name().to_string(), tokenizer_name)
+        })
+    }
+
+    /// Lowercases the literal characters of a wildcard pattern, if the search
+    /// analyzer of the field lowercases the indexed text.
+    ///
+    /// Wildcards and escaped characters are left untouched.
+    fn normalize_wildcard_pattern(
+        &self,
+        field: Field,
+        pattern: &str,
+    ) -> Result<String, QueryParserError> {
+        let analyzer = self.search_analyzer(field)?;
+        let mut normalized = String::with_capacity(pattern.len());
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' | '?' => normalized.push(c),
+                '\\' => {
+                    normalized.push(c);
+                    normalized.extend(chars.next());
+                }
+                _ => normalized.push(normalize_char(&analyzer, c)),
+            }
+        }
+        Ok(normalized)
+    }
+
+    fn compute_wildcard_literal(
+        &self,
+        field: Field,
+        pattern: &str,
+    ) -> Result<LogicalLiteral, QueryParserError> {
+        let pattern = &self.normalize_wildcard_pattern(field, pattern)?;
+        // `comp*` is a simple prefix query.
+        if let Some(prefix) = pattern.strip_suffix('*') {
+            if !prefix.contains(['*', '?', '\\']) {
+                return Ok(LogicalLiteral::Prefix(Term::from_field_text(field, prefix)));
+            }
+        }
+        // The automaton of a pattern may exceed the size limit of the regex engine.
+        let query = WildcardQuery::from_pattern(pattern, field)
+            .map_err(|_| QueryParserError::InvalidRegex(pattern.to_string()))?;
+        Ok(LogicalLiteral::Wildcard(query))
+    }
+
+    /// Computes the logical AST of a wildcard, fuzzy or regex leaf, given
+    /// the literal of each of the fields it applies to.
+    ///
+    /// These fields are required to be indexed text fields.
+    fn compute_logical_ast_for_pattern(
+        &self,
+        field_name: &Option<String>,
+        literal_for_field: impl Fn(Field) -> Result<LogicalLiteral, QueryParserError>,
+    ) -> Result<LogicalAst, QueryParserError> {
+        let fields = self.resolved_fields(field_name)?;
+        let mut clauses = fields
+            .iter()
+            .map(|&field| {
+                let field_entry = self.schema.get_field_entry(field);
+                let field_type = field_entry.field_type();
+                if !field_type.is_indexed() {
+                    let field_name = field_entry.name().to_string();
+                    return Err(QueryParserError::FieldNotIndexed(field_name));
+                }
+                if !matches!(field_type, FieldType::Str(_)) {
+                    let field_name = field_entry.name().to_string();
+                    return Err(QueryParserError::FieldNotText(field_name));
+                }
+                let boost = self.field_boost(field);
+                Ok(LogicalAst::from(literal_for_field(field)?).boost(boost))
+            })
+            .collect::<Result<Vec<_>, QueryParserError>>()?;
+        let result_ast = if clauses.len() == 1 {
+            clauses.pop().unwrap()
+        } else {
+            LogicalAst::Clause(
+                clauses
+                    .into_iter()
+                    .map(|clause| (Occur::Should, clause))
+                    .collect(),
+            )
+        };
+        Ok(result_ast)
     }
 
     fn default_occur(&self) -> Occur {
@@ -412,7 +620,7 @@ impl QueryParser {
         if terms.len() != 1 {
             return Err(QueryParserError::RangeMustNotHavePhrase);
         }
-        let (_, term) = terms.into_iter().next().unwrap();
+        let (_, _, term) = terms.into_iter().next().unwrap();
         match *bound {
             UserInputBound::Inclusive(_) => Ok(Bound::Included(term)),
             UserInputBound::Exclusive(_) => Ok(Bound::Excluded(term)),
@@ -487,10 +695,12 @@ impl QueryParser {
                 };
                 let mut asts: Vec<LogicalAst> = Vec::new();
                 for (field, phrase) in term_phrases {
-                    if let Some(ast) = self.compute_logical_ast_for_leaf(field, &phrase)? {
+                    if let Some(ast) =
+                        self.compute_logical_ast_for_leaf(field, &phrase, literal.slop)?
+                    {
                         // Apply some field specific boost defined at the query parser level.
                         let boost = self.field_boost(field);
-                        asts.push(LogicalAst::Leaf(Box::new(ast)).boost(boost));
+                        asts.push(ast.boost(boost));
                     }
                 }
                 let result_ast: LogicalAst = if asts.len() == 1 {
@@ -534,15 +744,196 @@ impl QueryParser {
                 };
                 Ok(result_ast)
             }
+            UserInputLeaf::Wildcard {
+                field_name,
+                pattern,
+            } => self.compute_logical_ast_for_pattern(&field_name, |field| {
+                self.compute_wildcard_literal(field, &pattern)
+            }),
+            UserInputLeaf::Fuzzy {
+                field_name,
+                term,
+                distance,
+                prefix,
+            } => {
+                if distance > MAX_FUZZY_DISTANCE {
+                    return Err(QueryParserError::InvalidFuzzyDistance(distance));
+                }
+                self.compute_logical_ast_for_pattern(&field_name, |field| {
+                    // Fuzzy terms are normalized like the indexed text, e.g. lowercased.
+                    let analyzer = self.search_analyzer(field)?;
+                    let term_text: String =
+                        term.chars().map(|c| normalize_char(&analyzer, c)).collect();
+                    Ok(LogicalLiteral::Fuzzy {
+                        term: Term::from_field_text(field, &term_text),
+                        distance,
+                        prefix,
+                    })
+                })
+            }
+            UserInputLeaf::Regex {
+                field_name,
+                pattern,
+            } => {
+                let regex = Regex::new(&pattern)
+                    .map_err(|_| QueryParserError::InvalidRegex(pattern.clone()))?;
+                let regex = Arc::new(regex);
+                self.compute_logical_ast_for_pattern(&field_name, |field| {
+                    Ok(LogicalLiteral::Regex {
+                        field,
+                        pattern: pattern.clone(),
+                        regex: regex.clone(),
+                    })
+                })
+            }
         }
     }
 }
 
+// Lowercases a character if the analyzer lowercases it in the indexed text.
+//
+// The other normalizations of the analyzer, such as stemming, accent folding or
+// stop words, are not applied: they are meaningless on a single character.
+fn normalize_char(analyzer: &TextAnalyzer, c: char) -> char {
+    let mut lowercase_chars = c.to_lowercase();
+    let lowercase = match (lowercase_chars.next(), lowercase_chars.next()) {
+        (Some(lowercase), None) if lowercase != c => lowercase,
+        _ => return c,
+    };
+    let mut buffer = [0u8; 4];
+    let mut token_stream = analyzer.token_stream(c.encode_utf8(&mut buffer));
+    let is_lowercased = token_stream.advance()
+        && token_stream
+            .token()
+            .text
+            .chars()
+            .eq(std::iter::once(lowercase));
+    if is_lowercased {
+        lowercase
+    } else {
+        c
+    }
+}
+
+// Builds the logical AST searching the terms computed for a phrase.
+fn logical_ast_for_terms(terms: Vec<(usize, usize, Term)>, slop: u32) -> Option<LogicalAst> {
+    match &terms[..] {
+        [] => None,
+        [(_, _, term)] => Some(LogicalLiteral::Term(term.clone()).into()),
+        _ => match token_graph_paths(&terms) {
+            Some(paths) => {
+                let mut asts: Vec<LogicalAst> = paths
+                    .into_iter()
+                    .map(|mut path| {
+                        if path.len() == 1 {
+                            LogicalLiteral::Term(path.pop().unwrap().1).into()
+                        } else {
+                            LogicalLiteral::Phrase(path, slop).into()
+                        }
+                    })
+                    .collect();
+                if asts.len() == 1 {
+                    asts.pop()
+                } else {
+                    Some(LogicalAst::Clause(
+                        asts.into_iter().map(|ast| (Occur::Should, ast)).collect(),
+                    ))
+                }
+            }
+            None => {
+                // Too many paths to be enumerated: the tokens sharing a position
+                // are searched as alternatives of a multi phrase instead.
+                let mut position_terms: BTreeMap<usize, Vec<Term>> = BTreeMap::new();
+                for (position, _, term) in terms {
+                    position_terms.entry(position).or_default().push(term);
+                }
+                if position_terms.len() == 1 {
+                    let (_, terms) = position_terms.into_iter().next()?;
+                    let clause = terms
+                        .into_iter()
+                        .map(|term| (Occur::Should, LogicalLiteral::Term(term).into()))
+                        .collect();
+                    Some(LogicalAst::Clause(clause))
+                } else {
+                    let terms = position_terms.into_iter().collect();
+                    Some(LogicalLiteral::MultiPhrase(terms, slop).into())
+                }
+            }
+        },
+    }
+}
+
+// Maximum Levenshtein distance of a fuzzy term, as supported by `FuzzyTermQuery`.
+const MAX_FUZZY_DISTANCE: u8 = 2;
+
+// Maximum number of paths of a token graph searched by the query parser.
+// Beyond this limit, the tokens are searched at their position in a multi phrase
+// instead, tokens spanning several positions matching at their first position only.
+const MAX_TOKEN_GRAPH_PATHS: usize = 32;
+
+// Enumerates the paths of the graph formed by the tokens of a phrase,
+// given as `(position, position_length, term)`.
+//
+// Tokens sharing a position are alternatives (e.g. synonyms), and
+// a token spans `position_length` positions. Each path is returned as the
+// terms of a phrase, with consecutive offsets except across position gaps.
+//
+// Returns `None` if the graph has more than `MAX_TOKEN_GRAPH_PATHS` paths.
+fn token_graph_paths(terms: &[(usize, usize, Term)]) -> Option<Vec<Vec<(usize, Term)>>> {
+    let start = terms.iter().map(|(position, _, _)| *position).min()?;
+    let end = terms
+        .iter()
+        .map(|(position, position_length, _)| position + (*position_length).max(1))
+        .max()?;
+    let mut paths: Vec<Vec<(usize, Term)>> = Vec::new();
+    let mut stack: Vec<(usize, Vec<(usize, Term)>)> = vec![(start, Vec::new())];
+    while let Some((node, path)) = stack.pop() {
+        let next_node_opt = terms
+            .iter()
+            .map(|(position, _, _)| *position)
+            .filter(|&position| position >= node)
+            .min();
+        let next_node = match next_node_opt {
+            Some(next_node) if node < end => next_node,
+            _ => {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+                if paths.len() > MAX_TOKEN_GRAPH_PATHS {
+                    return None;
+                }
+                continue;
+            }
+        };
+        let offset = match path.last() {
+            Some((last_offset, _)) => last_offset + 1 + next_node - node,
+            None => next_node,
+        };
+        for (_, position_length, term) in terms
+            .iter()
+            .rev()
+            .filter(|(position, _, _)| *position == next_node)
+        {
+            let mut next_path = path.clone();
+            next_path.push((offset, term.clone()));
+            stack.push((next_node + (*position_length).max(1), next_path));
+        }
+    }
+    Some(paths)
+}
+
 fn convert_literal_to_query(logical_literal: LogicalLiteral) -> Box<dyn Query> {
     match logical_literal {
         LogicalLiteral::Term(term) => Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs)),
-        LogicalLiteral::Phrase(term_with_offsets) => {
-            Box::new(PhraseQuery::new_with_offset(term_with_offsets))
+        LogicalLiteral::Phrase(term_with_offsets, slop) => {
+            let mut phrase_query = PhraseQuery::new_with_offset(term_with_offsets);
+            phrase_query.set_slop(slop);
+            Box::new(phrase_query)
+        }
+        LogicalLiteral::MultiPhrase(terms_with_offsets, slop) => {
+            let mut multi_phrase_query = MultiPhraseQuery::new_with_offset(terms_with_offsets);
+            multi_phrase_query.set_slop(slop);
+            Box::new(multi_phrase_query)
         }
         LogicalLiteral::Range {
             field,
@@ -552,6 +943,22 @@ fn convert_literal_to_query(logical_literal: LogicalLiteral) -> Box<dyn Query> {
         } => Box::new(RangeQuery::new_term_bounds(
             field, value_type, &lower, &upper,
         )),
+        LogicalLiteral::Prefix(prefix) => Box::new(PrefixQuery::new(prefix)),
+        LogicalLiteral::Wildcard(query) => Box::new(query),
+        LogicalLiteral::Fuzzy {
+            term,
+            distance,
+            prefix,
+        } => {
+            if prefix {
+                Box::new(FuzzyTermQuery::new_prefix(term, distance, true))
+            } else {
+                Box::new(FuzzyTermQuery::new(term, distance, true))
+            }
+        }
+        LogicalLiteral::Regex { field, regex, .. } => {
+            Box::new(RegexQuery::from_regex(regex, field))
+        }
         LogicalLiteral::All => Box::new(AllQuery),
     }
 }
@@ -586,12 +993,14 @@ mod test {
     use super::super::logical_ast::*;
     use super::QueryParser;
     use super::QueryParserError;
+    use crate::collector::Count;
     use crate::query::Query;
     use crate::schema::Field;
     use crate::schema::{IndexRecordOption, TextFieldIndexing, TextOptions};
     use crate::schema::{Schema, Term, INDEXED, STORED, STRING, TEXT};
     use crate::tokenizer::{
-        LowerCaser, SimpleTokenizer, StopWordFilter, TextAnalyzer, TokenizerManager,
+        Language, LowerCaser, NgramTokenizer, PhoneticAlgorithm, PhoneticFilter, SimpleTokenizer,
+        StopWordFilter, SynonymFilter, SynonymMap, TextAnalyzer, TokenizerManager,
     };
     use crate::Index;
     use matches::assert_matches;
@@ -676,6 +1085,163 @@ mod test {
         assert!(matches!(error, QueryParserError::FieldNotIndexed(_)));
     }
 
+    #[test]
+    fn test_parse_query_wildcard() {
+        test_parse_query_to_logical_ast_helper(
+            "title:comp*",
+            "Term(field=0,bytes=[99, 111, 109, 112])*",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "te?t",
+            "(Wildcard(field=0,pattern=\"te?t\") Wildcard(field=1,pattern=\"te?t\"))",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "title:c*p*",
+            "Wildcard(field=0,pattern=\"c*p*\")",
+            false,
+        );
+        let query_parser = make_query_parser();
+        let query = query_parser.parse_query("title:comp*").unwrap();
+        assert_eq!(
+            format!("{:?}", query),
+            "PrefixQuery { prefix: Term(field=0,bytes=[99, 111, 109, 112]) }"
+        );
+        assert_matches!(
+            query_parser.parse_query("unsigned:1*"),
+            Err(QueryParserError::FieldNotText(_))
+        );
+        assert_matches!(
+            query_parser.parse_query("notindexed_text:comp*"),
+            Err(QueryParserError::FieldNotIndexed(_))
+        );
+        // The automaton of this pattern exceeds the size limit of the regex engine.
+        assert_matches!(
+            query_parser.parse_query("title:*a??????????"),
+            Err(QueryParserError::InvalidRegex(_))
+        );
+    }
+
+    #[test]
+    fn test_query_parser_wildcard_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "Computer science"));
+        index_writer.add_document(doc!(title => "A compact test"));
+        index_writer.add_document(doc!(title => "Some text"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let count = |query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query)?, &Count)
+        };
+        assert_eq!(count("comp*")?, 2);
+        assert_eq!(count("title:comp*")?, 2);
+        assert_eq!(count("te?t")?, 2);
+        assert_eq!(count("te?t -comp*")?, 1);
+        // The patterns are lowercased, like the indexed text.
+        assert_eq!(count("title:Comp*")?, 2);
+        assert_eq!(count("TE?T")?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_parser_wildcard_normalization() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = |tokenizer: &str| {
+            TextOptions::default()
+                .set_indexing_options(TextFieldIndexing::default().set_tokenizer(tokenizer))
+        };
+        let stemmed = schema_builder.add_text_field("stemmed", text_options("en_stem"));
+        let cased = schema_builder.add_text_field("cased", text_options("cased"));
+        let index = Index::create_in_ram(schema_builder.build());
+        index
+            .tokenizers()
+            .register("cased", TextAnalyzer::from(SimpleTokenizer));
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(stemmed => "Running Dogs", cased => "Running Dogs"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![]);
+        let count = |query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query)?, &Count)
+        };
+        // The patterns are lowercased, but not stemmed.
+        assert_eq!(count("stemmed:Ru*")?, 1);
+        assert_eq!(count("stemmed:DO?")?, 1);
+        assert_eq!(count("stemmed:Runn*")?, 0);
+        assert_eq!(count("stemmed:dog?")?, 0);
+        // The patterns are not lowercased if the indexed text is not.
+        assert_eq!(count("cased:Runn*")?, 1);
+        assert_eq!(count("cased:runn*")?, 0);
+        assert_eq!(count("cased:D?gs")?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_query_fuzzy_and_regex() {
+        test_parse_query_to_logical_ast_helper(
+            "title:fox~1",
+            "Term(field=0,bytes=[102, 111, 120])~1",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "title:fox*~",
+            "Term(field=0,bytes=[102, 111, 120])*~2",
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "/fo[xg]/",
+            "(Regex(field=0,pattern=\"fo[xg]\") Regex(field=1,pattern=\"fo[xg]\"))",
+            false,
+        );
+        let query_parser = make_query_parser();
+        assert_matches!(
+            query_parser.parse_query("title:fox~3"),
+            Err(QueryParserError::InvalidFuzzyDistance(3))
+        );
+        assert_matches!(
+            query_parser.parse_query("title:/fo[x/"),
+            Err(QueryParserError::InvalidRegex(_))
+        );
+        assert_matches!(
+            query_parser.parse_query("signed:12~1"),
+            Err(QueryParserError::FieldNotText(_))
+        );
+        assert_matches!(
+            query_parser.parse_query("date:/2020.*/"),
+            Err(QueryParserError::FieldNotText(_))
+        );
+    }
+
+    #[test]
+    fn test_query_parser_fuzzy_and_regex_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "The quick fox"));
+        index_writer.add_document(doc!(title => "A lazy dog"));
+        index_writer.add_document(doc!(title => "Foxes and dogs"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let count = |query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query)?, &Count)
+        };
+        assert_eq!(count("fix~1")?, 1);
+        assert_eq!(count("fix~0")?, 0);
+        assert_eq!(count("title:fix*~1")?, 2);
+        assert_eq!(count("dogz~")?, 2);
+        assert_eq!(count("Fix~1")?, 1);
+        assert_eq!(count("/do.*/")?, 2);
+        assert_eq!(count("title:/fox(es)?/ -/dogs?/")?, 1);
+        Ok(())
+    }
+
     #[test]
     pub fn test_parse_query_with_boost() {
         let mut query_parser = make_query_parser();
@@ -1005,6 +1571,92 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn test_query_parser_search_tokenizer() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field_indexing = TextFieldIndexing::default()
+            .set_tokenizer("edge_ngram")
+            .set_search_tokenizer("raw")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let text_options = TextOptions::default().set_indexing_options(text_field_indexing);
+        let title = schema_builder.add_text_field("title", text_options);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        index
+            .tokenizers()
+            .register("edge_ngram", NgramTokenizer::prefix_only(1, 10));
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title=>"tantivy"));
+        index_writer.add_document(doc!(title=>"tango"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let query = query_parser.parse_query("tan").unwrap();
+        assert_eq!(
+            format!("{:?}", query),
+            r#"TermQuery(Term(field=0,bytes=[116, 97, 110]))"#
+        );
+        assert_eq!(searcher.search(&query, &Count)?, 2);
+        let query = query_parser.parse_query("tant").unwrap();
+        assert_eq!(searcher.search(&query, &Count)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_query_parser_tokenizer_field() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field_indexing = TextFieldIndexing::default()
+            .set_tokenizer_field("lang")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let text_options = TextOptions::default().set_indexing_options(text_field_indexing);
+        let body = schema_builder.add_text_field("body", text_options);
+        let lang = schema_builder.add_text_field("lang", STRING);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let language_analyzer = TokenizerManager::language_analyzer;
+        index
+            .tokenizers()
+            .register("fr", language_analyzer(Language::French));
+        index
+            .tokenizers()
+            .register("de", language_analyzer(Language::German));
+        let mut query_parser = QueryParser::for_index(&index, vec![body]);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(body=>"Les chevaux", lang=>"fr"));
+        index_writer.add_document(doc!(body=>"Die Häuser", lang=>"de"));
+        index_writer.commit()?;
+        index_writer.add_document(doc!(body=>"Les chevaux"));
+        index_writer.add_document(doc!(body=>"Die Häuser", lang=>"unknown"));
+        index_writer.commit()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        futures::executor::block_on(index_writer.merge(&segment_ids))?;
+        index_writer.wait_merging_threads()?;
+        let segment_metas = index.searchable_segment_metas()?;
+        assert_eq!(segment_metas.len(), 1);
+        let tokenizers: Vec<&str> = segment_metas[0].field_tokenizers()["body"]
+            .iter()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(tokenizers, vec!["de", "fr"]);
+        let reader = index.reader()?;
+        reader.reload()?;
+        let searcher = reader.searcher();
+        // Only the document analyzed with the default tokenizer is found.
+        let chevaux_query = query_parser.parse_query("chevaux").unwrap();
+        assert_eq!(searcher.search(&chevaux_query, &Count)?, 1);
+        query_parser.set_field_tokenizers_from_searcher(&searcher);
+        let count = |query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query).unwrap(), &Count)
+        };
+        // The default tokenizer, the french and the german analyzers.
+        assert_eq!(count("chevaux")?, 2);
+        assert_eq!(count("cheval")?, 1);
+        assert_eq!(count("häuser")?, 2);
+        assert_eq!(count("haus")?, 1);
+        assert_eq!(count("\"les chevaux\"")?, 2);
+        Ok(())
+    }
+
     #[test]
     pub fn test_query_parser_expected_int() {
         let query_parser = make_query_parser();
@@ -1102,6 +1754,24 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn test_parse_query_phrase_slop() {
+        test_parse_query_to_logical_ast_helper(
+            "title:\"a b\"~2",
+            "\"[(0, Term(field=0,bytes=[97])), \
+             (1, Term(field=0,bytes=[98]))]\"~2",
+            false,
+        );
+        // The slop of a single term is ignored.
+        test_parse_query_to_logical_ast_helper("title:\"a\"~2", "Term(field=0,bytes=[97])", false);
+        let query = make_query_parser().parse_query("title:\"a b\"~2").unwrap();
+        assert_eq!(
+            format!("{:?}", query),
+            "PhraseQuery { field: Field(0), phrase_terms: [(0, Term(field=0,bytes=[97])), \
+             (1, Term(field=0,bytes=[98]))], slop: 2 }"
+        );
+    }
+
     #[test]
     pub fn test_query_parser_hyphen() {
         test_parse_query_to_logical_ast_helper(
@@ -1132,4 +1802,230 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_query_parser_token_graph() {
+        let mut schema_builder = Schema::builder();
+        let text_field_indexing = TextFieldIndexing::default()
+            .set_tokenizer("synonyms")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let text_options = TextOptions::default().set_indexing_options(text_field_indexing);
+        let title = schema_builder.add_text_field("title", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let synonym_map = SynonymMap::from_solr("ny, new york", true).unwrap();
+        index.tokenizers().register(
+            "synonyms",
+            TextAnalyzer::from(SimpleTokenizer)
+                .filter(LowerCaser)
+                .filter(SynonymFilter::new(synonym_map)),
+        );
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let ny = Term::from_field_text(title, "ny");
+        let new = Term::from_field_text(title, "new");
+        let york = Term::from_field_text(title, "york");
+        let city = Term::from_field_text(title, "city");
+        let query = query_parser
+            .parse_query_to_logical_ast("\"ny city\"")
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", query),
+            format!(
+                "({:?} {:?})",
+                LogicalAst::from(LogicalLiteral::Phrase(
+                    vec![(0, ny.clone()), (1, city.clone())],
+                    0
+                )),
+                LogicalAst::from(LogicalLiteral::Phrase(
+                    vec![(0, new.clone()), (1, york.clone()), (2, city)],
+                    0
+                ))
+            )
+        );
+        let query = query_parser.parse_query_to_logical_ast("NY").unwrap();
+        assert_eq!(
+            format!("{:?}", query),
+            format!(
+                "({:?} {:?})",
+                LogicalAst::from(LogicalLiteral::Term(ny)),
+                LogicalAst::from(LogicalLiteral::Phrase(vec![(0, new), (1, york)], 0))
+            )
+        );
+    }
+
+    #[test]
+    fn test_query_parser_token_graph_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field_indexing =
+            TextFieldIndexing::default().set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let text_options = TextOptions::default().set_indexing_options(text_field_indexing);
+        let title = schema_builder.add_text_field("title", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title=>"new york city"));
+        index_writer.add_document(doc!(title=>"ny city"));
+        index_writer.add_document(doc!(title=>"new city of york"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        // Synonyms are only used at query time.
+        let synonym_map = SynonymMap::from_solr("ny, new york", true)?;
+        let tokenizer_manager = TokenizerManager::default();
+        tokenizer_manager.register(
+            "default",
+            TextAnalyzer::from(SimpleTokenizer)
+                .filter(LowerCaser)
+                .filter(SynonymFilter::new(synonym_map)),
+        );
+        let query_parser = QueryParser::new(index.schema(), vec![title], tokenizer_manager);
+        let count =
+            |query: &str| searcher.search(&query_parser.parse_query(query).unwrap(), &Count);
+        assert_eq!(count("\"ny city\"")?, 2);
+        assert_eq!(count("\"new york city\"")?, 2);
+        assert_eq!(count("ny")?, 2);
+        assert_eq!(count("york")?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_parser_stacked_tokens_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field_indexing = TextFieldIndexing::default()
+            .set_tokenizer("names")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let text_options = TextOptions::default().set_indexing_options(text_field_indexing);
+        let name = schema_builder.add_text_field("name", text_options.clone());
+        let exact_name = schema_builder.add_text_field(
+            "exact_name",
+            text_options.set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer("names")
+                    .set_search_tokenizer("default")
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            ),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        // The original name and its phonetic codes are indexed at the same position.
+        index.tokenizers().register(
+            "names",
+            TextAnalyzer::from(SimpleTokenizer)
+                .filter(LowerCaser)
+                .filter(PhoneticFilter::new(PhoneticAlgorithm::DoubleMetaphone)),
+        );
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(name=>"Smith", exact_name=>"Smith"));
+        index_writer.add_document(doc!(name=>"Smyth", exact_name=>"Smyth"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![]);
+        let count =
+            |query: &str| searcher.search(&query_parser.parse_query(query).unwrap(), &Count);
+        // The tokens sharing a position used to be all required, which only matched
+        // the name as typed. They are now alternatives, matching its phonetic codes.
+        assert_eq!(count("name:smith")?, 2);
+        assert_eq!(count("name:\"smith\"")?, 2);
+        // Searching without the stacked tokens restores the exact match.
+        assert_eq!(count("exact_name:smith")?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_parser_token_graph_too_many_paths_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field_indexing =
+            TextFieldIndexing::default().set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let text_options = TextOptions::default().set_indexing_options(text_field_indexing);
+        let title = schema_builder.add_text_field("title", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title=>"big fast red old cat ate"));
+        index_writer.add_document(doc!(title=>"large quick scarlet aged kitty consumed"));
+        index_writer.add_document(doc!(title=>"large quick scarlet aged kitty"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        // The 6 words with one synonym each form 64 paths, searched as a multi phrase.
+        let synonym_map = SynonymMap::from_solr(
+            "big, large\nfast, quick\nred, scarlet\nold, aged\ncat, kitty\nate, consumed",
+            true,
+        )?;
+        let tokenizer_manager = TokenizerManager::default();
+        tokenizer_manager.register(
+            "default",
+            TextAnalyzer::from(SimpleTokenizer)
+                .filter(LowerCaser)
+                .filter(SynonymFilter::new(synonym_map)),
+        );
+        let query_parser = QueryParser::new(index.schema(), vec![title], tokenizer_manager);
+        let count =
+            |query: &str| searcher.search(&query_parser.parse_query(query).unwrap(), &Count);
+        assert_eq!(count("\"big fast red old cat ate\"")?, 2);
+        assert_eq!(count("\"large fast scarlet old kitty ate\"")?, 2);
+        assert_eq!(count("\"big fast red old cat\"")?, 3);
+        assert_eq!(count("\"ate big fast red old cat\"")?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_parser_token_graph_one_way_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field_indexing = TextFieldIndexing::default()
+            .set_tokenizer("synonyms")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let text_options = TextOptions::default().set_indexing_options(text_field_indexing);
+        let title = schema_builder.add_text_field("title", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        // The synonyms are used both at indexing and at query time.
+        let synonym_map = SynonymMap::from_solr("new york => ny", true)?;
+        index.tokenizers().register(
+            "synonyms",
+            TextAnalyzer::from(SimpleTokenizer)
+                .filter(LowerCaser)
+                .filter(SynonymFilter::new(synonym_map)),
+        );
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title=>"new york city"));
+        index_writer.add_document(doc!(title=>"ny city"));
+        index_writer.add_document(doc!(title=>"new city"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let count =
+            |query: &str| searcher.search(&query_parser.parse_query(query).unwrap(), &Count);
+        assert_eq!(count("\"new york city\"")?, 2);
+        assert_eq!(count("\"ny city\"")?, 2);
+        assert_eq!(count("\"new city\"")?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_parser_token_alternatives_without_positions() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field_indexing = TextFieldIndexing::default()
+            .set_tokenizer("synonyms")
+            .set_index_option(IndexRecordOption::Basic);
+        let text_options = TextOptions::default().set_indexing_options(text_field_indexing);
+        let title = schema_builder.add_text_field("title", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let synonym_map = SynonymMap::from_solr("quick, fast\nny, new york", true)?;
+        index.tokenizers().register(
+            "synonyms",
+            TextAnalyzer::from(SimpleTokenizer)
+                .filter(LowerCaser)
+                .filter(SynonymFilter::new(synonym_map)),
+        );
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title=>"quick"));
+        index_writer.add_document(doc!(title=>"fast"));
+        index_writer.add_document(doc!(title=>"slow"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        // The synonyms of a single token are searched as alternative terms.
+        let query = query_parser.parse_query("fast")?;
+        assert_eq!(searcher.search(&query, &Count)?, 2);
+        // A synonym spanning several positions requires a phrase.
+        assert_matches!(
+            query_parser.parse_query("ny"),
+            Err(QueryParserError::FieldDoesNotHavePositionsIndexed(_))
+        );
+        Ok(())
+    }
 }
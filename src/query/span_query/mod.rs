@@ -0,0 +1,292 @@
+mod span_containing_query;
+mod span_first_query;
+mod span_near_query;
+mod span_not_query;
+mod span_or_query;
+mod span_query;
+mod span_scorer;
+mod span_term_query;
+mod span_weight;
+
+pub use self::span_containing_query::SpanContainingQuery;
+pub use self::span_first_query::SpanFirstQuery;
+pub use self::span_near_query::SpanNearQuery;
+pub use self::span_not_query::SpanNotQuery;
+pub use self::span_or_query::SpanOrQuery;
+pub use self::span_query::{SpanQuery, SpanQueryClone};
+pub use self::span_scorer::SpanScorer;
+pub use self::span_term_query::SpanTermQuery;
+pub use self::span_weight::{SpanQueryWeight, SpanWeight};
+
+use crate::docset::{DocSet, TERMINATED};
+use crate::DocId;
+
+/// A range of positions matched within a document.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Span {
+    /// Position of the first token of the span.
+    pub start: u32,
+    /// Position following the last token of the span.
+    pub end: u32,
+    /// Number of positions of the span that were not matched by a term.
+    pub width: u32,
+}
+
+impl Span {
+    /// Returns the number of positions covered by the span.
+    pub fn len(&self) -> u32 {
+        self.end - self.start
+    }
+
+    /// Returns true if the span covers no position.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// A `DocSet` exposing, for each of its documents, the spans it matched.
+pub trait Spans: DocSet {
+    /// Returns the spans of the current document, sorted by start and end positions.
+    ///
+    /// The spans of a document of the `DocSet` are never empty.
+    fn spans(&self) -> &[Span];
+}
+
+/// Computes the spans of the current document of a candidate `DocSet`.
+pub(crate) trait SpanMatcher: Send + 'static {
+    type Candidates: DocSet;
+
+    /// Pushes the spans of the current document of `candidates` to `output`.
+    fn compute_spans(&mut self, candidates: &mut Self::Candidates, output: &mut Vec<Span>);
+}
+
+/// `Spans` over the candidate documents in which a `SpanMatcher` found spans.
+pub(crate) struct MatchedSpans<TMatcher: SpanMatcher> {
+    candidates: TMatcher::Candidates,
+    matcher: TMatcher,
+    spans: Vec<Span>,
+}
+
+impl<TMatcher: SpanMatcher> MatchedSpans<TMatcher> {
+    pub(crate) fn new(
+        candidates: TMatcher::Candidates,
+        matcher: TMatcher,
+    ) -> MatchedSpans<TMatcher> {
+        let mut matched_spans = MatchedSpans {
+            candidates,
+            matcher,
+            spans: Vec::new(),
+        };
+        let doc = matched_spans.candidates.doc();
+        matched_spans.go_to_match(doc);
+        matched_spans
+    }
+
+    // Advances the candidates, starting from `doc`, to the first document having spans.
+    fn go_to_match(&mut self, mut doc: DocId) -> DocId {
+        while doc != TERMINATED {
+            self.spans.clear();
+            self.matcher
+                .compute_spans(&mut self.candidates, &mut self.spans);
+            if !self.spans.is_empty() {
+                self.spans.sort();
+                self.spans.dedup();
+                return doc;
+            }
+            doc = self.candidates.advance();
+        }
+        self.spans.clear();
+        TERMINATED
+    }
+}
+
+impl<TMatcher: SpanMatcher> DocSet for MatchedSpans<TMatcher> {
+    fn advance(&mut self) -> DocId {
+        let doc = self.candidates.advance();
+        self.go_to_match(doc)
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if self.doc() >= target {
+            return self.doc();
+        }
+        let doc = self.candidates.seek(target);
+        self.go_to_match(doc)
+    }
+
+    fn doc(&self) -> DocId {
+        self.candidates.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.candidates.size_hint()
+    }
+}
+
+impl<TMatcher: SpanMatcher> Spans for MatchedSpans<TMatcher> {
+    fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
+
+/// Intersection of `Spans`, preserving the order of its children.
+pub(crate) struct SpansIntersection {
+    children: Vec<Box<dyn Spans>>,
+}
+
+impl SpansIntersection {
+    pub(crate) fn new(children: Vec<Box<dyn Spans>>) -> SpansIntersection {
+        assert!(!children.is_empty());
+        let mut intersection = SpansIntersection { children };
+        intersection.go_to_common_doc();
+        intersection
+    }
+
+    pub(crate) fn children(&self) -> &[Box<dyn Spans>] {
+        &self.children
+    }
+
+    fn go_to_common_doc(&mut self) -> DocId {
+        let mut candidate = self.children[0].doc();
+        loop {
+            let mut aligned = true;
+            for child in &mut self.children {
+                let doc = child.seek(candidate);
+                if doc > candidate {
+                    candidate = doc;
+                    aligned = false;
+                }
+            }
+            if aligned {
+                return candidate;
+            }
+        }
+    }
+}
+
+impl DocSet for SpansIntersection {
+    fn advance(&mut self) -> DocId {
+        self.children[0].advance();
+        self.go_to_common_doc()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.children[0].seek(target);
+        self.go_to_common_doc()
+    }
+
+    fn doc(&self) -> DocId {
+        self.children[0].doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.children
+            .iter()
+            .map(|child| child.size_hint())
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{SpanNearQuery, SpanQuery, SpanQueryWeight, SpanTermQuery};
+    use crate::assert_nearly_equals;
+    use crate::collector::TopDocs;
+    use crate::core::Index;
+    use crate::docset::{DocSet, TERMINATED};
+    use crate::query::{Query, TermQuery};
+    use crate::schema::{Field, IndexRecordOption, Schema, STRING, TEXT};
+    use crate::{DocAddress, DocId, Term};
+
+    pub fn create_index(texts: &[&'static str]) -> Index {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        {
+            let mut index_writer = index.writer_for_tests().unwrap();
+            for &text in texts {
+                index_writer.add_document(doc!(text_field=>text));
+            }
+            assert!(index_writer.commit().is_ok());
+        }
+        index
+    }
+
+    pub fn span_term(field: Field, text: &str) -> Box<dyn SpanQuery> {
+        Box::new(SpanTermQuery::new(Term::from_field_text(field, text)))
+    }
+
+    // Returns the documents matching `query`, with the start and end of their spans.
+    pub fn span_matches<TSpanQuery: SpanQuery>(
+        index: &Index,
+        query: &TSpanQuery,
+    ) -> Vec<(DocId, Vec<(u32, u32)>)> {
+        let searcher = index.reader().unwrap().searcher();
+        let weight = SpanQueryWeight::new(query, &searcher, true).unwrap();
+        let mut matches = Vec::new();
+        for segment_reader in searcher.segment_readers() {
+            let mut scorer = match weight.span_scorer(segment_reader, 1.0).unwrap() {
+                Some(scorer) => scorer,
+                None => continue,
+            };
+            while scorer.doc() != TERMINATED {
+                let spans = scorer.spans().iter().map(|span| (span.start, span.end));
+                matches.push((scorer.doc(), spans.collect()));
+                scorer.advance();
+            }
+        }
+        matches
+    }
+
+    #[test]
+    fn test_span_query_score() {
+        let index = create_index(&["a b", "a x b", "a b a b", "b a"]);
+        let field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let query = SpanNearQuery::new(vec![span_term(field, "a"), span_term(field, "b")], 1, true);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(4)).unwrap();
+        let docs: Vec<DocAddress> = top_docs.iter().map(|(_, doc)| *doc).collect();
+        // Two exact matches beat one exact match, which beats a sloppy match.
+        assert_eq!(
+            docs,
+            vec![
+                DocAddress::new(0, 2),
+                DocAddress::new(0, 0),
+                DocAddress::new(0, 1)
+            ]
+        );
+        let explanation = query.explain(&searcher, DocAddress::new(0, 1)).unwrap();
+        assert!(explanation.to_pretty_json().contains("Span Scorer"));
+        assert!(query.explain(&searcher, DocAddress::new(0, 3)).is_err());
+        // A single term span query scores like a term query.
+        let term = Term::from_field_text(field, "a");
+        let term_query = TermQuery::new(term.clone(), IndexRecordOption::WithFreqs);
+        let span_query = SpanTermQuery::new(term);
+        let expected = searcher
+            .search(&term_query, &TopDocs::with_limit(4))
+            .unwrap();
+        let top_docs = searcher
+            .search(&span_query, &TopDocs::with_limit(4))
+            .unwrap();
+        assert_eq!(top_docs.len(), expected.len());
+        for ((score, doc), (expected_score, expected_doc)) in top_docs.iter().zip(&expected) {
+            assert_eq!(doc, expected_doc);
+            assert_nearly_equals!(*score, *expected_score);
+        }
+    }
+
+    #[test]
+    fn test_span_query_requires_positions() {
+        let mut schema_builder = Schema::builder();
+        let field = schema_builder.add_text_field("text", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let searcher = index.reader().unwrap().searcher();
+        let query = SpanTermQuery::new(Term::from_field_text(field, "a"));
+        assert!(matches!(
+            query.weight(&searcher, true),
+            Err(crate::TantivyError::SchemaError(_))
+        ));
+    }
+}
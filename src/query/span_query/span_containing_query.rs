@@ -0,0 +1,122 @@
+use super::{
+    MatchedSpans, Span, SpanMatcher, SpanQuery, SpanQueryWeight, SpanWeight, Spans,
+    SpansIntersection,
+};
+use crate::core::{Searcher, SegmentReader};
+use crate::query::{Query, Weight};
+use crate::schema::{Field, Term};
+use std::collections::BTreeMap;
+
+/// `SpanContainingQuery` matches the spans of its `big` clause
+/// containing at least one span of its `little` clause.
+#[derive(Clone, Debug)]
+pub struct SpanContainingQuery {
+    big: Box<dyn SpanQuery>,
+    little: Box<dyn SpanQuery>,
+}
+
+impl SpanContainingQuery {
+    /// Creates a new `SpanContainingQuery`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clauses do not belong to the same field.
+    pub fn new(big: Box<dyn SpanQuery>, little: Box<dyn SpanQuery>) -> SpanContainingQuery {
+        assert_eq!(
+            big.field(),
+            little.field(),
+            "All clauses of a span query must belong to the same field"
+        );
+        SpanContainingQuery { big, little }
+    }
+
+    /// Returns the clause whose spans are matched.
+    pub fn big(&self) -> &dyn SpanQuery {
+        self.big.as_ref()
+    }
+
+    /// Returns the clause whose spans must be contained.
+    pub fn little(&self) -> &dyn SpanQuery {
+        self.little.as_ref()
+    }
+}
+
+impl Query for SpanContainingQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(SpanQueryWeight::new(
+            self,
+            searcher,
+            scoring_enabled,
+        )?))
+    }
+
+    fn query_terms(&self, terms: &mut BTreeMap<Term, bool>) {
+        self.big.query_terms(terms);
+        self.little.query_terms(terms);
+    }
+}
+
+impl SpanQuery for SpanContainingQuery {
+    fn field(&self) -> Field {
+        self.big.field()
+    }
+
+    fn span_weight(&self) -> Box<dyn SpanWeight> {
+        Box::new(SpanContainingWeight {
+            big: self.big.span_weight(),
+            little: self.little.span_weight(),
+        })
+    }
+}
+
+struct SpanContainingWeight {
+    big: Box<dyn SpanWeight>,
+    little: Box<dyn SpanWeight>,
+}
+
+impl SpanWeight for SpanContainingWeight {
+    fn spans(&self, reader: &SegmentReader) -> crate::Result<Option<Box<dyn Spans>>> {
+        let (big, little) = match (self.big.spans(reader)?, self.little.spans(reader)?) {
+            (Some(big), Some(little)) => (big, little),
+            _ => return Ok(None),
+        };
+        Ok(Some(Box::new(MatchedSpans::new(
+            SpansIntersection::new(vec![big, little]),
+            ContainingMatcher,
+        ))))
+    }
+}
+
+struct ContainingMatcher;
+
+impl SpanMatcher for ContainingMatcher {
+    type Candidates = SpansIntersection;
+
+    fn compute_spans(&mut self, candidates: &mut SpansIntersection, output: &mut Vec<Span>) {
+        let (big, little) = (
+            candidates.children()[0].spans(),
+            candidates.children()[1].spans(),
+        );
+        output.extend(big.iter().filter(|big_span| {
+            little
+                .iter()
+                .any(|span| span.start >= big_span.start && span.end <= big_span.end)
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_index, span_matches, span_term};
+    use super::super::SpanNearQuery;
+    use super::SpanContainingQuery;
+
+    #[test]
+    fn test_span_containing_query() {
+        let index = create_index(&["a b c", "a c b", "b a x c", "a c"]);
+        let field = index.schema().get_field("text").unwrap();
+        let a_c = SpanNearQuery::new(vec![span_term(field, "a"), span_term(field, "c")], 2, true);
+        let query = SpanContainingQuery::new(Box::new(a_c), span_term(field, "b"));
+        assert_eq!(span_matches(&index, &query), vec![(0, vec![(0, 3)])]);
+    }
+}
@@ -0,0 +1,100 @@
+use super::{MatchedSpans, Span, SpanMatcher, SpanQuery, SpanQueryWeight, SpanWeight, Spans};
+use crate::core::{Searcher, SegmentReader};
+use crate::query::{Query, Weight};
+use crate::schema::{Field, Term};
+use std::collections::BTreeMap;
+
+/// `SpanFirstQuery` matches the spans of its clause ending
+/// within the first `end` positions of the field.
+#[derive(Clone, Debug)]
+pub struct SpanFirstQuery {
+    clause: Box<dyn SpanQuery>,
+    end: u32,
+}
+
+impl SpanFirstQuery {
+    /// Creates a new `SpanFirstQuery`.
+    pub fn new(clause: Box<dyn SpanQuery>, end: u32) -> SpanFirstQuery {
+        SpanFirstQuery { clause, end }
+    }
+
+    /// Returns the clause of the query.
+    pub fn clause(&self) -> &dyn SpanQuery {
+        self.clause.as_ref()
+    }
+
+    /// Returns the position before which the spans must end.
+    pub fn end(&self) -> u32 {
+        self.end
+    }
+}
+
+impl Query for SpanFirstQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(SpanQueryWeight::new(
+            self,
+            searcher,
+            scoring_enabled,
+        )?))
+    }
+
+    fn query_terms(&self, terms: &mut BTreeMap<Term, bool>) {
+        self.clause.query_terms(terms);
+    }
+}
+
+impl SpanQuery for SpanFirstQuery {
+    fn field(&self) -> Field {
+        self.clause.field()
+    }
+
+    fn span_weight(&self) -> Box<dyn SpanWeight> {
+        Box::new(SpanFirstWeight {
+            clause: self.clause.span_weight(),
+            end: self.end,
+        })
+    }
+}
+
+struct SpanFirstWeight {
+    clause: Box<dyn SpanWeight>,
+    end: u32,
+}
+
+impl SpanWeight for SpanFirstWeight {
+    fn spans(&self, reader: &SegmentReader) -> crate::Result<Option<Box<dyn Spans>>> {
+        Ok(self.clause.spans(reader)?.map(|clause| {
+            Box::new(MatchedSpans::new(clause, FirstMatcher { end: self.end })) as Box<dyn Spans>
+        }))
+    }
+}
+
+struct FirstMatcher {
+    end: u32,
+}
+
+impl SpanMatcher for FirstMatcher {
+    type Candidates = Box<dyn Spans>;
+
+    fn compute_spans(&mut self, clause: &mut Box<dyn Spans>, output: &mut Vec<Span>) {
+        let end = self.end;
+        output.extend(clause.spans().iter().filter(|span| span.end <= end));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_index, span_matches, span_term};
+    use super::SpanFirstQuery;
+
+    #[test]
+    fn test_span_first_query() {
+        let index = create_index(&["a b", "b a", "b b a", "a b a"]);
+        let field = index.schema().get_field("text").unwrap();
+        let query = SpanFirstQuery::new(span_term(field, "a"), 2);
+        assert_eq!(
+            span_matches(&index, &query),
+            vec![(0, vec![(0, 1)]), (1, vec![(1, 2)]), (3, vec![(0, 1)])]
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use super::span_query::assert_same_field;
+use super::{
+    MatchedSpans, Span, SpanMatcher, SpanQuery, SpanQueryWeight, SpanWeight, Spans,
+    SpansIntersection,
+};
+use crate::core::{Searcher, SegmentReader};
+use crate::query::{Query, Weight};
+use crate::schema::{Field, Term};
+use std::collections::BTreeMap;
+
+/// `SpanNearQuery` matches the spans of its clauses when they are close to
+/// each other.
+///
+/// A match is made of one span of each clause. The `slop` is the maximum
+/// number of positions, within the match, that are not covered by these spans.
+/// If `in_order` is true, the spans must also appear in the order of the
+/// clauses, without overlapping.
+///
+/// For instance, `"quick fox"` is matched by the clauses `quick` and `fox` in
+/// order with a slop of 1, while `"fox is quick"` requires an unordered match
+/// with a slop of 1.
+#[derive(Clone, Debug)]
+pub struct SpanNearQuery {
+    field: Field,
+    clauses: Vec<Box<dyn SpanQuery>>,
+    slop: u32,
+    in_order: bool,
+}
+
+impl SpanNearQuery {
+    /// Creates a new `SpanNearQuery`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than two clauses, or if they do not
+    /// belong to the same field.
+    pub fn new(clauses: Vec<Box<dyn SpanQuery>>, slop: u32, in_order: bool) -> SpanNearQuery {
+        assert!(
+            clauses.len() > 1,
+            "A span near query is required to have strictly more than one clause."
+        );
+        let field = clauses[0].field();
+        assert_same_field(field, &clauses);
+        SpanNearQuery {
+            field,
+            clauses,
+            slop,
+            in_order,
+        }
+    }
+
+    /// Returns the clauses of the query.
+    pub fn clauses(&self) -> &[Box<dyn SpanQuery>] {
+        &self.clauses
+    }
+
+    /// Returns the maximum number of unmatched positions within a match.
+    pub fn slop(&self) -> u32 {
+        self.slop
+    }
+
+    /// Returns true if the clauses must match in order.
+    pub fn in_order(&self) -> bool {
+        self.in_order
+    }
+}
+
+impl Query for SpanNearQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(SpanQueryWeight::new(
+            self,
+            searcher,
+            scoring_enabled,
+        )?))
+    }
+
+    fn query_terms(&self, terms: &mut BTreeMap<Term, bool>) {
+        for clause in &self.clauses {
+            clause.query_terms(terms);
+        }
+    }
+}
+
+impl SpanQuery for SpanNearQuery {
+    fn field(&self) -> Field {
+        self.field
+    }
+
+    fn span_weight(&self) -> Box<dyn SpanWeight> {
+        Box::new(SpanNearWeight {
+            clauses: self
+                .clauses
+                .iter()
+                .map(|clause| clause.span_weight())
+                .collect(),
+            matcher: NearMatcher {
+                slop: self.slop,
+                in_order: self.in_order,
+            },
+        })
+    }
+}
+
+struct SpanNearWeight {
+    clauses: Vec<Box<dyn SpanWeight>>,
+    matcher: NearMatcher,
+}
+
+impl SpanWeight for SpanNearWeight {
+    fn spans(&self, reader: &SegmentReader) -> crate::Result<Option<Box<dyn Spans>>> {
+        let mut clauses = Vec::with_capacity(self.clauses.len());
+        for clause in &self.clauses {
+            match clause.spans(reader)? {
+                Some(spans) => clauses.push(spans),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(Box::new(MatchedSpans::new(
+            SpansIntersection::new(clauses),
+            self.matcher.clone(),
+        ))))
+    }
+}
+
+#[derive(Clone)]
+struct NearMatcher {
+    slop: u32,
+    in_order: bool,
+}
+
+impl SpanMatcher for NearMatcher {
+    type Candidates = SpansIntersection;
+
+    fn compute_spans(&mut self, candidates: &mut SpansIntersection, output: &mut Vec<Span>) {
+        let clauses: Vec<&[Span]> = candidates
+            .children()
+            .iter()
+            .map(|clause| clause.spans())
+            .collect();
+        if self.in_order {
+            ordered_spans(&clauses, self.slop, output);
+        } else {
+            unordered_spans(&clauses, self.slop, output);
+        }
+    }
+}
+
+// Builds a match of each span of the first clause, followed, for each
+// subsequent clause, by its span ending first after the previous span.
+// The ends of the spans of a clause are not necessarily increasing (e.g. for a
+// `SpanOrQuery`), so a later span of the first clause may still match.
+fn ordered_spans(clauses: &[&[Span]], slop: u32, output: &mut Vec<Span>) {
+    'first: for first in clauses[0] {
+        let mut end = first.end;
+        let mut covered = first.len();
+        let mut width = first.width;
+        for clause in &clauses[1..] {
+            let next = clause
+                .iter()
+                .filter(|span| span.start >= end)
+                .min_by_key(|span| span.end);
+            match next {
+                Some(span) => {
+                    end = span.end;
+                    covered += span.len();
+                    width += span.width;
+                }
+                None => continue 'first,
+            }
+        }
+        let gaps = end - first.start - covered;
+        if gaps <= slop {
+            output.push(Span {
+                start: first.start,
+                end,
+                width: width + gaps,
+            });
+        }
+    }
+}
+
+// Sweeps through the spans of the clauses, considering, at each step, the
+// current span of every clause, then moving past the span starting first.
+fn unordered_spans(clauses: &[&[Span]], slop: u32, output: &mut Vec<Span>) {
+    let mut cursors = vec![0; clauses.len()];
+    loop {
+        let current = clauses
+            .iter()
+            .zip(&cursors)
+            .map(|(clause, &cursor)| clause[cursor]);
+        let start = current.clone().map(|span| span.start).min().unwrap();
+        let end = current.clone().map(|span| span.end).max().unwrap();
+        let covered: u32 = current.clone().map(|span| span.len()).sum();
+        let width: u32 = current.map(|span| span.width).sum();
+        let gaps = (end - start).saturating_sub(covered);
+        if gaps <= slop {
+            output.push(Span {
+                start,
+                end,
+                width: width + gaps,
+            });
+        }
+        let (first_ord, _) = clauses
+            .iter()
+            .zip(&cursors)
+            .enumerate()
+            .min_by_key(|(_, (clause, &cursor))| (clause[cursor].start, clause[cursor].end))
+            .unwrap();
+        cursors[first_ord] += 1;
+        if cursors[first_ord] == clauses[first_ord].len() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_index, span_matches, span_term};
+    use super::super::SpanOrQuery;
+    use super::SpanNearQuery;
+
+    #[test]
+    fn test_span_near_query() {
+        let index = create_index(&["a b c", "c b a", "a x x c", "a c a"]);
+        let field = index.schema().get_field("text").unwrap();
+        let a_c = || vec![span_term(field, "a"), span_term(field, "c")];
+        let query = SpanNearQuery::new(a_c(), 1, true);
+        assert_eq!(
+            span_matches(&index, &query),
+            vec![(0, vec![(0, 3)]), (3, vec![(0, 2)])]
+        );
+        let query = SpanNearQuery::new(a_c(), 2, true);
+        assert_eq!(
+            span_matches(&index, &query),
+            vec![(0, vec![(0, 3)]), (2, vec![(0, 4)]), (3, vec![(0, 2)])]
+        );
+        let query = SpanNearQuery::new(a_c(), 1, false);
+        assert_eq!(
+            span_matches(&index, &query),
+            vec![
+                (0, vec![(0, 3)]),
+                (1, vec![(0, 3)]),
+                (3, vec![(0, 2), (1, 3)])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_span_near_query_nested() {
+        let index = create_index(&["a b c d", "a b x c d", "c d a b"]);
+        let field = index.schema().get_field("text").unwrap();
+        let a_b = SpanNearQuery::new(vec![span_term(field, "a"), span_term(field, "b")], 0, true);
+        let c_d = SpanNearQuery::new(vec![span_term(field, "c"), span_term(field, "d")], 0, true);
+        let query = SpanNearQuery::new(vec![Box::new(a_b), Box::new(c_d)], 0, true);
+        assert_eq!(span_matches(&index, &query), vec![(0, vec![(0, 4)])]);
+    }
+
+    #[test]
+    fn test_span_near_query_wide_first_span() {
+        let index = create_index(&["a c d b"]);
+        let field = index.schema().get_field("text").unwrap();
+        // The span `a c d b` of the first clause starts before `c`, but ends after it.
+        let a_b = SpanNearQuery::new(vec![span_term(field, "a"), span_term(field, "b")], 3, true);
+        let a_b_or_c = SpanOrQuery::new(vec![Box::new(a_b), span_term(field, "c")]);
+        let query = SpanNearQuery::new(vec![Box::new(a_b_or_c), span_term(field, "d")], 0, true);
+        assert_eq!(span_matches(&index, &query), vec![(0, vec![(1, 3)])]);
+    }
+}
@@ -0,0 +1,173 @@
+use super::{MatchedSpans, Span, SpanMatcher, SpanQuery, SpanQueryWeight, SpanWeight, Spans};
+use crate::core::{Searcher, SegmentReader};
+use crate::docset::DocSet;
+use crate::query::{Query, Weight};
+use crate::schema::{Field, Term};
+use std::collections::BTreeMap;
+
+/// `SpanNotQuery` matches the spans of its `include` clause that do not
+/// overlap any span of its `exclude` clause.
+///
+/// The `pre` and `post` distances extend the `include` spans, respectively
+/// before and after them, when checking for overlaps.
+#[derive(Clone, Debug)]
+pub struct SpanNotQuery {
+    include: Box<dyn SpanQuery>,
+    exclude: Box<dyn SpanQuery>,
+    pre: u32,
+    post: u32,
+}
+
+impl SpanNotQuery {
+    /// Creates a new `SpanNotQuery`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clauses do not belong to the same field.
+    pub fn new(include: Box<dyn SpanQuery>, exclude: Box<dyn SpanQuery>) -> SpanNotQuery {
+        assert_eq!(
+            include.field(),
+            exclude.field(),
+            "All clauses of a span query must belong to the same field"
+        );
+        SpanNotQuery {
+            include,
+            exclude,
+            pre: 0,
+            post: 0,
+        }
+    }
+
+    /// Sets the number of positions, before and after the `include` spans,
+    /// in which no `exclude` span may start or end.
+    pub fn set_distances(&mut self, pre: u32, post: u32) {
+        self.pre = pre;
+        self.post = post;
+    }
+
+    /// Returns the `include` clause.
+    pub fn include(&self) -> &dyn SpanQuery {
+        self.include.as_ref()
+    }
+
+    /// Returns the `exclude` clause.
+    pub fn exclude(&self) -> &dyn SpanQuery {
+        self.exclude.as_ref()
+    }
+}
+
+impl Query for SpanNotQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(SpanQueryWeight::new(
+            self,
+            searcher,
+            scoring_enabled,
+        )?))
+    }
+
+    fn query_terms(&self, terms: &mut BTreeMap<Term, bool>) {
+        self.include.query_terms(terms);
+    }
+}
+
+impl SpanQuery for SpanNotQuery {
+    fn field(&self) -> Field {
+        self.include.field()
+    }
+
+    fn span_weight(&self) -> Box<dyn SpanWeight> {
+        Box::new(SpanNotWeight {
+            include: self.include.span_weight(),
+            exclude: self.exclude.span_weight(),
+            pre: self.pre,
+            post: self.post,
+        })
+    }
+}
+
+struct SpanNotWeight {
+    include: Box<dyn SpanWeight>,
+    exclude: Box<dyn SpanWeight>,
+    pre: u32,
+    post: u32,
+}
+
+impl SpanWeight for SpanNotWeight {
+    fn spans(&self, reader: &SegmentReader) -> crate::Result<Option<Box<dyn Spans>>> {
+        let include = match self.include.spans(reader)? {
+            Some(include) => include,
+            None => return Ok(None),
+        };
+        let exclude = match self.exclude.spans(reader)? {
+            Some(exclude) => exclude,
+            None => return Ok(Some(include)),
+        };
+        let matcher = NotMatcher {
+            exclude,
+            pre: self.pre,
+            post: self.post,
+        };
+        Ok(Some(Box::new(MatchedSpans::new(include, matcher))))
+    }
+}
+
+struct NotMatcher {
+    exclude: Box<dyn Spans>,
+    pre: u32,
+    post: u32,
+}
+
+impl SpanMatcher for NotMatcher {
+    type Candidates = Box<dyn Spans>;
+
+    fn compute_spans(&mut self, include: &mut Box<dyn Spans>, output: &mut Vec<Span>) {
+        let doc = include.doc();
+        if self.exclude.seek(doc) != doc {
+            output.extend_from_slice(include.spans());
+            return;
+        }
+        let excluded = self.exclude.spans();
+        let (pre, post) = (self.pre, self.post);
+        output.extend(include.spans().iter().filter(|span| {
+            !excluded.iter().any(|excluded_span| {
+                excluded_span.start < span.end + post && excluded_span.end + pre > span.start
+            })
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_index, span_matches, span_term};
+    use super::super::SpanNearQuery;
+    use super::SpanNotQuery;
+
+    #[test]
+    fn test_span_not_query() {
+        let index = create_index(&["a b c", "a x c", "c", "a c x", "x a c"]);
+        let field = index.schema().get_field("text").unwrap();
+        let a_c = || {
+            Box::new(SpanNearQuery::new(
+                vec![span_term(field, "a"), span_term(field, "c")],
+                1,
+                true,
+            ))
+        };
+        let query = SpanNotQuery::new(a_c(), span_term(field, "x"));
+        assert_eq!(
+            span_matches(&index, &query),
+            vec![(0, vec![(0, 3)]), (3, vec![(0, 2)]), (4, vec![(1, 3)])]
+        );
+        let mut query = SpanNotQuery::new(a_c(), span_term(field, "x"));
+        query.set_distances(0, 1);
+        assert_eq!(
+            span_matches(&index, &query),
+            vec![(0, vec![(0, 3)]), (4, vec![(1, 3)])]
+        );
+        query.set_distances(1, 0);
+        assert_eq!(
+            span_matches(&index, &query),
+            vec![(0, vec![(0, 3)]), (3, vec![(0, 2)])]
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use super::span_query::assert_same_field;
+use super::{Span, SpanQuery, SpanQueryWeight, SpanWeight, Spans};
+use crate::core::{Searcher, SegmentReader};
+use crate::docset::{DocSet, TERMINATED};
+use crate::query::{Query, Weight};
+use crate::schema::{Field, Term};
+use crate::DocId;
+use std::collections::BTreeMap;
+
+/// `SpanOrQuery` matches the spans of any of its clauses.
+#[derive(Clone, Debug)]
+pub struct SpanOrQuery {
+    field: Field,
+    clauses: Vec<Box<dyn SpanQuery>>,
+}
+
+impl SpanOrQuery {
+    /// Creates a new `SpanOrQuery`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no clause, or if they do not belong to the same field.
+    pub fn new(clauses: Vec<Box<dyn SpanQuery>>) -> SpanOrQuery {
+        assert!(
+            !clauses.is_empty(),
+            "A span or query is required to have at least one clause."
+        );
+        let field = clauses[0].field();
+        assert_same_field(field, &clauses);
+        SpanOrQuery { field, clauses }
+    }
+
+    /// Returns the clauses of the query.
+    pub fn clauses(&self) -> &[Box<dyn SpanQuery>] {
+        &self.clauses
+    }
+}
+
+impl Query for SpanOrQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(SpanQueryWeight::new(
+            self,
+            searcher,
+            scoring_enabled,
+        )?))
+    }
+
+    fn query_terms(&self, terms: &mut BTreeMap<Term, bool>) {
+        for clause in &self.clauses {
+            clause.query_terms(terms);
+        }
+    }
+}
+
+impl SpanQuery for SpanOrQuery {
+    fn field(&self) -> Field {
+        self.field
+    }
+
+    fn span_weight(&self) -> Box<dyn SpanWeight> {
+        Box::new(SpanOrWeight {
+            clauses: self
+                .clauses
+                .iter()
+                .map(|clause| clause.span_weight())
+                .collect(),
+        })
+    }
+}
+
+struct SpanOrWeight {
+    clauses: Vec<Box<dyn SpanWeight>>,
+}
+
+impl SpanWeight for SpanOrWeight {
+    fn spans(&self, reader: &SegmentReader) -> crate::Result<Option<Box<dyn Spans>>> {
+        let mut clauses = Vec::with_capacity(self.clauses.len());
+        for clause in &self.clauses {
+            clauses.extend(clause.spans(reader)?);
+        }
+        if clauses.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Box::new(SpansUnion::new(clauses))))
+    }
+}
+
+/// Union of `Spans`, merging the spans of the children positioned on the same document.
+struct SpansUnion {
+    children: Vec<Box<dyn Spans>>,
+    doc: DocId,
+    spans: Vec<Span>,
+}
+
+impl SpansUnion {
+    fn new(children: Vec<Box<dyn Spans>>) -> SpansUnion {
+        let mut union = SpansUnion {
+            children,
+            doc: TERMINATED,
+            spans: Vec::new(),
+        };
+        union.load_spans();
+        union
+    }
+
+    fn load_spans(&mut self) -> DocId {
+        self.doc = self
+            .children
+            .iter()
+            .map(|child| child.doc())
+            .min()
+            .unwrap_or(TERMINATED);
+        self.spans.clear();
+        if self.doc == TERMINATED {
+            return TERMINATED;
+        }
+        for child in &self.children {
+            if child.doc() == self.doc {
+                self.spans.extend_from_slice(child.spans());
+            }
+        }
+        self.spans.sort();
+        self.spans.dedup();
+        self.doc
+    }
+}
+
+impl DocSet for SpansUnion {
+    fn advance(&mut self) -> DocId {
+        let doc = self.doc;
+        for child in &mut self.children {
+            if child.doc() == doc {
+                child.advance();
+            }
+        }
+        self.load_spans()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        for child in &mut self.children {
+            if child.doc() < target {
+                child.seek(target);
+            }
+        }
+        self.load_spans()
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.children
+            .iter()
+            .map(|child| child.size_hint())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Spans for SpansUnion {
+    fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_index, span_matches, span_term};
+    use super::super::SpanNearQuery;
+    use super::SpanOrQuery;
+
+    #[test]
+    fn test_span_or_query() {
+        let index = create_index(&["a b", "c", "b c a", "d"]);
+        let field = index.schema().get_field("text").unwrap();
+        let query = SpanOrQuery::new(vec![
+            span_term(field, "a"),
+            span_term(field, "c"),
+            span_term(field, "e"),
+        ]);
+        assert_eq!(
+            span_matches(&index, &query),
+            vec![
+                (0, vec![(0, 1)]),
+                (1, vec![(0, 1)]),
+                (2, vec![(1, 2), (2, 3)])
+            ]
+        );
+        // "b" followed by either "a" or "c".
+        let query = SpanNearQuery::new(
+            vec![
+                span_term(field, "b"),
+                Box::new(SpanOrQuery::new(vec![
+                    span_term(field, "a"),
+                    span_term(field, "c"),
+                ])),
+            ],
+            0,
+            true,
+        );
+        assert_eq!(span_matches(&index, &query), vec![(2, vec![(0, 2)])]);
+    }
+}
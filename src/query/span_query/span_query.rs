@@ -0,0 +1,66 @@
+use super::SpanWeight;
+use crate::query::Query;
+use crate::schema::Field;
+
+/// A `SpanQuery` matches positional constraints between the terms of a document.
+///
+/// Rather than a simple set of documents, a span query computes, for each
+/// matching document, the list of its [`Span`](./struct.Span.html)s: the
+/// ranges of positions it matched. Span queries can then be nested,
+/// to express for instance that two terms must appear close to each other,
+/// but not in the vicinity of a third term.
+///
+/// A document matches a span query if it has at least one span. The more spans
+/// a document has, and the narrower they are, the higher its score.
+///
+/// # Example
+/// ```rust
+/// use tantivy::query::{SpanNearQuery, SpanNotQuery, SpanQuery, SpanTermQuery};
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::Term;
+///
+/// let mut schema_builder = Schema::builder();
+/// let body = schema_builder.add_text_field("body", TEXT);
+/// let term = |text: &str| -> Box<dyn SpanQuery> {
+///     Box::new(SpanTermQuery::new(Term::from_field_text(body, text)))
+/// };
+/// // "liability" within 5 positions of "limitation", in any order...
+/// let near = SpanNearQuery::new(vec![term("liability"), term("limitation")], 5, false);
+/// // ... unless "except" appears in between.
+/// let query = SpanNotQuery::new(Box::new(near), term("except"));
+/// ```
+pub trait SpanQuery: Query + SpanQueryClone {
+    /// Returns the field the spans are computed on.
+    fn field(&self) -> Field;
+
+    /// Creates the `SpanWeight` computing the spans of the query.
+    fn span_weight(&self) -> Box<dyn SpanWeight>;
+}
+
+/// Implements `box_clone_span`.
+pub trait SpanQueryClone {
+    /// Returns a boxed clone of `self`.
+    fn box_clone_span(&self) -> Box<dyn SpanQuery>;
+}
+
+impl<T> SpanQueryClone for T
+where
+    T: 'static + SpanQuery + Clone,
+{
+    fn box_clone_span(&self) -> Box<dyn SpanQuery> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn SpanQuery> {
+    fn clone(&self) -> Self {
+        self.box_clone_span()
+    }
+}
+
+pub(crate) fn assert_same_field(field: Field, clauses: &[Box<dyn SpanQuery>]) {
+    assert!(
+        clauses.iter().all(|clause| clause.field() == field),
+        "All clauses of a span query must belong to the same field"
+    );
+}
@@ -0,0 +1,70 @@
+use super::{Span, Spans};
+use crate::docset::DocSet;
+use crate::fieldnorm::FieldNormReader;
+use crate::query::bm25::Bm25Weight;
+use crate::query::Scorer;
+use crate::{DocId, Score};
+
+/// `Scorer` of a `SpanQuery`, exposing the spans of the current document.
+///
+/// The score of a document is given by BM25, using as term frequency the sum,
+/// over its spans, of `1 / (1 + width)`.
+pub struct SpanScorer {
+    spans: Box<dyn Spans>,
+    similarity_weight: Bm25Weight,
+    fieldnorm_reader: FieldNormReader,
+}
+
+impl SpanScorer {
+    pub(crate) fn new(
+        spans: Box<dyn Spans>,
+        similarity_weight: Bm25Weight,
+        fieldnorm_reader: FieldNormReader,
+    ) -> SpanScorer {
+        SpanScorer {
+            spans,
+            similarity_weight,
+            fieldnorm_reader,
+        }
+    }
+
+    /// Returns the spans of the current document, sorted by start and end positions.
+    pub fn spans(&self) -> &[Span] {
+        self.spans.spans()
+    }
+
+    /// Returns the frequency of the spans of the current document,
+    /// where narrower spans weigh more.
+    pub fn span_freq(&self) -> Score {
+        self.spans()
+            .iter()
+            .map(|span| 1.0 / (1.0 + span.width as Score))
+            .sum()
+    }
+}
+
+impl DocSet for SpanScorer {
+    fn advance(&mut self) -> DocId {
+        self.spans.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.spans.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.spans.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.spans.size_hint()
+    }
+}
+
+impl Scorer for SpanScorer {
+    fn score(&mut self) -> Score {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        self.similarity_weight
+            .score_freq(fieldnorm_id, self.span_freq())
+    }
+}
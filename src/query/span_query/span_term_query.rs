@@ -0,0 +1,151 @@
+use super::{Span, SpanQuery, SpanQueryWeight, SpanWeight, Spans};
+use crate::core::{Searcher, SegmentReader};
+use crate::docset::{DocSet, TERMINATED};
+use crate::postings::{Postings, SegmentPostings};
+use crate::query::{Query, Weight};
+use crate::schema::{Field, IndexRecordOption, Term};
+use crate::DocId;
+use std::collections::BTreeMap;
+
+/// `SpanTermQuery` matches the positions of a term.
+///
+/// It is the building block of the other span queries.
+#[derive(Clone, Debug)]
+pub struct SpanTermQuery {
+    term: Term,
+}
+
+impl SpanTermQuery {
+    /// Creates a new `SpanTermQuery`.
+    pub fn new(term: Term) -> SpanTermQuery {
+        SpanTermQuery { term }
+    }
+
+    /// The `Term` this query is built out of.
+    pub fn term(&self) -> &Term {
+        &self.term
+    }
+}
+
+impl Query for SpanTermQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(SpanQueryWeight::new(
+            self,
+            searcher,
+            scoring_enabled,
+        )?))
+    }
+
+    fn query_terms(&self, terms: &mut BTreeMap<Term, bool>) {
+        terms.insert(self.term.clone(), true);
+    }
+}
+
+impl SpanQuery for SpanTermQuery {
+    fn field(&self) -> Field {
+        self.term.field()
+    }
+
+    fn span_weight(&self) -> Box<dyn SpanWeight> {
+        Box::new(SpanTermWeight {
+            term: self.term.clone(),
+        })
+    }
+}
+
+struct SpanTermWeight {
+    term: Term,
+}
+
+impl SpanWeight for SpanTermWeight {
+    fn spans(&self, reader: &SegmentReader) -> crate::Result<Option<Box<dyn Spans>>> {
+        let postings_opt = reader
+            .inverted_index(self.term.field())?
+            .read_postings(&self.term, IndexRecordOption::WithFreqsAndPositions)?;
+        Ok(postings_opt.map(|postings| Box::new(TermSpans::new(postings)) as Box<dyn Spans>))
+    }
+}
+
+/// Spans of the positions of a term, each covering a single position.
+struct TermSpans {
+    postings: SegmentPostings,
+    positions: Vec<u32>,
+    spans: Vec<Span>,
+}
+
+impl TermSpans {
+    fn new(postings: SegmentPostings) -> TermSpans {
+        let mut term_spans = TermSpans {
+            postings,
+            positions: Vec::new(),
+            spans: Vec::new(),
+        };
+        term_spans.load_spans();
+        term_spans
+    }
+
+    fn load_spans(&mut self) {
+        self.spans.clear();
+        if self.postings.doc() == TERMINATED {
+            return;
+        }
+        self.postings.positions(&mut self.positions);
+        self.spans
+            .extend(self.positions.iter().map(|&position| Span {
+                start: position,
+                end: position + 1,
+                width: 0,
+            }));
+    }
+}
+
+impl DocSet for TermSpans {
+    fn advance(&mut self) -> DocId {
+        let doc = self.postings.advance();
+        self.load_spans();
+        doc
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if self.doc() >= target {
+            return self.doc();
+        }
+        let doc = self.postings.seek(target);
+        self.load_spans();
+        doc
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+impl Spans for TermSpans {
+    fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{create_index, span_matches};
+    use super::SpanTermQuery;
+    use crate::Term;
+
+    #[test]
+    fn test_span_term_query() {
+        let index = create_index(&["a b a", "b c", "c a"]);
+        let field = index.schema().get_field("text").unwrap();
+        let query = SpanTermQuery::new(Term::from_field_text(field, "a"));
+        assert_eq!(
+            span_matches(&index, &query),
+            vec![(0, vec![(0, 1), (2, 3)]), (2, vec![(1, 2)])]
+        );
+        let query = SpanTermQuery::new(Term::from_field_text(field, "d"));
+        assert!(span_matches(&index, &query).is_empty());
+    }
+}
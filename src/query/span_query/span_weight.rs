@@ -0,0 +1,111 @@
+use super::{SpanQuery, SpanScorer, Spans};
+use crate::core::{Searcher, SegmentReader};
+use crate::fieldnorm::FieldNormReader;
+use crate::query::bm25::Bm25Weight;
+use crate::query::explanation::does_not_match;
+use crate::query::{EmptyScorer, Explanation, Scorer, Weight};
+use crate::schema::{Field, IndexRecordOption, Term};
+use crate::{DocId, DocSet, Score};
+use std::collections::BTreeMap;
+
+/// Computes the spans of a `SpanQuery` within a segment.
+pub trait SpanWeight: Send + Sync + 'static {
+    /// Returns the spans of the documents of the segment,
+    /// or `None` if no document of the segment can match.
+    fn spans(&self, reader: &SegmentReader) -> crate::Result<Option<Box<dyn Spans>>>;
+}
+
+/// The `Weight` of a `SpanQuery`, scoring its documents given their spans.
+pub struct SpanQueryWeight {
+    field: Field,
+    span_weight: Box<dyn SpanWeight>,
+    similarity_weight: Bm25Weight,
+    scoring_enabled: bool,
+}
+
+impl SpanQueryWeight {
+    /// Creates the weight of a span query.
+    ///
+    /// Returns an error if the field of the query does not have positions indexed.
+    pub fn new<TSpanQuery: SpanQuery>(
+        query: &TSpanQuery,
+        searcher: &Searcher,
+        scoring_enabled: bool,
+    ) -> crate::Result<SpanQueryWeight> {
+        let field = query.field();
+        let field_entry = searcher.schema().get_field_entry(field);
+        let has_positions = field_entry
+            .field_type()
+            .get_index_record_option()
+            .map(IndexRecordOption::has_positions)
+            .unwrap_or(false);
+        if !has_positions {
+            return Err(crate::TantivyError::SchemaError(format!(
+                "Applied span query on field {:?}, which does not have positions indexed",
+                field_entry.name()
+            )));
+        }
+        let mut terms = BTreeMap::new();
+        query.query_terms(&mut terms);
+        let terms: Vec<Term> = terms.into_keys().collect();
+        let similarity_weight = Bm25Weight::for_terms(searcher, &terms)?;
+        Ok(SpanQueryWeight {
+            field,
+            span_weight: query.span_weight(),
+            similarity_weight,
+            scoring_enabled,
+        })
+    }
+
+    fn fieldnorm_reader(&self, reader: &SegmentReader) -> crate::Result<FieldNormReader> {
+        if self.scoring_enabled {
+            reader.get_fieldnorms_reader(self.field)
+        } else {
+            Ok(FieldNormReader::constant(reader.max_doc(), 1))
+        }
+    }
+
+    /// Returns the `SpanScorer` of the segment,
+    /// or `None` if no document of the segment can match.
+    pub fn span_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: Score,
+    ) -> crate::Result<Option<SpanScorer>> {
+        let spans = match self.span_weight.spans(reader)? {
+            Some(spans) => spans,
+            None => return Ok(None),
+        };
+        Ok(Some(SpanScorer::new(
+            spans,
+            self.similarity_weight.boost_by(boost),
+            self.fieldnorm_reader(reader)?,
+        )))
+    }
+}
+
+impl Weight for SpanQueryWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        if let Some(scorer) = self.span_scorer(reader, boost)? {
+            Ok(Box::new(scorer))
+        } else {
+            Ok(Box::new(EmptyScorer))
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self
+            .span_scorer(reader, 1.0)?
+            .ok_or_else(|| does_not_match(doc))?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        let fieldnorm_id = self.fieldnorm_reader(reader)?.fieldnorm_id(doc);
+        let mut explanation = Explanation::new("Span Scorer", scorer.score());
+        explanation.add_detail(
+            self.similarity_weight
+                .explain_freq(fieldnorm_id, scorer.span_freq()),
+        );
+        Ok(explanation)
+    }
+}
@@ -0,0 +1,154 @@
+use crate::error::TantivyError;
+use crate::query::{AutomatonWeight, Query, Weight};
+use crate::schema::Field;
+use crate::Searcher;
+use std::sync::Arc;
+use tantivy_fst::Regex;
+
+/// Translates a wildcard pattern into the equivalent regular expression.
+///
+/// `*` matches any sequence of characters, `?` matches any single character,
+/// and `\` escapes the character following it.
+fn wildcard_to_regex(pattern: &str) -> String {
+    let mut regex_pattern = String::with_capacity(pattern.len() * 2);
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex_pattern.push_str(".*"),
+            '?' => regex_pattern.push('.'),
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    regex_pattern.push_str(&regex::escape(&escaped.to_string()));
+                }
+            }
+            _ => regex_pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex_pattern
+}
+
+/// A Wildcard Query matches all of the documents
+/// containing a term matching a wildcard pattern.
+///
+/// In the pattern, `*` matches any sequence of characters (including none),
+/// `?` matches exactly one character, and `\` escapes the character following it.
+/// The pattern is matched against the indexed terms, as is: it goes through
+/// no tokenizer.
+///
+/// Like the `RegexQuery`, all of the matching documents get a score of 1.0.
+///
+/// ```rust
+/// use tantivy::collector::Count;
+/// use tantivy::query::WildcardQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let schema = schema_builder.build();
+/// let index = Index::create_in_ram(schema);
+/// {
+///     let mut index_writer = index.writer(3_000_000)?;
+///     index_writer.add_document(doc!(title => "A test"));
+///     index_writer.add_document(doc!(title => "Some text"));
+///     index_writer.add_document(doc!(title => "The tempest"));
+///     index_writer.commit()?;
+/// }
+/// let searcher = index.reader()?.searcher();
+/// let query = WildcardQuery::from_pattern("te?t", title)?;
+/// assert_eq!(searcher.search(&query, &Count)?, 2);
+/// let query = WildcardQuery::from_pattern("te*t", title)?;
+/// assert_eq!(searcher.search(&query, &Count)?, 3);
+/// # Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct WildcardQuery {
+    pattern: String,
+    regex: Arc<Regex>,
+    field: Field,
+}
+
+impl WildcardQuery {
+    /// Creates a new `WildcardQuery` from a wildcard pattern.
+    pub fn from_pattern(pattern: &str, field: Field) -> crate::Result<WildcardQuery> {
+        let regex = Regex::new(&wildcard_to_regex(pattern))
+            .map_err(|_| TantivyError::InvalidArgument(pattern.to_string()))?;
+        Ok(WildcardQuery {
+            pattern: pattern.to_string(),
+            regex: Arc::new(regex),
+            field,
+        })
+    }
+
+    /// Returns the wildcard pattern of the query.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the field of the query.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    fn specialized_weight(&self) -> AutomatonWeight<Regex> {
+        AutomatonWeight::new(self.field, self.regex.clone())
+    }
+}
+
+impl Query for WildcardQuery {
+    fn weight(
+        &self,
+        _searcher: &Searcher,
+        _scoring_enabled: bool,
+    ) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(self.specialized_weight()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{wildcard_to_regex, WildcardQuery};
+    use crate::collector::Count;
+    use crate::schema::{Schema, STRING};
+    use crate::Index;
+
+    #[test]
+    pub fn test_wildcard_to_regex() {
+        assert_eq!(wildcard_to_regex("te?t*"), "te.t.*");
+        assert_eq!(wildcard_to_regex("a.b+c"), "a\\.b\\+c");
+        assert_eq!(wildcard_to_regex("why\\?*"), "why\\?.*");
+    }
+
+    #[test]
+    pub fn test_wildcard_query() {
+        let mut schema_builder = Schema::builder();
+        let code = schema_builder.add_text_field("code", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_for_tests().unwrap();
+            index_writer.add_document(doc!(code => "ab-12.c"));
+            index_writer.add_document(doc!(code => "ab-123"));
+            index_writer.add_document(doc!(code => "abc?"));
+            index_writer.add_document(doc!(code => "ébène"));
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let count = |pattern: &str| {
+            let query = WildcardQuery::from_pattern(pattern, code).unwrap();
+            searcher.search(&query, &Count).unwrap()
+        };
+        assert_eq!(count("ab*"), 3);
+        assert_eq!(count("ab-12?"), 1);
+        assert_eq!(count("ab-12.?"), 1);
+        assert_eq!(count("*-*"), 2);
+        assert_eq!(count("abc\\?"), 1);
+        assert_eq!(count("abc\\*"), 0);
+        // `?` matches a character, rather than a byte.
+        assert_eq!(count("?b?ne"), 1);
+        assert_eq!(count("*"), 4);
+        assert_eq!(count("ab"), 0);
+    }
+}
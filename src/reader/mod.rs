@@ -9,7 +9,8 @@ use crate::directory::{Directory, WatchCallback};
 use crate::Index;
 use crate::Searcher;
 use crate::SegmentReader;
-use std::sync::Arc;
+use chrono::Utc;
+use std::sync::{Arc, Mutex};
 use std::{convert::TryInto, io};
 
 /// Defines when a new version of the index should be reloaded.
@@ -66,6 +67,7 @@ impl IndexReaderBuilder {
             index: self.index,
             num_searchers: self.num_searchers,
             searcher_pool: Pool::new(),
+            segment_readers: Mutex::new(Vec::new()),
         };
         inner_reader.reload()?;
         let inner_reader_arc = Arc::new(inner_reader);
@@ -125,6 +127,8 @@ struct InnerIndexReader {
     num_searchers: usize,
     searcher_pool: Pool<Searcher>,
     index: Index,
+    // The segment readers of the last reload, reused while they are up to date.
+    segment_readers: Mutex<Vec<SegmentReader>>,
 }
 
 impl InnerIndexReader {
@@ -132,10 +136,25 @@ impl InnerIndexReader {
         let segment_readers: Vec<SegmentReader> = {
             let _meta_lock = self.index.directory().acquire_lock(&META_LOCK)?;
             let searchable_segments = self.searchable_segments()?;
-            searchable_segments
+            let mut previous_segment_readers = self
+                .segment_readers
+                .lock()
+                .expect("Segment readers lock poisoned. This should never happen.");
+            let now = Utc::now().timestamp();
+            let segment_readers = searchable_segments
                 .iter()
-                .map(SegmentReader::open)
-                .collect::<crate::Result<_>>()?
+                .map(|segment| {
+                    let previous_segment_reader = previous_segment_readers
+                        .iter()
+                        .find(|segment_reader| segment_reader.is_up_to_date(segment, now));
+                    match previous_segment_reader {
+                        Some(segment_reader) => Ok(segment_reader.clone()),
+                        None => SegmentReader::open(segment),
+                    }
+                })
+                .collect::<crate::Result<Vec<_>>>()?;
+            *previous_segment_readers = segment_readers.clone();
+            segment_readers
         };
         let schema = self.index.schema();
         let searchers: Vec<Searcher> = std::iter::repeat_with(|| {
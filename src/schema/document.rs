@@ -212,7 +212,7 @@ impl BinarySerializable for Document {
 mod tests {
 
     use crate::schema::*;
-    use crate::tokenizer::{PreTokenizedString, Token};
+    use crate::tokenizer::{PreTokenizedString, Token, TokenType};
 
     #[test]
     fn test_doc() {
@@ -237,6 +237,7 @@ mod tests {
                 position: 0,
                 text: String::from("A"),
                 position_length: 1,
+                token_type: TokenType::Word,
             }],
         };
 
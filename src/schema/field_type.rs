@@ -229,7 +229,7 @@ mod tests {
     use crate::schema::TextOptions;
     use crate::schema::Value;
     use crate::schema::{Schema, INDEXED};
-    use crate::tokenizer::{PreTokenizedString, Token};
+    use crate::tokenizer::{PreTokenizedString, Token, TokenType};
     use crate::{DateTime, Document};
     use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Utc};
 
@@ -316,6 +316,7 @@ mod tests {
                     position: 0,
                     text: String::from("The"),
                     position_length: 1,
+                    token_type: TokenType::Word,
                 },
                 Token {
                     offset_from: 4,
@@ -323,6 +324,7 @@ mod tests {
                     position: 1,
                     text: String::from("Old"),
                     position_length: 1,
+                    token_type: TokenType::Word,
                 },
                 Token {
                     offset_from: 8,
@@ -330,6 +332,7 @@ mod tests {
                     position: 2,
                     text: String::from("Man"),
                     position_length: 1,
+                    token_type: TokenType::Word,
                 },
             ],
         });
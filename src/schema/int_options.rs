@@ -21,6 +21,12 @@ pub struct IntOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     fast: Option<Cardinality>,
     stored: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    expiry: bool,
+}
+
+fn is_false(val: &bool) -> bool {
+    !*val
 }
 
 impl IntOptions {
@@ -39,6 +45,11 @@ impl IntOptions {
         self.fast.is_some()
     }
 
+    /// Returns true iff the field holds the expiry date of the documents.
+    pub fn is_expiry(&self) -> bool {
+        self.expiry
+    }
+
     /// Set the field as stored.
     ///
     /// Only the fields that are set as *stored* are
@@ -68,6 +79,19 @@ impl IntOptions {
         self
     }
 
+    /// Set the field as the expiry date of the documents.
+    ///
+    /// Only a single-valued date fast field can be used as expiry field,
+    /// and a schema can have at most one of them.
+    ///
+    /// Documents whose expiry date has passed are hidden from searchers,
+    /// and dropped when their segment gets merged. Documents without
+    /// any value for the expiry field never expire.
+    pub fn set_expiry(mut self) -> IntOptions {
+        self.expiry = true;
+        self
+    }
+
     /// Returns the cardinality of the fastfield.
     ///
     /// If the field has not been declared as a fastfield, then
@@ -83,6 +107,7 @@ impl Default for IntOptions {
             indexed: false,
             stored: false,
             fast: None,
+            expiry: false,
         }
     }
 }
@@ -99,6 +124,7 @@ impl From<FastFlag> for IntOptions {
             indexed: false,
             stored: false,
             fast: Some(Cardinality::SingleValue),
+            expiry: false,
         }
     }
 }
@@ -109,6 +135,7 @@ impl From<StoredFlag> for IntOptions {
             indexed: false,
             stored: true,
             fast: None,
+            expiry: false,
         }
     }
 }
@@ -119,6 +146,7 @@ impl From<IndexedFlag> for IntOptions {
             indexed: true,
             stored: false,
             fast: None,
+            expiry: false,
         }
     }
 }
@@ -132,6 +160,7 @@ impl<T: Into<IntOptions>> BitOr<T> for IntOptions {
             indexed: self.indexed | other.indexed,
             stored: self.stored | other.stored,
             fast: self.fast.or(other.fast),
+            expiry: self.expiry | other.expiry,
         }
     }
 }
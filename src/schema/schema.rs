@@ -183,9 +183,40 @@ impl SchemaBuilder {
         field
     }
 
+    /// Checks that the fields selecting the tokenizer of a text field
+    /// (see `TextFieldIndexing::set_tokenizer_field`) exist.
+    fn check_tokenizer_fields(&self) -> Result<(), String> {
+        for field_entry in &self.fields {
+            let tokenizer_field = match field_entry.field_type() {
+                FieldType::Str(ref text_options) => text_options
+                    .get_indexing_options()
+                    .and_then(|indexing_options| indexing_options.tokenizer_field()),
+                _ => None,
+            };
+            if let Some(tokenizer_field) = tokenizer_field {
+                if !self.fields_map.contains_key(tokenizer_field) {
+                    return Err(format!(
+                        "The field {:?} selecting the tokenizer of the field {:?} does not exist",
+                        tokenizer_field,
+                        field_entry.name()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Finalize the creation of a `Schema`
     /// This will consume your `SchemaBuilder`
+    ///
+    /// # Panics
+    ///
+    /// Panics if a text field selects its tokenizer with a field
+    /// that does not exist.
     pub fn build(self) -> Schema {
+        if let Err(msg) = self.check_tokenizer_fields() {
+            panic!("{}", msg);
+        }
         Schema(Arc::new(InnerSchema {
             fields: self.fields,
             fields_map: self.fields_map,
@@ -249,6 +280,18 @@ impl Schema {
             .map(|(field_id, field_entry)| (Field::from_field_id(field_id as u32), field_entry))
     }
 
+    /// Returns the field holding the expiry date of the documents, if any.
+    ///
+    /// See [`IntOptions::set_expiry`](./struct.IntOptions.html#method.set_expiry).
+    pub fn expiry_field(&self) -> Option<Field> {
+        self.fields()
+            .find(|(_, field_entry)| match field_entry.field_type() {
+                FieldType::Date(int_options) => int_options.is_expiry(),
+                _ => false,
+            })
+            .map(|(field, _)| field)
+    }
+
     /// Creates a new builder.
     pub fn builder() -> SchemaBuilder {
         SchemaBuilder::default()
@@ -380,6 +423,9 @@ impl<'de> Deserialize<'de> for Schema {
                     schema.add_field(value);
                 }
 
+                schema
+                    .check_tokenizer_fields()
+                    .map_err(serde::de::Error::custom)?;
                 Ok(schema.build())
             }
         }
@@ -741,6 +787,37 @@ mod tests {
         }
     }
 
+    #[test]
+    #[should_panic(expected = "The field \"lang\" selecting the tokenizer")]
+    pub fn test_schema_unknown_tokenizer_field() {
+        let mut schema_builder = Schema::builder();
+        let indexing = TextFieldIndexing::default().set_tokenizer_field("lang");
+        schema_builder.add_text_field(
+            "body",
+            TextOptions::default().set_indexing_options(indexing),
+        );
+        schema_builder.build();
+    }
+
+    #[test]
+    pub fn test_schema_deserialize_unknown_tokenizer_field() {
+        let schema_content = r#"[
+  {
+    "name": "body",
+    "type": "text",
+    "options": {
+      "indexing": {
+        "record": "basic",
+        "tokenizer": "default",
+        "tokenizer_field": "lang"
+      },
+      "stored": false
+    }
+  }
+]"#;
+        assert!(serde_json::from_str::<Schema>(schema_content).is_err());
+    }
+
     #[test]
     pub fn test_schema_add_field() {
         let mut schema_builder = SchemaBuilder::default();
@@ -51,10 +51,18 @@ impl Default for TextOptions {
 /// - the amount of information that should be stored about the presence of a term in a document.
 /// Essentially, should we store the term frequency and/or the positions (See [`IndexRecordOption`](./enum.IndexRecordOption.html)).
 /// - the name of the `Tokenizer` that should be used to process the field.
+/// - optionally, the name of a different `Tokenizer` to process the queries on the field.
+/// - optionally, the name of a field selecting the `Tokenizer` of each document.
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct TextFieldIndexing {
     record: IndexRecordOption,
     tokenizer: Cow<'static, str>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    search_tokenizer: Option<Cow<'static, str>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tokenizer_field: Option<Cow<'static, str>>,
 }
 
 impl Default for TextFieldIndexing {
@@ -62,6 +70,8 @@ impl Default for TextFieldIndexing {
         TextFieldIndexing {
             tokenizer: Cow::Borrowed("default"),
             record: IndexRecordOption::Basic,
+            search_tokenizer: None,
+            tokenizer_field: None,
         }
     }
 }
@@ -78,6 +88,45 @@ impl TextFieldIndexing {
         &self.tokenizer
     }
 
+    /// Sets the tokenizer to be used by the `QueryParser` to process the queries on this field.
+    ///
+    /// For instance, a field indexed with edge n-grams for autocompletion
+    /// should be searched without n-grams.
+    /// By default, queries are processed with the indexing tokenizer.
+    pub fn set_search_tokenizer(mut self, tokenizer_name: &str) -> TextFieldIndexing {
+        self.search_tokenizer = Some(Cow::Owned(tokenizer_name.to_string()));
+        self
+    }
+
+    /// Returns the tokenizer that will be used to process the queries on this field.
+    pub fn search_tokenizer(&self) -> &str {
+        self.search_tokenizer.as_deref().unwrap_or(&self.tokenizer)
+    }
+
+    /// Selects the tokenizer of each document from the value of another field.
+    ///
+    /// The first text value of the field `field_name` in the document is used as
+    /// the name of the tokenizer. Documents without such a value are processed by
+    /// the tokenizer of the field. So are the documents whose value is not a registered
+    /// tokenizer, after a warning naming the unknown tokenizer is logged.
+    ///
+    /// For instance, a multilingual `body` field can be analyzed according to a `lang`
+    /// field, given analyzers registered under the language codes.
+    /// The tokenizers used are recorded in the segment metas, and
+    /// `QueryParser::set_field_tokenizers_from_searcher` makes the query parser
+    /// search the field with all of them.
+    ///
+    /// The field `field_name` must exist in the schema: `SchemaBuilder::build` panics otherwise.
+    pub fn set_tokenizer_field(mut self, field_name: &str) -> TextFieldIndexing {
+        self.tokenizer_field = Some(Cow::Owned(field_name.to_string()));
+        self
+    }
+
+    /// Returns the name of the field selecting the tokenizer of each document, if any.
+    pub fn tokenizer_field(&self) -> Option<&str> {
+        self.tokenizer_field.as_deref()
+    }
+
     /// Sets which information should be indexed with the tokens.
     ///
     /// See [IndexRecordOption](./enum.IndexRecordOption.html) for more detail.
@@ -98,6 +147,8 @@ impl TextFieldIndexing {
 pub const STRING: TextOptions = TextOptions {
     indexing: Some(TextFieldIndexing {
         tokenizer: Cow::Borrowed("raw"),
+        search_tokenizer: None,
+        tokenizer_field: None,
         record: IndexRecordOption::Basic,
     }),
     stored: false,
@@ -107,6 +158,8 @@ pub const STRING: TextOptions = TextOptions {
 pub const TEXT: TextOptions = TextOptions {
     indexing: Some(TextFieldIndexing {
         tokenizer: Cow::Borrowed("default"),
+        search_tokenizer: None,
+        tokenizer_field: None,
         record: IndexRecordOption::WithFreqsAndPositions,
     }),
     stored: false,
@@ -169,6 +222,39 @@ mod tests {
                 if text_options.get_indexing_options().unwrap().tokenizer() == "default"));
     }
 
+    #[test]
+    fn test_search_tokenizer() {
+        let indexing = TextFieldIndexing::default().set_tokenizer("en_stem");
+        assert_eq!(indexing.search_tokenizer(), "en_stem");
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert_eq!(json, r#"{"record":"basic","tokenizer":"en_stem"}"#);
+        let indexing = indexing.set_search_tokenizer("default");
+        assert_eq!(indexing.tokenizer(), "en_stem");
+        assert_eq!(indexing.search_tokenizer(), "default");
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert_eq!(
+            json,
+            r#"{"record":"basic","tokenizer":"en_stem","search_tokenizer":"default"}"#
+        );
+        let deserialized: TextFieldIndexing = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, indexing);
+    }
+
+    #[test]
+    fn test_tokenizer_field() {
+        let indexing = TextFieldIndexing::default();
+        assert_eq!(indexing.tokenizer_field(), None);
+        let indexing = indexing.set_tokenizer_field("lang");
+        assert_eq!(indexing.tokenizer_field(), Some("lang"));
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert_eq!(
+            json,
+            r#"{"record":"basic","tokenizer":"default","tokenizer_field":"lang"}"#
+        );
+        let deserialized: TextFieldIndexing = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, indexing);
+    }
+
     #[test]
     fn test_cmp_index_record_option() {
         assert!(IndexRecordOption::WithFreqsAndPositions > IndexRecordOption::WithFreqs);
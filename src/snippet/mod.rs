@@ -239,7 +239,7 @@ impl SnippetGenerator {
         field: Field,
     ) -> crate::Result<SnippetGenerator> {
         let mut terms = BTreeMap::new();
-        query.query_terms(&mut terms);
+        query.query_terms_with_searcher(searcher, &mut terms)?;
         let mut terms_text: BTreeMap<String, Score> = Default::default();
         for (term, _) in terms {
             if term.field() != field {
@@ -296,7 +296,7 @@ mod tests {
     use super::{search_fragments, select_best_fragment_combination};
     use crate::query::QueryParser;
     use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions, TEXT};
-    use crate::tokenizer::SimpleTokenizer;
+    use crate::tokenizer::{HtmlStripCharFilter, SimpleTokenizer, TextAnalyzer};
     use crate::Index;
     use crate::SnippetGenerator;
     use maplit::btreemap;
@@ -558,4 +558,17 @@ Survey in 2016, 2017, and 2018."#;
             assert_eq!(snippet.to_html(), "<b>Rust</b> is syntactically similar to C++[according to whom?],\nbut its <b>designers</b> intend it to");
         }
     }
+
+    #[test]
+    fn test_snippet_with_char_filter() {
+        let terms = btreemap! {
+            String::from("chips") => 1.0,
+        };
+        let tokenizer = TextAnalyzer::from(SimpleTokenizer).char_filter(HtmlStripCharFilter);
+        let text = "<p>Fish &amp; <b>chips</b></p>";
+        let fragments = search_fragments(&tokenizer, text, &terms, 100);
+        let snippet = select_best_fragment_combination(&fragments[..], text);
+        assert_eq!(snippet.highlighted(), &[17..22]);
+        assert_eq!(&text[17..22], "chips");
+    }
 }
@@ -0,0 +1,531 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let definition: AnalyzerDefinition = serde_json::from_str(r#"{
+//!     "tokenizer": {"type": "simple"},
+//!     "filters": [
+//!         {"type": "remove_long", "limit": 40},
+//!         {"type": "lowercase"},
+//!         {"type": "stemmer", "language": "French"}
+//!     ]
+//! }"#).unwrap();
+//! let analyzer = definition.build().unwrap();
+//!
+//! let mut stream = analyzer.token_stream("Chevaux");
+//! assert_eq!(stream.next().unwrap().text, "cheval");
+//! assert!(stream.next().is_none());
+//! ```
+use super::dictionary_compound_word_filter;
+use super::stop_word_filter::stop_words;
+use super::{
+    AlphaNumOnlyFilter, AsciiFoldingFilter, CjkBigramFilter, DictionaryCompoundWordFilter,
+    ElisionFilter, HtmlStripCharFilter, Language, LowerCaser, MappingCharFilter, NgramTokenizer,
+    NormalizationForm, PatternCaptureFilter, PhoneticAlgorithm, PhoneticFilter, RawTokenizer,
+    RegexReplaceCharFilter, RegexTokenizer, RemoveLongFilter, ShingleFilter, SimpleTokenizer,
+    StandardTokenizer, Stemmer, StopWordFilter, SynonymFilter, SynonymMap, TextAnalyzer,
+    UnicodeNormalizationFilter, WordDelimiterFilter,
+};
+use crate::TantivyError;
+use serde::{Deserialize, Serialize};
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_separator() -> String {
+    " ".to_string()
+}
+
+fn default_filler_token() -> String {
+    "_".to_string()
+}
+
+fn default_min_word_size() -> usize {
+    dictionary_compound_word_filter::DEFAULT_MIN_WORD_SIZE
+}
+
+fn default_min_subword_size() -> usize {
+    dictionary_compound_word_filter::DEFAULT_MIN_SUBWORD_SIZE
+}
+
+fn default_max_subword_size() -> usize {
+    dictionary_compound_word_filter::DEFAULT_MAX_SUBWORD_SIZE
+}
+
+/// Definition of a [`TextAnalyzer`](./struct.TextAnalyzer.html) as data.
+///
+/// Contrary to a `TextAnalyzer`, an `AnalyzerDefinition` can be serialized.
+/// Analyzer definitions registered in the
+/// [`IndexSettings`](../struct.IndexSettings.html#structfield.analyzers) are persisted
+/// in the index `meta.json` file, and automatically instantiated when the index is opened.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AnalyzerDefinition {
+    /// Char filters, applied in order before the tokenizer.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub char_filters: Vec<CharFilterDefinition>,
+    /// Tokenizer.
+    pub tokenizer: TokenizerDefinition,
+    /// Token filters, applied in order after the tokenizer.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<TokenFilterDefinition>,
+}
+
+/// Definition of a [`Tokenizer`](./trait.Tokenizer.html).
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TokenizerDefinition {
+    /// [`RawTokenizer`](./struct.RawTokenizer.html)
+    Raw,
+    /// [`SimpleTokenizer`](./struct.SimpleTokenizer.html)
+    Simple,
+    /// [`StandardTokenizer`](./struct.StandardTokenizer.html)
+    Standard,
+    /// [`NgramTokenizer`](./struct.NgramTokenizer.html)
+    #[allow(missing_docs)]
+    Ngram {
+        min_gram: usize,
+        max_gram: usize,
+        #[serde(default)]
+        prefix_only: bool,
+    },
+    /// [`RegexTokenizer`](./struct.RegexTokenizer.html), splitting the text on
+    /// `pattern` if `split` is true, or emitting its matches otherwise.
+    #[allow(missing_docs)]
+    Regex {
+        pattern: String,
+        #[serde(default)]
+        split: bool,
+    },
+}
+
+/// Definition of a [`CharFilter`](./trait.CharFilter.html).
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CharFilterDefinition {
+    /// [`HtmlStripCharFilter`](./struct.HtmlStripCharFilter.html)
+    HtmlStrip,
+    /// [`MappingCharFilter`](./struct.MappingCharFilter.html)
+    #[allow(missing_docs)]
+    Mapping { mappings: Vec<(String, String)> },
+    /// [`RegexReplaceCharFilter`](./struct.RegexReplaceCharFilter.html)
+    #[allow(missing_docs)]
+    RegexReplace {
+        pattern: String,
+        replacement: String,
+    },
+}
+
+/// Definition of a [`TokenFilter`](./trait.TokenFilter.html).
+///
+/// Omitted parameters take the default value of the corresponding filter.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[allow(missing_docs)]
+pub enum TokenFilterDefinition {
+    /// [`AlphaNumOnlyFilter`](./struct.AlphaNumOnlyFilter.html)
+    AlphaNumOnly,
+    /// [`AsciiFoldingFilter`](./struct.AsciiFoldingFilter.html)
+    AsciiFolding,
+    /// [`CjkBigramFilter`](./struct.CjkBigramFilter.html)
+    CjkBigram {
+        #[serde(default)]
+        output_unigrams: bool,
+    },
+    /// [`DictionaryCompoundWordFilter`](./struct.DictionaryCompoundWordFilter.html),
+    /// decomposing compounds into the given words.
+    DictionaryCompoundWord {
+        words: Vec<String>,
+        #[serde(default = "default_min_word_size")]
+        min_word_size: usize,
+        #[serde(default = "default_min_subword_size")]
+        min_subword_size: usize,
+        #[serde(default = "default_max_subword_size")]
+        max_subword_size: usize,
+        #[serde(default)]
+        only_longest_match: bool,
+    },
+    /// [`ElisionFilter`](./struct.ElisionFilter.html), removing the given articles.
+    Elision { articles: Vec<String> },
+    /// [`LowerCaser`](./struct.LowerCaser.html)
+    Lowercase,
+    /// [`PatternCaptureFilter`](./struct.PatternCaptureFilter.html)
+    PatternCapture {
+        patterns: Vec<String>,
+        #[serde(default = "default_true")]
+        preserve_original: bool,
+    },
+    /// [`PhoneticFilter`](./struct.PhoneticFilter.html)
+    Phonetic {
+        algorithm: PhoneticAlgorithm,
+        #[serde(default = "default_true")]
+        inject: bool,
+    },
+    /// [`RemoveLongFilter`](./struct.RemoveLongFilter.html)
+    RemoveLong { limit: usize },
+    /// [`ShingleFilter`](./struct.ShingleFilter.html)
+    Shingle {
+        min_shingle_size: usize,
+        max_shingle_size: usize,
+        #[serde(default = "default_true")]
+        output_unigrams: bool,
+        #[serde(default = "default_separator")]
+        separator: String,
+        #[serde(default = "default_filler_token")]
+        filler_token: String,
+    },
+    /// [`Stemmer`](./struct.Stemmer.html)
+    Stemmer { language: Language },
+    /// [`StopWordFilter`](./struct.StopWordFilter.html), removing the given words
+    /// and the built-in stop words of `language`, if any.
+    StopWords {
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        language: Option<Language>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        words: Vec<String>,
+    },
+    /// [`SynonymFilter`](./struct.SynonymFilter.html), given rules in the
+    /// Solr format (see [`SynonymMap::from_solr`](./struct.SynonymMap.html#method.from_solr)).
+    Synonyms {
+        rules: String,
+        #[serde(default = "default_true")]
+        expand: bool,
+    },
+    /// [`UnicodeNormalizationFilter`](./struct.UnicodeNormalizationFilter.html)
+    UnicodeNormalization {
+        form: NormalizationForm,
+        #[serde(default = "default_true")]
+        case_folding: bool,
+    },
+    /// [`WordDelimiterFilter`](./struct.WordDelimiterFilter.html)
+    WordDelimiter {
+        #[serde(default = "default_true")]
+        generate_word_parts: bool,
+        #[serde(default = "default_true")]
+        generate_number_parts: bool,
+        #[serde(default)]
+        catenate_words: bool,
+        #[serde(default)]
+        catenate_numbers: bool,
+        #[serde(default)]
+        catenate_all: bool,
+        #[serde(default)]
+        preserve_original: bool,
+        #[serde(default = "default_true")]
+        split_on_case_change: bool,
+        #[serde(default = "default_true")]
+        split_on_numerics: bool,
+    },
+}
+
+impl AnalyzerDefinition {
+    /// Creates an `AnalyzerDefinition` given its tokenizer.
+    pub fn new(tokenizer: TokenizerDefinition) -> AnalyzerDefinition {
+        AnalyzerDefinition {
+            char_filters: Vec::new(),
+            tokenizer,
+            filters: Vec::new(),
+        }
+    }
+
+    /// Appends a char filter to the definition.
+    pub fn char_filter(mut self, char_filter: CharFilterDefinition) -> AnalyzerDefinition {
+        self.char_filters.push(char_filter);
+        self
+    }
+
+    /// Appends a token filter to the definition.
+    pub fn filter(mut self, filter: TokenFilterDefinition) -> AnalyzerDefinition {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Instantiates the `TextAnalyzer` described by the definition.
+    ///
+    /// Returns an error if one of the parameters is invalid,
+    /// such as a regular expression that does not compile.
+    pub fn build(&self) -> crate::Result<TextAnalyzer> {
+        let mut analyzer = self.tokenizer.build()?;
+        for char_filter in &self.char_filters {
+            analyzer = char_filter.add_to(analyzer)?;
+        }
+        for filter in &self.filters {
+            analyzer = filter.add_to(analyzer)?;
+        }
+        Ok(analyzer)
+    }
+}
+
+impl TokenizerDefinition {
+    fn build(&self) -> crate::Result<TextAnalyzer> {
+        let analyzer = match self {
+            TokenizerDefinition::Raw => TextAnalyzer::from(RawTokenizer),
+            TokenizerDefinition::Simple => TextAnalyzer::from(SimpleTokenizer),
+            TokenizerDefinition::Standard => TextAnalyzer::from(StandardTokenizer),
+            TokenizerDefinition::Ngram {
+                min_gram,
+                max_gram,
+                prefix_only,
+            } => {
+                if *min_gram == 0 || min_gram > max_gram {
+                    return Err(TantivyError::InvalidArgument(format!(
+                        "Invalid ngram sizes: {}..{}",
+                        min_gram, max_gram
+                    )));
+                }
+                TextAnalyzer::from(NgramTokenizer::new(*min_gram, *max_gram, *prefix_only))
+            }
+            TokenizerDefinition::Regex { pattern, split } => {
+                if *split {
+                    TextAnalyzer::from(RegexTokenizer::split(pattern)?)
+                } else {
+                    TextAnalyzer::from(RegexTokenizer::new(pattern)?)
+                }
+            }
+        };
+        Ok(analyzer)
+    }
+}
+
+impl CharFilterDefinition {
+    fn add_to(&self, analyzer: TextAnalyzer) -> crate::Result<TextAnalyzer> {
+        let analyzer = match self {
+            CharFilterDefinition::HtmlStrip => analyzer.char_filter(HtmlStripCharFilter),
+            CharFilterDefinition::Mapping { mappings } => {
+                analyzer.char_filter(MappingCharFilter::new(mappings.clone()))
+            }
+            CharFilterDefinition::RegexReplace {
+                pattern,
+                replacement,
+            } => analyzer.char_filter(RegexReplaceCharFilter::new(pattern, replacement)?),
+        };
+        Ok(analyzer)
+    }
+}
+
+impl TokenFilterDefinition {
+    fn add_to(&self, analyzer: TextAnalyzer) -> crate::Result<TextAnalyzer> {
+        let analyzer = match self {
+            TokenFilterDefinition::AlphaNumOnly => analyzer.filter(AlphaNumOnlyFilter),
+            TokenFilterDefinition::AsciiFolding => analyzer.filter(AsciiFoldingFilter),
+            TokenFilterDefinition::DictionaryCompoundWord {
+                words,
+                min_word_size,
+                min_subword_size,
+                max_subword_size,
+                only_longest_match,
+            } => analyzer.filter(
+                DictionaryCompoundWordFilter::new(words.clone())
+                    .set_min_word_size(*min_word_size)
+                    .set_min_subword_size(*min_subword_size)
+                    .set_max_subword_size(*max_subword_size)
+                    .set_only_longest_match(*only_longest_match),
+            ),
+            TokenFilterDefinition::Elision { articles } => {
+                analyzer.filter(ElisionFilter::with_articles(articles.clone()))
+            }
+            TokenFilterDefinition::CjkBigram { output_unigrams } => {
+                analyzer.filter(CjkBigramFilter::new(*output_unigrams))
+            }
+            TokenFilterDefinition::Lowercase => analyzer.filter(LowerCaser),
+            TokenFilterDefinition::PatternCapture {
+                patterns,
+                preserve_original,
+            } => {
+                let patterns: Vec<&str> = patterns.iter().map(String::as_str).collect();
+                analyzer.filter(PatternCaptureFilter::new(&patterns, *preserve_original)?)
+            }
+            TokenFilterDefinition::Phonetic { algorithm, inject } => {
+                analyzer.filter(PhoneticFilter::new(*algorithm).set_inject(*inject))
+            }
+            TokenFilterDefinition::RemoveLong { limit } => {
+                analyzer.filter(RemoveLongFilter::limit(*limit))
+            }
+            TokenFilterDefinition::Shingle {
+                min_shingle_size,
+                max_shingle_size,
+                output_unigrams,
+                separator,
+                filler_token,
+            } => {
+                if *min_shingle_size < 2 || min_shingle_size > max_shingle_size {
+                    return Err(TantivyError::InvalidArgument(format!(
+                        "Invalid shingle sizes: {}..{}",
+                        min_shingle_size, max_shingle_size
+                    )));
+                }
+                analyzer.filter(
+                    ShingleFilter::new(*min_shingle_size, *max_shingle_size)
+                        .set_output_unigrams(*output_unigrams)
+                        .set_separator(separator)
+                        .set_filler_token(filler_token),
+                )
+            }
+            TokenFilterDefinition::Stemmer { language } => analyzer.filter(Stemmer::new(*language)),
+            TokenFilterDefinition::StopWords { language, words } => {
+                let mut words = words.clone();
+                if let Some(language) = *language {
+                    words.extend(stop_words(language).iter().map(|&word| word.to_string()));
+                }
+                analyzer.filter(StopWordFilter::remove(words))
+            }
+            TokenFilterDefinition::Synonyms { rules, expand } => {
+                analyzer.filter(SynonymFilter::new(SynonymMap::from_solr(rules, *expand)?))
+            }
+            TokenFilterDefinition::UnicodeNormalization { form, case_folding } => analyzer
+                .filter(UnicodeNormalizationFilter::new(*form).set_case_folding(*case_folding)),
+            TokenFilterDefinition::WordDelimiter {
+                generate_word_parts,
+                generate_number_parts,
+                catenate_words,
+                catenate_numbers,
+                catenate_all,
+                preserve_original,
+                split_on_case_change,
+                split_on_numerics,
+            } => analyzer.filter(
+                WordDelimiterFilter::default()
+                    .set_generate_word_parts(*generate_word_parts)
+                    .set_generate_number_parts(*generate_number_parts)
+                    .set_catenate_words(*catenate_words)
+                    .set_catenate_numbers(*catenate_numbers)
+                    .set_catenate_all(*catenate_all)
+                    .set_preserve_original(*preserve_original)
+                    .set_split_on_case_change(*split_on_case_change)
+                    .set_split_on_numerics(*split_on_numerics),
+            ),
+        };
+        Ok(analyzer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AnalyzerDefinition, CharFilterDefinition, TokenFilterDefinition, TokenizerDefinition,
+    };
+    use crate::tokenizer::{PhoneticAlgorithm, Token};
+
+    fn token_texts(definition: &AnalyzerDefinition, text: &str) -> Vec<String> {
+        let analyzer = definition.build().unwrap();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.text.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+
+    #[test]
+    fn test_analyzer_definition_serialization() {
+        let definition = AnalyzerDefinition::new(TokenizerDefinition::Standard)
+            .char_filter(CharFilterDefinition::HtmlStrip)
+            .filter(TokenFilterDefinition::Lowercase)
+            .filter(TokenFilterDefinition::Phonetic {
+                algorithm: PhoneticAlgorithm::Soundex,
+                inject: false,
+            });
+        let json = serde_json::to_string(&definition).unwrap();
+        assert_eq!(
+            json,
+            r#"{"char_filters":[{"type":"html_strip"}],"tokenizer":{"type":"standard"},"filters":[{"type":"lowercase"},{"type":"phonetic","algorithm":"Soundex","inject":false}]}"#
+        );
+        let deserialized: AnalyzerDefinition = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, definition);
+        assert_eq!(
+            token_texts(&definition, "<b>Robert</b> Rupert"),
+            vec!["R163", "R163"]
+        );
+    }
+
+    #[test]
+    fn test_analyzer_definition_defaults() {
+        let definition: AnalyzerDefinition = serde_json::from_str(
+            r#"{
+                "tokenizer": {"type": "regex", "pattern": " +", "split": true},
+                "filters": [
+                    {"type": "word_delimiter", "split_on_case_change": false},
+                    {"type": "shingle", "min_shingle_size": 2, "max_shingle_size": 2, "output_unigrams": false}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(token_texts(&definition, "WiFi-5 x"), vec!["WiFi 5", "5 x"]);
+    }
+
+    #[test]
+    fn test_analyzer_definition_stop_words() {
+        let definition: AnalyzerDefinition = serde_json::from_str(
+            r#"{
+                "tokenizer": {"type": "standard"},
+                "filters": [
+                    {"type": "lowercase"},
+                    {"type": "elision", "articles": ["l", "d"]},
+                    {"type": "stop_words", "language": "French", "words": ["avion"]}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            token_texts(&definition, "L'avion d'Alice est là"),
+            vec!["alice", "là"]
+        );
+    }
+
+    #[test]
+    fn test_analyzer_definition_dictionary_compound_word() {
+        let definition: AnalyzerDefinition = serde_json::from_str(
+            r#"{
+                "tokenizer": {"type": "simple"},
+                "filters": [
+                    {"type": "lowercase"},
+                    {"type": "dictionary_compound_word", "words": ["fuß", "fußball", "schuh"],
+                     "only_longest_match": true}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            token_texts(&definition, "Fußballschuhe"),
+            vec!["fußballschuhe", "fußball", "schuh"]
+        );
+    }
+
+    #[test]
+    fn test_analyzer_definition_invalid() {
+        let invalid_definitions = [
+            AnalyzerDefinition::new(TokenizerDefinition::Regex {
+                pattern: "(".to_string(),
+                split: false,
+            }),
+            AnalyzerDefinition::new(TokenizerDefinition::Ngram {
+                min_gram: 3,
+                max_gram: 2,
+                prefix_only: false,
+            }),
+            AnalyzerDefinition::new(TokenizerDefinition::Simple).filter(
+                TokenFilterDefinition::Shingle {
+                    min_shingle_size: 1,
+                    max_shingle_size: 2,
+                    output_unigrams: true,
+                    separator: " ".to_string(),
+                    filler_token: "_".to_string(),
+                },
+            ),
+            AnalyzerDefinition::new(TokenizerDefinition::Simple).char_filter(
+                CharFilterDefinition::RegexReplace {
+                    pattern: "[".to_string(),
+                    replacement: String::new(),
+                },
+            ),
+        ];
+        for definition in &invalid_definitions {
+            assert!(definition.build().is_err());
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::ops::{Deref, Range};
+
+/// Records how the offsets of a text rewritten by a [`CharFilter`](./trait.CharFilter.html)
+/// map to the offsets of the original text.
+///
+/// The char filter records each of the ranges of the original text it replaced
+/// (or removed), along with the corresponding range of the rewritten text.
+/// Offsets located inside a replacement are mapped to the boundaries of the
+/// replaced range, so that tokens always point to valid ranges of the original text.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OffsetCorrections {
+    // (range of the rewritten text, range of the original text),
+    // sorted by range of the rewritten text.
+    replacements: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl OffsetCorrections {
+    /// Records that the range `range` of the rewritten text replaces
+    /// the range `original_range` of the original text.
+    ///
+    /// Replacements must be added in increasing order, and must not overlap.
+    pub fn add_replacement(&mut self, range: Range<usize>, original_range: Range<usize>) {
+        if let Some((last_range, last_original_range)) = self.replacements.last() {
+            assert!(
+                last_range.end <= range.start && last_original_range.end <= original_range.start,
+                "Replacements must be added in increasing order"
+            );
+        }
+        self.replacements.push((range, original_range));
+    }
+
+    /// Returns the offset of the original text corresponding to the offset of the
+    /// beginning of a token in the rewritten text.
+    pub fn correct_start(&self, offset: usize) -> usize {
+        let num_replacements = self
+            .replacements
+            .partition_point(|(range, _)| range.start <= offset);
+        if num_replacements == 0 {
+            return offset;
+        }
+        let (range, original_range) = &self.replacements[num_replacements - 1];
+        if offset < range.end {
+            original_range.start
+        } else {
+            original_range.end + offset - range.end
+        }
+    }
+
+    /// Returns the offset of the original text corresponding to the offset of the
+    /// end of a token in the rewritten text.
+    ///
+    /// Contrary to `correct_start`, text removed right after the token (e.g. an HTML
+    /// closing tag) is not considered as part of the token.
+    pub fn correct_end(&self, offset: usize) -> usize {
+        let num_replacements = self
+            .replacements
+            .partition_point(|(range, _)| range.start < offset);
+        if num_replacements == 0 {
+            return offset;
+        }
+        let (range, original_range) = &self.replacements[num_replacements - 1];
+        if offset <= range.end {
+            original_range.end
+        } else {
+            original_range.end + offset - range.end
+        }
+    }
+}
+
+/// `CharFilter`s rewrite the text before it gets tokenized.
+///
+/// They typically remove markup or normalize characters. Tokens keep pointing
+/// to the original text thanks to the [`OffsetCorrections`](./struct.OffsetCorrections.html)
+/// recorded by the `CharFilter`.
+///
+/// Char filters are applied in the order they were added to the `TextAnalyzer`,
+/// before the tokenizer.
+pub trait CharFilter: 'static + Send + Sync + CharFilterClone {
+    /// Rewrites `text`, and records in `offset_corrections` how the offsets
+    /// of the result map to the offsets of `text`.
+    fn filter(&self, text: &str, offset_corrections: &mut OffsetCorrections) -> String;
+}
+
+pub trait CharFilterClone {
+    fn box_clone(&self) -> BoxCharFilter;
+}
+
+impl<T: CharFilter + Clone> CharFilterClone for T {
+    fn box_clone(&self) -> BoxCharFilter {
+        BoxCharFilter::from(self.clone())
+    }
+}
+
+/// Simple wrapper of `Box<dyn CharFilter>`.
+pub struct BoxCharFilter(Box<dyn CharFilter>);
+
+impl Deref for BoxCharFilter {
+    type Target = dyn CharFilter;
+
+    fn deref(&self) -> &dyn CharFilter {
+        &*self.0
+    }
+}
+
+impl<T: CharFilter> From<T> for BoxCharFilter {
+    fn from(char_filter: T) -> BoxCharFilter {
+        BoxCharFilter(Box::new(char_filter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OffsetCorrections;
+
+    #[test]
+    fn test_offset_corrections() {
+        // "<b>foo</b> &frac12;" => "foo 1/2"
+        let mut offset_corrections = OffsetCorrections::default();
+        assert_eq!(offset_corrections.correct_start(2), 2);
+        offset_corrections.add_replacement(0..0, 0..3);
+        offset_corrections.add_replacement(3..3, 6..10);
+        offset_corrections.add_replacement(4..7, 11..19);
+        assert_eq!(offset_corrections.correct_start(0), 3);
+        assert_eq!(offset_corrections.correct_end(3), 6);
+        assert_eq!(offset_corrections.correct_start(3), 10);
+        assert_eq!(offset_corrections.correct_start(4), 11);
+        assert_eq!(offset_corrections.correct_start(6), 11);
+        assert_eq!(offset_corrections.correct_end(5), 19);
+        assert_eq!(offset_corrections.correct_end(7), 19);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_offset_corrections_order() {
+        let mut offset_corrections = OffsetCorrections::default();
+        offset_corrections.add_replacement(3..4, 3..4);
+        offset_corrections.add_replacement(2..3, 5..6);
+    }
+}
@@ -0,0 +1,322 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let tokenizer = TextAnalyzer::from(StandardTokenizer)
+//!   .filter(CjkBigramFilter::default());
+//!
+//! let mut stream = tokenizer.token_stream("東京都 tokyo");
+//! assert_eq!(stream.next().unwrap().text, "東京");
+//! assert_eq!(stream.next().unwrap().text, "京都");
+//! assert_eq!(stream.next().unwrap().text, "tokyo");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{
+    sub_token_offsets, BoxTokenStream, Token, TokenBuffer, TokenFilter, TokenStream, TokenType,
+};
+
+/// `CjkBigramFilter` splits the Han, Hiragana, Katakana and Hangul tokens
+/// into overlapping bigrams of characters.
+///
+/// Chinese and Japanese do not separate words with spaces, and splitting
+/// these texts into words requires a dictionary. Indexing overlapping bigrams
+/// is a simple alternative that works reasonably well for search.
+///
+/// This filter relies on the token types detected by the
+/// [`StandardTokenizer`](./struct.StandardTokenizer.html). Adjacent CJK tokens
+/// (without anything between them in the original text) are joined before being
+/// split into bigrams, and a CJK character that is not adjacent to
+/// any other CJK character is emitted as a unigram.
+/// Other tokens are left untouched.
+///
+/// Positions are assigned so that the bigrams of a text are consecutive,
+/// and `PhraseQuery` works as expected when the same filter is used at query time.
+/// If unigrams are emitted as well, each bigram is emitted at the position of its
+/// first character, with a `position_length` of 2.
+#[derive(Clone, Default)]
+pub struct CjkBigramFilter {
+    output_unigrams: bool,
+}
+
+impl CjkBigramFilter {
+    /// Creates a `CjkBigramFilter`.
+    ///
+    /// If `output_unigrams` is true, every CJK character is also emitted
+    /// as a unigram, which makes it possible to search for single characters
+    /// at the cost of a larger index.
+    pub fn new(output_unigrams: bool) -> CjkBigramFilter {
+        CjkBigramFilter { output_unigrams }
+    }
+}
+
+impl TokenFilter for CjkBigramFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(CjkBigramFilterStream {
+            tail: token_stream,
+            output_unigrams: self.output_unigrams,
+            output: TokenBuffer::default(),
+            pending: None,
+            tail_position: usize::MAX,
+            position: usize::MAX,
+        })
+    }
+}
+
+pub struct CjkBigramFilterStream<'a> {
+    tail: BoxTokenStream<'a>,
+    output_unigrams: bool,
+    output: TokenBuffer,
+    // Token read from the tail while looking for the end of a CJK run,
+    // that is not part of the run.
+    pending: Option<Token>,
+    // Position of the last token read from the tail.
+    tail_position: usize,
+    // Position of the last token produced.
+    position: usize,
+}
+
+fn is_cjk(token_type: TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::Ideographic | TokenType::Hiragana | TokenType::Katakana | TokenType::Hangul
+    )
+}
+
+// Returns true for the combining marks that must stay attached
+// to the preceding CJK character.
+fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{3099}'..='\u{309A}' | '\u{FE00}'..='\u{FE0F}')
+}
+
+// A character of a CJK run, along with the combining marks following it.
+struct CjkChar {
+    text_from: usize,
+    text_to: usize,
+    offset_from: usize,
+    offset_to: usize,
+    token_type: TokenType,
+}
+
+// Appends the characters of `token` to `run_chars`, and their text to `run_text`.
+fn push_chars(token: &Token, run_text: &mut String, run_chars: &mut Vec<CjkChar>) {
+    let base = run_text.len();
+    run_text.push_str(&token.text);
+    let mut char_indices = token.text.char_indices().peekable();
+    while let Some((start, _)) = char_indices.next() {
+        while char_indices
+            .peek()
+            .map(|&(_, c)| is_combining_mark(c))
+            .unwrap_or(false)
+        {
+            char_indices.next();
+        }
+        let end = char_indices
+            .peek()
+            .map(|&(offset, _)| offset)
+            .unwrap_or_else(|| token.text.len());
+        let (offset_from, offset_to) = sub_token_offsets(token, start, end);
+        run_chars.push(CjkChar {
+            text_from: base + start,
+            text_to: base + end,
+            offset_from,
+            offset_to,
+            token_type: token.token_type,
+        });
+    }
+}
+
+impl<'a> CjkBigramFilterStream<'a> {
+    fn next_tail_token(&mut self) -> Option<Token> {
+        if let Some(token) = self.pending.take() {
+            return Some(token);
+        }
+        if self.tail.advance() {
+            Some(self.tail.token().clone())
+        } else {
+            None
+        }
+    }
+
+    // Reads the CJK tokens adjacent to `first_token`, and
+    // pushes the resulting grams to the buffer.
+    fn process_run(&mut self, first_token: Token, position: usize) {
+        let mut run_text = String::new();
+        let mut run_chars = Vec::new();
+        let mut run_end = first_token.offset_to;
+        push_chars(&first_token, &mut run_text, &mut run_chars);
+        while self.tail.advance() {
+            let token = self.tail.token();
+            if !is_cjk(token.token_type) || token.offset_from != run_end {
+                self.pending = Some(token.clone());
+                break;
+            }
+            run_end = token.offset_to;
+            self.tail_position = token.position;
+            push_chars(token, &mut run_text, &mut run_chars);
+        }
+        let new_token =
+            |from: &CjkChar, to: &CjkChar, position: usize, position_length: usize| Token {
+                offset_from: from.offset_from,
+                offset_to: to.offset_to,
+                position,
+                text: run_text[from.text_from..to.text_to].to_string(),
+                position_length,
+                token_type: from.token_type,
+            };
+        if run_chars.len() == 1 {
+            self.output
+                .push(new_token(&run_chars[0], &run_chars[0], position, 1));
+            self.position = position;
+            return;
+        }
+        let bigram_position_length = if self.output_unigrams { 2 } else { 1 };
+        for (i, chars) in run_chars.windows(2).enumerate() {
+            if self.output_unigrams {
+                self.output
+                    .push(new_token(&chars[0], &chars[0], position + i, 1));
+            }
+            self.output.push(new_token(
+                &chars[0],
+                &chars[1],
+                position + i,
+                bigram_position_length,
+            ));
+        }
+        self.position = if self.output_unigrams {
+            let last_char = &run_chars[run_chars.len() - 1];
+            let last_position = position + run_chars.len() - 1;
+            self.output
+                .push(new_token(last_char, last_char, last_position, 1));
+            last_position
+        } else {
+            position + run_chars.len() - 2
+        };
+    }
+}
+
+impl<'a> TokenStream for CjkBigramFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        if self.output.is_empty() {
+            let mut token = match self.next_tail_token() {
+                Some(token) => token,
+                None => return false,
+            };
+            // Preserves the position gaps of the tail, but not the positions themselves,
+            // as a run of CJK tokens does not produce as many grams as it has tokens.
+            let position_increment = token.position.wrapping_sub(self.tail_position);
+            let position = self.position.wrapping_add(position_increment);
+            self.tail_position = token.position;
+            if is_cjk(token.token_type) {
+                self.process_run(token, position);
+            } else {
+                token.position = position;
+                self.position = position;
+                self.output.push(token);
+            }
+        }
+        self.output.advance()
+    }
+
+    fn token(&self) -> &Token {
+        self.output.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.output.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CjkBigramFilter;
+    use crate::collector::Count;
+    use crate::query::QueryParser;
+    use crate::schema::{Schema, TextFieldIndexing, TextOptions};
+    use crate::tokenizer::tests::{assert_token, token_stream_helper};
+    use crate::tokenizer::{StandardTokenizer, TextAnalyzer, TokenType};
+    use crate::Index;
+
+    fn cjk_bigram_analyzer(output_unigrams: bool) -> TextAnalyzer {
+        TextAnalyzer::from(StandardTokenizer).filter(CjkBigramFilter::new(output_unigrams))
+    }
+
+    #[test]
+    fn test_cjk_bigram_filter() {
+        let tokens = token_stream_helper(&cjk_bigram_analyzer(false), "東京都に住む the 犬");
+        assert_eq!(tokens.len(), 7);
+        assert_token(&tokens[0], 0, "東京", 0, 6);
+        assert_token(&tokens[1], 1, "京都", 3, 9);
+        assert_token(&tokens[2], 2, "都に", 6, 12);
+        assert_token(&tokens[3], 3, "に住", 9, 15);
+        assert_token(&tokens[4], 4, "住む", 12, 18);
+        assert_eq!(tokens[4].token_type, TokenType::Ideographic);
+        assert_token(&tokens[5], 5, "the", 19, 22);
+        assert_eq!(tokens[5].token_type, TokenType::Word);
+        assert_token(&tokens[6], 6, "犬", 23, 26);
+        let tokens = token_stream_helper(&cjk_bigram_analyzer(false), "the 犬 and カタカナ");
+        assert_eq!(tokens.len(), 6);
+        assert_token(&tokens[1], 1, "犬", 4, 7);
+        assert_token(&tokens[2], 2, "and", 8, 11);
+        assert_token(&tokens[3], 3, "カタ", 12, 18);
+        assert_token(&tokens[5], 5, "カナ", 18, 24);
+        assert_eq!(tokens[5].token_type, TokenType::Katakana);
+    }
+
+    #[test]
+    fn test_cjk_bigram_filter_hangul() {
+        let tokens = token_stream_helper(&cjk_bigram_analyzer(false), "한국어 문서");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "한국", 0, 6);
+        assert_token(&tokens[1], 1, "국어", 3, 9);
+        assert_token(&tokens[2], 2, "문서", 10, 16);
+        assert_eq!(tokens[2].token_type, TokenType::Hangul);
+    }
+
+    #[test]
+    fn test_cjk_bigram_filter_unigrams() {
+        let tokens = token_stream_helper(&cjk_bigram_analyzer(true), "a 東京都 b");
+        assert_eq!(tokens.len(), 7);
+        assert_token(&tokens[0], 0, "a", 0, 1);
+        assert_token(&tokens[1], 1, "東", 2, 5);
+        assert_token(&tokens[2], 1, "東京", 2, 8);
+        assert_eq!(tokens[2].position_length, 2);
+        assert_token(&tokens[3], 2, "京", 5, 8);
+        assert_token(&tokens[4], 2, "京都", 5, 11);
+        assert_token(&tokens[5], 3, "都", 8, 11);
+        assert_eq!(tokens[5].position_length, 1);
+        assert_token(&tokens[6], 4, "b", 12, 13);
+    }
+
+    #[test]
+    fn test_cjk_bigram_filter_combining_marks() {
+        // か followed by a combining voiced sound mark.
+        let tokens = token_stream_helper(&cjk_bigram_analyzer(false), "か\u{3099}き");
+        assert_eq!(tokens.len(), 1);
+        assert_token(&tokens[0], 0, "か\u{3099}き", 0, 9);
+    }
+
+    #[test]
+    fn test_cjk_bigram_phrase_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("ja")
+                .set_index_option(crate::schema::IndexRecordOption::WithFreqsAndPositions),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text=>"東京都に住んでいます"));
+        index_writer.add_document(doc!(text=>"京都と東京"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![text]);
+        let count =
+            |query: &str| searcher.search(&query_parser.parse_query(query).unwrap(), &Count);
+        assert_eq!(count("\"東京都\"")?, 1);
+        assert_eq!(count("京都")?, 2);
+        assert_eq!(count("\"京都と\"")?, 1);
+        assert_eq!(count("\"東京と\"")?, 0);
+        Ok(())
+    }
+}
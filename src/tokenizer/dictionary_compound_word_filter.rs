@@ -0,0 +1,246 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let words = vec!["fuß".to_string(), "ball".to_string(), "schuh".to_string()];
+//! let tokenizer = TextAnalyzer::from(SimpleTokenizer)
+//!   .filter(LowerCaser)
+//!   .filter(DictionaryCompoundWordFilter::new(words));
+//!
+//! let mut stream = tokenizer.token_stream("Fußballschuhe");
+//! assert_eq!(stream.next().unwrap().text, "fußballschuhe");
+//! assert_eq!(stream.next().unwrap().text, "fuß");
+//! assert_eq!(stream.next().unwrap().text, "ball");
+//! assert_eq!(stream.next().unwrap().text, "schuh");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{sub_token_offsets, BoxTokenStream, Token, TokenBuffer, TokenFilter, TokenStream};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+pub(crate) const DEFAULT_MIN_WORD_SIZE: usize = 5;
+pub(crate) const DEFAULT_MIN_SUBWORD_SIZE: usize = 2;
+pub(crate) const DEFAULT_MAX_SUBWORD_SIZE: usize = 15;
+
+/// `DictionaryCompoundWordFilter` decomposes compound words, as found in German,
+/// Dutch or the Scandinavian languages, into the words of a dictionary they contain.
+///
+/// The compound token is emitted unchanged, followed by each of its subwords
+/// found in the dictionary, at the position of the compound. For instance, given
+/// a dictionary containing `fuß`, `ball` and `schuh`, the token `fußballschuhe` also
+/// produces the tokens `fuß`, `ball` and `schuh`.
+///
+/// Subwords are matched case sensitively: the filter is meant to be applied
+/// after lowercasing. Sizes are expressed in number of characters.
+#[derive(Clone)]
+pub struct DictionaryCompoundWordFilter {
+    dictionary: Arc<HashSet<String>>,
+    min_word_size: usize,
+    min_subword_size: usize,
+    max_subword_size: usize,
+    only_longest_match: bool,
+}
+
+impl DictionaryCompoundWordFilter {
+    /// Creates a `DictionaryCompoundWordFilter` given the words of the dictionary.
+    ///
+    /// By default, only the tokens of at least 5 characters are decomposed, into
+    /// subwords of 2 to 15 characters.
+    pub fn new(words: Vec<String>) -> DictionaryCompoundWordFilter {
+        DictionaryCompoundWordFilter {
+            dictionary: Arc::new(words.into_iter().collect()),
+            min_word_size: DEFAULT_MIN_WORD_SIZE,
+            min_subword_size: DEFAULT_MIN_SUBWORD_SIZE,
+            max_subword_size: DEFAULT_MAX_SUBWORD_SIZE,
+            only_longest_match: false,
+        }
+    }
+
+    /// Sets the minimum size of the tokens to decompose.
+    pub fn set_min_word_size(mut self, min_word_size: usize) -> Self {
+        self.min_word_size = min_word_size;
+        self
+    }
+
+    /// Sets the minimum size of the subwords.
+    pub fn set_min_subword_size(mut self, min_subword_size: usize) -> Self {
+        self.min_subword_size = min_subword_size;
+        self
+    }
+
+    /// Sets the maximum size of the subwords.
+    pub fn set_max_subword_size(mut self, max_subword_size: usize) -> Self {
+        self.max_subword_size = max_subword_size;
+        self
+    }
+
+    /// If true, only the longest subword starting at a given character is emitted.
+    ///
+    /// For instance, with a dictionary containing `fuß` and `fußball`,
+    /// `fußballschuhe` produces `fußball` but not `fuß`.
+    pub fn set_only_longest_match(mut self, only_longest_match: bool) -> Self {
+        self.only_longest_match = only_longest_match;
+        self
+    }
+
+    // Pushes `token` followed by its subwords to `output`.
+    fn decompose(&self, token: &Token, output: &mut TokenBuffer) {
+        output.push(token.clone());
+        let text = &token.text;
+        let char_boundaries: Vec<usize> = text
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain(std::iter::once(text.len()))
+            .collect();
+        let num_chars = char_boundaries.len() - 1;
+        if num_chars < self.min_word_size {
+            return;
+        }
+        let mut subwords: Vec<(usize, usize)> = Vec::new();
+        for start in 0..num_chars {
+            let min_end = start + self.min_subword_size.max(1);
+            let max_end = num_chars.min(start + self.max_subword_size);
+            let mut longest_match = None;
+            for end in min_end..=max_end {
+                let (from, to) = (char_boundaries[start], char_boundaries[end]);
+                if to - from == text.len() || !self.dictionary.contains(&text[from..to]) {
+                    continue;
+                }
+                if self.only_longest_match {
+                    longest_match = Some((from, to));
+                } else {
+                    subwords.push((from, to));
+                }
+            }
+            subwords.extend(longest_match);
+        }
+        let mut emitted: Vec<&str> = Vec::new();
+        for (from, to) in subwords {
+            let subword = &text[from..to];
+            if emitted.contains(&subword) {
+                continue;
+            }
+            emitted.push(subword);
+            let (offset_from, offset_to) = sub_token_offsets(token, from, to);
+            output.push(Token {
+                offset_from,
+                offset_to,
+                text: subword.to_string(),
+                ..token.clone()
+            });
+        }
+    }
+}
+
+impl TokenFilter for DictionaryCompoundWordFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(DictionaryCompoundWordFilterStream {
+            tail: token_stream,
+            filter: self.clone(),
+            output: TokenBuffer::default(),
+        })
+    }
+}
+
+pub struct DictionaryCompoundWordFilterStream<'a> {
+    tail: BoxTokenStream<'a>,
+    filter: DictionaryCompoundWordFilter,
+    output: TokenBuffer,
+}
+
+impl<'a> TokenStream for DictionaryCompoundWordFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        if self.output.is_empty() {
+            if !self.tail.advance() {
+                return false;
+            }
+            self.filter.decompose(self.tail.token(), &mut self.output);
+        }
+        self.output.advance()
+    }
+
+    fn token(&self) -> &Token {
+        self.output.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.output.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DictionaryCompoundWordFilter;
+    use crate::tokenizer::tests::{assert_token, token_stream_helper};
+    use crate::tokenizer::{AsciiFoldingFilter, LowerCaser, SimpleTokenizer, TextAnalyzer};
+
+    fn dictionary(words: &[&str]) -> DictionaryCompoundWordFilter {
+        DictionaryCompoundWordFilter::new(words.iter().map(|word| word.to_string()).collect())
+    }
+
+    #[test]
+    fn test_dictionary_compound_word_filter() {
+        let analyzer = TextAnalyzer::from(SimpleTokenizer)
+            .filter(LowerCaser)
+            .filter(dictionary(&["fuß", "ball", "fußball", "schuh", "boll"]));
+        let tokens = token_stream_helper(&analyzer, "Neue Fußballschuhe");
+        assert_eq!(tokens.len(), 6);
+        assert_token(&tokens[0], 0, "neue", 0, 4);
+        assert_token(&tokens[1], 1, "fußballschuhe", 5, 19);
+        assert_token(&tokens[2], 1, "fuß", 5, 9);
+        assert_token(&tokens[3], 1, "fußball", 5, 13);
+        assert_token(&tokens[4], 1, "ball", 9, 13);
+        assert_token(&tokens[5], 1, "schuh", 13, 18);
+        // A dictionary word is not decomposed into itself.
+        let tokens = token_stream_helper(&analyzer, "fußball");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "fußball", 0, 8);
+        assert_token(&tokens[1], 0, "fuß", 0, 4);
+        assert_token(&tokens[2], 0, "ball", 4, 8);
+    }
+
+    #[test]
+    fn test_dictionary_compound_word_filter_only_longest_match() {
+        let analyzer = TextAnalyzer::from(SimpleTokenizer)
+            .filter(dictionary(&["fot", "fotboll", "boll", "skor"]).set_only_longest_match(true));
+        let tokens = token_stream_helper(&analyzer, "fotbollsskor");
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "fotbollsskor", 0, 12);
+        assert_token(&tokens[1], 0, "fotboll", 0, 7);
+        assert_token(&tokens[2], 0, "boll", 3, 7);
+        assert_token(&tokens[3], 0, "skor", 8, 12);
+    }
+
+    #[test]
+    fn test_dictionary_compound_word_filter_sizes() {
+        let analyzer = TextAnalyzer::from(SimpleTokenizer)
+            .filter(dictionary(&["ab", "abc", "bc", "de"]).set_min_subword_size(3));
+        // "abcd" is too short to be decomposed.
+        let tokens = token_stream_helper(&analyzer, "abcd abcde");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[1], 1, "abcde", 5, 10);
+        assert_token(&tokens[2], 1, "abc", 5, 8);
+        let analyzer = TextAnalyzer::from(SimpleTokenizer).filter(
+            dictionary(&["ab", "abc", "bc", "de"])
+                .set_min_word_size(4)
+                .set_max_subword_size(2),
+        );
+        let tokens = token_stream_helper(&analyzer, "abcd");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[1], 0, "ab", 0, 2);
+        assert_token(&tokens[2], 0, "bc", 1, 3);
+    }
+
+    #[test]
+    fn test_dictionary_compound_word_filter_modified_text() {
+        // Offsets of subwords can not be computed once the text was modified.
+        let analyzer = TextAnalyzer::from(SimpleTokenizer)
+            .filter(AsciiFoldingFilter)
+            .filter(dictionary(&["fuss", "ball"]).set_min_subword_size(4));
+        let mut token_stream = analyzer.token_stream("fussbàll");
+        assert_eq!(token_stream.next().unwrap().text, "fussball");
+        assert_token(token_stream.next().unwrap(), 0, "fuss", 0, 9);
+        assert_token(token_stream.next().unwrap(), 0, "ball", 0, 9);
+        assert!(token_stream.next().is_none());
+    }
+}
@@ -0,0 +1,142 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let tokenizer = TextAnalyzer::from(StandardTokenizer)
+//!   .filter(LowerCaser)
+//!   .filter(ElisionFilter::new(Language::French).unwrap());
+//!
+//! let mut stream = tokenizer.token_stream("L'avion qu’il prend");
+//! assert_eq!(stream.next().unwrap().text, "avion");
+//! assert_eq!(stream.next().unwrap().text, "il");
+//! assert_eq!(stream.next().unwrap().text, "prend");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{BoxTokenStream, Language, Token, TokenFilter, TokenStream};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+const FRENCH_ARTICLES: [&str; 13] = [
+    "l", "m", "t", "qu", "n", "s", "j", "d", "c", "jusqu", "quoiqu", "lorsqu", "puisqu",
+];
+
+const ITALIAN_ARTICLES: [&str; 21] = [
+    "c", "l", "all", "dall", "dell", "nell", "sull", "coll", "pell", "gl", "agl", "dagl", "degl",
+    "negl", "sugl", "un", "m", "t", "s", "v", "d",
+];
+
+/// `ElisionFilter` removes the elided articles prefixing tokens, such as
+/// the `l'` of the French `l'avion`, or the `dell'` of the Italian `dell'arte`.
+///
+/// Articles are matched case sensitively: the filter is meant to be applied
+/// after lowercasing. Both the apostrophe `'` and the right single quotation
+/// mark `’` are recognized.
+#[derive(Clone)]
+pub struct ElisionFilter {
+    articles: Arc<HashSet<String>>,
+}
+
+impl ElisionFilter {
+    /// Creates an `ElisionFilter` removing the usual articles of the given language.
+    ///
+    /// Returns `None` if the language does not use elision (only French and
+    /// Italian are supported).
+    pub fn new(language: Language) -> Option<ElisionFilter> {
+        let articles: &[&str] = match language {
+            Language::French => &FRENCH_ARTICLES,
+            Language::Italian => &ITALIAN_ARTICLES,
+            _ => return None,
+        };
+        Some(ElisionFilter::with_articles(
+            articles
+                .iter()
+                .map(|&article| article.to_string())
+                .collect(),
+        ))
+    }
+
+    /// Creates an `ElisionFilter` removing the given articles.
+    pub fn with_articles(articles: Vec<String>) -> ElisionFilter {
+        ElisionFilter {
+            articles: Arc::new(articles.into_iter().collect()),
+        }
+    }
+}
+
+impl TokenFilter for ElisionFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(ElisionFilterStream {
+            tail: token_stream,
+            articles: self.articles.clone(),
+        })
+    }
+}
+
+pub struct ElisionFilterStream<'a> {
+    tail: BoxTokenStream<'a>,
+    articles: Arc<HashSet<String>>,
+}
+
+impl<'a> TokenStream for ElisionFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        let text = &self.tail.token().text;
+        if let Some(apostrophe_pos) = text.find(['\'', '’']) {
+            let apostrophe_len = text[apostrophe_pos..].chars().next().unwrap().len_utf8();
+            let suffix_start = apostrophe_pos + apostrophe_len;
+            if suffix_start < text.len() && self.articles.contains(&text[..apostrophe_pos]) {
+                self.tail.token_mut().text.drain(..suffix_start);
+            }
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ElisionFilter;
+    use crate::tokenizer::tests::{assert_token, token_stream_helper};
+    use crate::tokenizer::{Language, LowerCaser, StandardTokenizer, TextAnalyzer};
+
+    fn elision_analyzer(elision_filter: ElisionFilter) -> TextAnalyzer {
+        TextAnalyzer::from(StandardTokenizer)
+            .filter(LowerCaser)
+            .filter(elision_filter)
+    }
+
+    #[test]
+    fn test_elision_filter() {
+        let french = || ElisionFilter::new(Language::French).unwrap();
+        let tokens =
+            token_stream_helper(&elision_analyzer(french()), "Jusqu'à l’aube, aujourd'hui");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "à", 0, 8);
+        assert_token(&tokens[1], 1, "aube", 9, 17);
+        // "aujourd" is not an article.
+        assert_token(&tokens[2], 2, "aujourd'hui", 19, 30);
+        let italian = ElisionFilter::new(Language::Italian).unwrap();
+        let tokens = token_stream_helper(&elision_analyzer(italian), "Storia dell'arte");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[1], 1, "arte", 7, 16);
+        assert!(ElisionFilter::new(Language::German).is_none());
+    }
+
+    #[test]
+    fn test_elision_filter_with_articles() {
+        let filter = ElisionFilter::with_articles(vec!["o".to_string()]);
+        let tokens = token_stream_helper(&elision_analyzer(filter), "o'clock l'avion");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "clock", 0, 7);
+        assert_token(&tokens[1], 1, "l'avion", 8, 15);
+    }
+}
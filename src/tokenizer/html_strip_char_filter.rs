@@ -0,0 +1,234 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let tokenizer = TextAnalyzer::from(SimpleTokenizer)
+//!   .char_filter(HtmlStripCharFilter);
+//!
+//! let text = "<p>Fish &amp; <b>chips</b></p>";
+//! let mut stream = tokenizer.token_stream(text);
+//! assert_eq!(stream.next().unwrap().text, "Fish");
+//! let token = stream.next().unwrap();
+//! assert_eq!(token.text, "chips");
+//! assert_eq!(&text[token.offset_from..token.offset_to], "chips");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{CharFilter, OffsetCorrections};
+
+/// `HtmlStripCharFilter` removes the HTML markup from the text.
+///
+/// Tags, comments, processing instructions, as well as the content of
+/// `<script>` and `<style>` elements are removed. Block-level tags (`<p>`, `<div>`,
+/// `<br>`, `<li>`...) are replaced by a space, so that the words they separate
+/// are not joined. Character references (`&amp;`, `&#233;`...) are decoded.
+#[derive(Clone)]
+pub struct HtmlStripCharFilter;
+
+const BLOCK_ELEMENTS: [&str; 42] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "body",
+    "br",
+    "caption",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "option",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+];
+
+// Elements whose content is removed along with their tags.
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+// Maximum length of a character reference, such as `&CounterClockwiseContourIntegral;`.
+const MAX_CHAR_REF_LEN: usize = 40;
+
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+// Returns the end of the tag starting at `start`, skipping quoted attribute values.
+fn tag_end(text: &str, start: usize) -> Option<usize> {
+    let mut quote: Option<u8> = None;
+    for (offset, &b) in text.as_bytes()[start..].iter().enumerate() {
+        match (quote, b) {
+            (Some(quote_char), _) if quote_char == b => quote = None,
+            (Some(_), _) => {}
+            (None, b'"') | (None, b'\'') => quote = Some(b),
+            (None, b'>') => return Some(start + offset + 1),
+            (None, _) => {}
+        }
+    }
+    None
+}
+
+// Returns the end of the markup starting at `start`, as well as its replacement,
+// or `None` if the `<` at `start` does not start any markup.
+fn match_markup(text: &str, start: usize) -> Option<(usize, &'static str)> {
+    let rest = &text[start..];
+    if let Some(comment) = rest.strip_prefix("<!--") {
+        let end = comment
+            .find("-->")
+            .map(|comment_len| start + 4 + comment_len + 3)
+            .unwrap_or(text.len());
+        return Some((end, ""));
+    }
+    if rest.starts_with("<!") || rest.starts_with("<?") {
+        return Some((tag_end(text, start)?, ""));
+    }
+    let name_start = if rest.starts_with("</") { 2 } else { 1 };
+    let name_len = rest[name_start..]
+        .bytes()
+        .take_while(u8::is_ascii_alphanumeric)
+        .count();
+    if name_len == 0 || !rest.as_bytes()[name_start].is_ascii_alphabetic() {
+        return None;
+    }
+    let name = &rest[name_start..name_start + name_len];
+    let end = tag_end(text, start)?;
+    if name_start == 1 {
+        for element in RAW_TEXT_ELEMENTS.iter() {
+            if name.eq_ignore_ascii_case(element) {
+                let closing_tag = format!("</{}", element);
+                let end = find_ignore_ascii_case(&text[end..], &closing_tag)
+                    .and_then(|closing_tag_start| tag_end(text, end + closing_tag_start))
+                    .unwrap_or(text.len());
+                return Some((end, ""));
+            }
+        }
+    }
+    let is_block_element = BLOCK_ELEMENTS
+        .iter()
+        .any(|element| name.eq_ignore_ascii_case(element));
+    Some((end, if is_block_element { " " } else { "" }))
+}
+
+// Returns the end of the character reference starting at `start`, and the
+// decoded character, or `None` if there is no valid character reference at `start`.
+fn match_char_ref(text: &str, start: usize) -> Option<(usize, String)> {
+    let rest = &text[start + 1..];
+    let name_len = rest
+        .bytes()
+        .take(MAX_CHAR_REF_LEN)
+        .take_while(|&b| b.is_ascii_alphanumeric() || b == b'#')
+        .count();
+    if name_len == 0 || !rest[name_len..].starts_with(';') {
+        return None;
+    }
+    let end = start + name_len + 2;
+    let decoded = htmlescape::decode_html(&text[start..end]).ok()?;
+    Some((end, decoded))
+}
+
+impl CharFilter for HtmlStripCharFilter {
+    fn filter(&self, text: &str, offset_corrections: &mut OffsetCorrections) -> String {
+        let mut output = String::with_capacity(text.len());
+        // Offset of the first byte of `text` that is not in `output` yet.
+        let mut copied = 0;
+        let mut offset = 0;
+        while let Some(markup_offset) = text[offset..].find(['<', '&']) {
+            let start = offset + markup_offset;
+            let replacement = if text.as_bytes()[start] == b'<' {
+                match_markup(text, start).map(|(end, replacement)| (end, replacement.to_string()))
+            } else {
+                match_char_ref(text, start)
+            };
+            match replacement {
+                Some((end, replacement)) => {
+                    output.push_str(&text[copied..start]);
+                    let output_start = output.len();
+                    output.push_str(&replacement);
+                    offset_corrections.add_replacement(output_start..output.len(), start..end);
+                    copied = end;
+                    offset = end;
+                }
+                None => offset = start + 1,
+            }
+        }
+        output.push_str(&text[copied..]);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HtmlStripCharFilter;
+    use crate::tokenizer::{CharFilter, OffsetCorrections};
+
+    fn strip(text: &str) -> String {
+        HtmlStripCharFilter.filter(text, &mut OffsetCorrections::default())
+    }
+
+    #[test]
+    fn test_html_strip_char_filter() {
+        assert_eq!(strip("<b>bold</b> text"), "bold text");
+        assert_eq!(strip("<p>first</p><p>second</p>"), " first  second ");
+        assert_eq!(strip("a<br/>b<BR>c"), "a b c");
+        assert_eq!(strip("<a href=\"x>y\" title='>'>link</a>"), "link");
+        assert_eq!(strip("a<!-- <b>comment</b> -->b"), "ab");
+        assert_eq!(strip("<!DOCTYPE html><?xml version=\"1.0\"?>a"), "a");
+        assert_eq!(
+            strip("a<script type=\"text/javascript\">if (a < b) {}</script>b<STYLE>p {}</style>c"),
+            "abc"
+        );
+        assert_eq!(
+            strip("fish &amp; chips&#33; caf&eacute; &#x263A;"),
+            "fish & chips! café ☺"
+        );
+        assert_eq!(
+            strip("a < b && c &unknown; &amp"),
+            "a < b && c &unknown; &amp"
+        );
+        assert_eq!(strip("unclosed <b tag"), "unclosed <b tag");
+        assert_eq!(strip("unclosed <!-- comment"), "unclosed ");
+    }
+
+    #[test]
+    fn test_html_strip_char_filter_offsets() {
+        let text = "<p>caf&eacute;</p> <b>au</b>lait";
+        let mut offset_corrections = OffsetCorrections::default();
+        let stripped = HtmlStripCharFilter.filter(text, &mut offset_corrections);
+        assert_eq!(stripped, " café  aulait");
+        // café
+        assert_eq!(offset_corrections.correct_start(1), 3);
+        assert_eq!(offset_corrections.correct_end(6), 14);
+        // aulait
+        assert_eq!(offset_corrections.correct_start(8), 22);
+        assert_eq!(offset_corrections.correct_end(14), 32);
+    }
+}
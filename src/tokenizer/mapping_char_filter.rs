@@ -0,0 +1,122 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let tokenizer = TextAnalyzer::from(SimpleTokenizer)
+//!   .char_filter(MappingCharFilter::new(vec![
+//!       ("œ".to_string(), "oe".to_string()),
+//!       ("&".to_string(), " and ".to_string()),
+//!   ]));
+//!
+//! let mut stream = tokenizer.token_stream("bœuf&cie");
+//! assert_eq!(stream.next().unwrap().text, "boeuf");
+//! assert_eq!(stream.next().unwrap().text, "and");
+//! assert_eq!(stream.next().unwrap().text, "cie");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{CharFilter, OffsetCorrections};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// `MappingCharFilter` replaces strings of the text according to a list
+/// of mappings.
+///
+/// At each position of the text, the longest matching string is replaced.
+/// The replacements are not themselves matched against the mappings.
+#[derive(Clone)]
+pub struct MappingCharFilter {
+    mappings: Arc<HashMap<String, String>>,
+    // First characters of the strings to replace.
+    first_chars: Arc<HashSet<char>>,
+    max_len: usize,
+}
+
+impl MappingCharFilter {
+    /// Creates a `MappingCharFilter` given a list of `(string, replacement)`.
+    ///
+    /// Empty strings are ignored.
+    pub fn new(mappings: Vec<(String, String)>) -> MappingCharFilter {
+        let mappings: HashMap<String, String> = mappings
+            .into_iter()
+            .filter(|(from, _)| !from.is_empty())
+            .collect();
+        let first_chars = mappings
+            .keys()
+            .flat_map(|from| from.chars().next())
+            .collect();
+        let max_len = mappings.keys().map(String::len).max().unwrap_or(0);
+        MappingCharFilter {
+            mappings: Arc::new(mappings),
+            first_chars: Arc::new(first_chars),
+            max_len,
+        }
+    }
+
+    // Returns the longest string to replace starting at `start`, and its replacement.
+    fn longest_match(&self, text: &str, start: usize) -> Option<(usize, &str)> {
+        let max_end = text.len().min(start + self.max_len);
+        (start + 1..=max_end)
+            .rev()
+            .filter(|&end| text.is_char_boundary(end))
+            .find_map(|end| {
+                self.mappings
+                    .get(&text[start..end])
+                    .map(|replacement| (end, replacement.as_str()))
+            })
+    }
+}
+
+impl CharFilter for MappingCharFilter {
+    fn filter(&self, text: &str, offset_corrections: &mut OffsetCorrections) -> String {
+        let mut output = String::with_capacity(text.len());
+        // Offset of the first byte of `text` that is not in `output` yet.
+        let mut copied = 0;
+        let mut offset = 0;
+        while let Some(c) = text[offset..].chars().next() {
+            if self.first_chars.contains(&c) {
+                if let Some((end, replacement)) = self.longest_match(text, offset) {
+                    output.push_str(&text[copied..offset]);
+                    let output_start = output.len();
+                    output.push_str(replacement);
+                    offset_corrections.add_replacement(output_start..output.len(), offset..end);
+                    copied = end;
+                    offset = end;
+                    continue;
+                }
+            }
+            offset += c.len_utf8();
+        }
+        output.push_str(&text[copied..]);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MappingCharFilter;
+    use crate::tokenizer::{CharFilter, OffsetCorrections};
+
+    #[test]
+    fn test_mapping_char_filter() {
+        let mapping_char_filter = MappingCharFilter::new(vec![
+            ("a".to_string(), "b".to_string()),
+            ("aa".to_string(), "c".to_string()),
+            ("b".to_string(), "a".to_string()),
+            ("ß".to_string(), "ss".to_string()),
+            ("½".to_string(), "1/2".to_string()),
+            ("".to_string(), "x".to_string()),
+        ]);
+        let mut offset_corrections = OffsetCorrections::default();
+        let text = "aaab ½ große";
+        let filtered = mapping_char_filter.filter(text, &mut offset_corrections);
+        assert_eq!(filtered, "cba 1/2 grosse");
+        assert_eq!(offset_corrections.correct_start(1), 2);
+        // 1/2
+        assert_eq!(offset_corrections.correct_start(4), 5);
+        assert_eq!(offset_corrections.correct_end(5), 7);
+        assert_eq!(offset_corrections.correct_end(7), 7);
+        // grosse
+        assert_eq!(offset_corrections.correct_start(8), 8);
+        assert_eq!(offset_corrections.correct_end(14), text.len());
+    }
+}
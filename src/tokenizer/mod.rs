@@ -41,6 +41,21 @@
 //! It will chop your text on punctuation and whitespaces,
 //! removes tokens that are longer than 40 chars, and lowercase your text.
 //!
+//! ## `standard`
+//!
+//! `standard` is a drop-in replacement for `default`, that chops
+//! your text following the word boundary rules of the Unicode Standard
+//! Annex #29. Contractions (`don't`), acronyms (`U.S.A`), numbers (`3.14`),
+//! email addresses and URLs are kept as single tokens, and the type of
+//! each token is recorded in `Token::token_type`.
+//! It also removes tokens that are longer than 40 chars, and lowercases your text.
+//!
+//! ## `cjk`
+//!
+//! Like `standard`, but Chinese, Japanese and Korean texts are indexed as
+//! overlapping bigrams of characters (see [`CjkBigramFilter`](./struct.CjkBigramFilter.html)).
+//! It is also registered as `ja`.
+//!
 //! ## `raw`
 //! Does not actual tokenizer your text. It keeps it entirely unprocessed.
 //! It can be useful to index uuids, or urls for instance.
@@ -52,6 +67,17 @@
 //! remove their inflection. This tokenizer is slower than the default one,
 //! but is recommended to improve recall.
 //!
+//! ## Language analyzers
+//!
+//! For every other language supported by the [`Stemmer`](./struct.Stemmer.html),
+//! an analyzer named after the ISO 639-1 code of the language is registered
+//! (`ar_stem`, `da_stem`, `de_stem`, `el_stem`, `es_stem`, `fi_stem`, `fr_stem`, `hu_stem`,
+//! `it_stem`, `nl_stem`, `no_stem`, `pt_stem`, `ro_stem`, `ru_stem`, `sv_stem`, `ta_stem`
+//! and `tr_stem`).
+//! It chops the text like `standard`, removes tokens that are longer than 40 chars,
+//! applies Unicode normalization and case folding, removes elided articles (French and
+//! Italian), removes the stop words of the language and applies stemming.
+//!
 //!
 //! # Custom tokenizers
 //!
@@ -70,6 +96,11 @@
 //!     .filter(Stemmer::new(Language::English));
 //! ```
 //!
+//! The text can also be rewritten before it gets tokenized, by adding
+//! [`CharFilter`s](./trait.CharFilter.html) to the `TextAnalyzer`
+//! (e.g. [`HtmlStripCharFilter`](./struct.HtmlStripCharFilter.html) to index HTML content).
+//! The offsets of the resulting tokens still point to the original text.
+//!
 //! Once your tokenizer is defined, you need to
 //! register it with a name in your index's [`TokenizerManager`](./struct.TokenizerManager.html).
 //!
@@ -118,38 +149,137 @@
 //! ```
 //!
 mod alphanum_only;
+mod analyzer_definition;
 mod ascii_folding_filter;
+mod char_filter;
+mod cjk_bigram_filter;
+mod dictionary_compound_word_filter;
+mod elision_filter;
 mod facet_tokenizer;
+mod html_strip_char_filter;
 mod lower_caser;
+mod mapping_char_filter;
 mod ngram_tokenizer;
+mod pattern_capture_filter;
+mod phonetic;
 mod raw_tokenizer;
+mod regex_replace_char_filter;
+mod regex_tokenizer;
 mod remove_long;
+mod shingle_filter;
 mod simple_tokenizer;
+mod standard_tokenizer;
 mod stemmer;
 mod stop_word_filter;
+mod synonym_filter;
 mod token_stream_chain;
 mod tokenized_string;
 mod tokenizer;
 mod tokenizer_manager;
+mod unicode_normalization_filter;
+mod word_delimiter_filter;
 
 pub use self::alphanum_only::AlphaNumOnlyFilter;
+pub use self::analyzer_definition::{
+    AnalyzerDefinition, CharFilterDefinition, TokenFilterDefinition, TokenizerDefinition,
+};
 pub use self::ascii_folding_filter::AsciiFoldingFilter;
+pub use self::char_filter::{BoxCharFilter, CharFilter, OffsetCorrections};
+pub use self::cjk_bigram_filter::CjkBigramFilter;
+pub use self::dictionary_compound_word_filter::DictionaryCompoundWordFilter;
+pub use self::elision_filter::ElisionFilter;
 pub use self::facet_tokenizer::FacetTokenizer;
+pub use self::html_strip_char_filter::HtmlStripCharFilter;
 pub use self::lower_caser::LowerCaser;
+pub use self::mapping_char_filter::MappingCharFilter;
 pub use self::ngram_tokenizer::NgramTokenizer;
+pub use self::pattern_capture_filter::PatternCaptureFilter;
+pub use self::phonetic::{PhoneticAlgorithm, PhoneticFilter};
 pub use self::raw_tokenizer::RawTokenizer;
+pub use self::regex_replace_char_filter::RegexReplaceCharFilter;
+pub use self::regex_tokenizer::RegexTokenizer;
 pub use self::remove_long::RemoveLongFilter;
+pub use self::shingle_filter::ShingleFilter;
 pub use self::simple_tokenizer::SimpleTokenizer;
+pub use self::standard_tokenizer::StandardTokenizer;
 pub use self::stemmer::{Language, Stemmer};
 pub use self::stop_word_filter::StopWordFilter;
+pub use self::synonym_filter::{SynonymFilter, SynonymMap};
 pub(crate) use self::token_stream_chain::TokenStreamChain;
 
 pub use self::tokenized_string::{PreTokenizedStream, PreTokenizedString};
 pub use self::tokenizer::{
-    BoxTokenFilter, BoxTokenStream, TextAnalyzer, Token, TokenFilter, TokenStream, Tokenizer,
+    BoxTokenFilter, BoxTokenStream, TextAnalyzer, Token, TokenFilter, TokenStream, TokenType,
+    Tokenizer,
 };
 
 pub use self::tokenizer_manager::TokenizerManager;
+pub use self::unicode_normalization_filter::{NormalizationForm, UnicodeNormalizationFilter};
+pub use self::word_delimiter_filter::WordDelimiterFilter;
+
+use std::collections::VecDeque;
+
+/// Returns the offsets in the original text of the bytes `from..to` of the text of `token`.
+///
+/// Offsets within the token only make sense if its text was not modified by a
+/// previous filter. Otherwise, the offsets of the whole token are returned.
+pub(crate) fn sub_token_offsets(token: &Token, from: usize, to: usize) -> (usize, usize) {
+    if token.offset_to - token.offset_from == token.text.len() {
+        (token.offset_from + from, token.offset_from + to)
+    } else {
+        (token.offset_from, token.offset_to)
+    }
+}
+
+/// Tokens produced by a token stream, but not emitted yet.
+///
+/// The filters producing several tokens out of a token of their tail push them
+/// to this buffer, and emit them one by one.
+#[derive(Default)]
+pub(crate) struct TokenBuffer {
+    tokens: VecDeque<Token>,
+    token: Token,
+}
+
+impl TokenBuffer {
+    /// Appends a token to the buffer.
+    pub(crate) fn push(&mut self, token: Token) {
+        self.tokens.push_back(token);
+    }
+
+    /// Appends tokens to the buffer.
+    pub(crate) fn extend(&mut self, tokens: impl IntoIterator<Item = Token>) {
+        self.tokens.extend(tokens);
+    }
+
+    /// Returns true iff all of the tokens of the buffer have been emitted.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Emits the next token of the buffer.
+    ///
+    /// Returns false if the buffer is empty.
+    pub(crate) fn advance(&mut self) -> bool {
+        match self.tokens.pop_front() {
+            Some(token) => {
+                self.token = token;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the token emitted last.
+    pub(crate) fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// Returns a mutable reference to the token emitted last.
+    pub(crate) fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
 
 /// Maximum authorized len (in bytes) for a token.
 ///
@@ -161,7 +291,8 @@ pub const MAX_TOKEN_LEN: usize = u16::max_value() as usize - 4;
 #[cfg(test)]
 pub mod tests {
     use super::{
-        Language, LowerCaser, RemoveLongFilter, SimpleTokenizer, Stemmer, Token, TokenizerManager,
+        Language, LowerCaser, RemoveLongFilter, SimpleTokenizer, Stemmer, Token, TokenType,
+        TokenizerManager,
     };
     use crate::tokenizer::TextAnalyzer;
 
@@ -186,6 +317,17 @@ pub mod tests {
         );
     }
 
+    /// Returns the tokens produced by `analyzer` for `text`.
+    pub fn token_stream_helper(analyzer: &TextAnalyzer, text: &str) -> Vec<Token> {
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+
     #[test]
     fn test_raw_tokenizer() {
         let tokenizer_manager = TokenizerManager::default();
@@ -225,6 +367,50 @@ pub mod tests {
         assert_token(&tokens[3], 3, "payer", 17, 22);
     }
 
+    #[test]
+    fn test_standard_tokenizer() {
+        let tokenizer_manager = TokenizerManager::default();
+        let standard_tokenizer = tokenizer_manager.get("standard").unwrap();
+        let mut tokens: Vec<Token> = vec![];
+        {
+            let mut add_token = |token: &Token| {
+                tokens.push(token.clone());
+            };
+            standard_tokenizer
+                .token_stream("Don't email Paul@Example.com!")
+                .process(&mut add_token);
+        }
+
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "don't", 0, 5);
+        assert_token(&tokens[1], 1, "email", 6, 11);
+        assert_token(&tokens[2], 2, "paul@example.com", 12, 28);
+        assert_eq!(tokens[2].token_type, TokenType::Email);
+    }
+
+    #[test]
+    fn test_cjk_tokenizer() {
+        let tokenizer_manager = TokenizerManager::default();
+        for tokenizer_name in &["cjk", "ja"] {
+            let cjk_tokenizer = tokenizer_manager.get(tokenizer_name).unwrap();
+            let mut tokens: Vec<Token> = vec![];
+            {
+                let mut add_token = |token: &Token| {
+                    tokens.push(token.clone());
+                };
+                cjk_tokenizer
+                    .token_stream("Tantivyは検索エンジン")
+                    .process(&mut add_token);
+            }
+            assert_eq!(tokens.len(), 7);
+            assert_token(&tokens[0], 0, "tantivy", 0, 7);
+            assert_token(&tokens[1], 1, "は検", 7, 13);
+            assert_token(&tokens[2], 2, "検索", 10, 16);
+            assert_token(&tokens[3], 3, "索エ", 13, 19);
+            assert_token(&tokens[6], 6, "ジン", 22, 28);
+        }
+    }
+
     #[test]
     fn test_non_en_tokenizer() {
         let tokenizer_manager = TokenizerManager::default();
@@ -252,6 +438,35 @@ pub mod tests {
         assert_token(&tokens[2], 2, "φορολογουμεν", 37, 63);
     }
 
+    #[test]
+    fn test_language_tokenizers() {
+        let tokenizer_manager = TokenizerManager::default();
+        let tokens_for = |tokenizer_name: &str, text: &str| {
+            let tokenizer = tokenizer_manager.get(tokenizer_name).unwrap();
+            let mut tokens: Vec<Token> = vec![];
+            tokenizer
+                .token_stream(text)
+                .process(&mut |token: &Token| tokens.push(token.clone()));
+            tokens
+        };
+        let tokens = tokens_for("fr_stem", "L'avion et les Chevaux");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "avion", 0, 7);
+        assert_token(&tokens[1], 3, "cheval", 15, 22);
+        let tokens = tokens_for("de_stem", "Die Häuser, daß STRASSEN");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 1, "haus", 4, 11);
+        assert_token(&tokens[1], 3, "strass", 18, 26);
+        let tokens = tokens_for("el_stem", "ΠΟΙΟΣ ποιος φορολογούμενος");
+        assert_eq!(tokens.len(), 1);
+        assert_token(&tokens[0], 2, "φορολογουμεν", 22, 50);
+        for code in &[
+            "ar", "da", "nl", "fi", "hu", "it", "no", "pt", "ro", "ru", "es", "sv", "ta", "tr",
+        ] {
+            assert!(tokenizer_manager.get(&format!("{}_stem", code)).is_some());
+        }
+    }
+
     #[test]
     fn test_tokenizer_empty() {
         let tokenizer_manager = TokenizerManager::default();
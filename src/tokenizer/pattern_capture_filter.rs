@@ -0,0 +1,163 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! // Also indexes the parts of camelCase identifiers.
+//! let tokenizer = TextAnalyzer::from(SimpleTokenizer)
+//!   .filter(PatternCaptureFilter::new(&["([A-Z]?[a-z]+)"], true).unwrap());
+//!
+//! let mut stream = tokenizer.token_stream("getResponse");
+//! assert_eq!(stream.next().unwrap().text, "getResponse");
+//! assert_eq!(stream.next().unwrap().text, "get");
+//! assert_eq!(stream.next().unwrap().text, "Response");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{sub_token_offsets, BoxTokenStream, Token, TokenBuffer, TokenFilter, TokenStream};
+use crate::TantivyError;
+use regex::Regex;
+
+/// `PatternCaptureFilter` emits the text captured by the groups of one or
+/// several regular expressions as additional tokens.
+///
+/// Every match of every capture group of the regular expressions produces a
+/// token, emitted at the position of the original token. The group `0`
+/// (i.e. the whole match) is ignored unless the regular expression has no capture
+/// group. Duplicate and empty tokens are not emitted.
+///
+/// If none of the regular expressions match a token, the token is emitted unchanged,
+/// regardless of `preserve_original`.
+#[derive(Clone)]
+pub struct PatternCaptureFilter {
+    regexes: Vec<Regex>,
+    preserve_original: bool,
+}
+
+impl PatternCaptureFilter {
+    /// Creates a `PatternCaptureFilter` given a list of regular expressions.
+    ///
+    /// If `preserve_original` is true, the original token is emitted
+    /// before the captured tokens.
+    ///
+    /// Returns an error if one of the regular expressions is invalid.
+    pub fn new(patterns: &[&str], preserve_original: bool) -> crate::Result<PatternCaptureFilter> {
+        let regexes = patterns
+            .iter()
+            .map(|&pattern| {
+                Regex::new(pattern).map_err(|_| TantivyError::InvalidArgument(pattern.to_string()))
+            })
+            .collect::<crate::Result<Vec<Regex>>>()?;
+        Ok(PatternCaptureFilter {
+            regexes,
+            preserve_original,
+        })
+    }
+
+    // Pushes the tokens captured from `token` to `output`.
+    fn capture(&self, token: &Token, output: &mut TokenBuffer) {
+        let mut captured: Vec<&str> = Vec::new();
+        let mut captured_tokens: Vec<Token> = Vec::new();
+        for regex in &self.regexes {
+            let first_group = if regex.captures_len() > 1 { 1 } else { 0 };
+            for captures in regex.captures_iter(&token.text) {
+                for group in captures.iter().skip(first_group).flatten() {
+                    let text = group.as_str();
+                    if text.is_empty() || captured.contains(&text) {
+                        continue;
+                    }
+                    captured.push(text);
+                    let (offset_from, offset_to) =
+                        sub_token_offsets(token, group.start(), group.end());
+                    captured_tokens.push(Token {
+                        offset_from,
+                        offset_to,
+                        text: text.to_string(),
+                        ..token.clone()
+                    });
+                }
+            }
+        }
+        if captured_tokens.is_empty() {
+            output.push(token.clone());
+            return;
+        }
+        if self.preserve_original {
+            captured_tokens.retain(|captured_token| captured_token.text != token.text);
+            output.push(token.clone());
+        }
+        output.extend(captured_tokens);
+    }
+}
+
+impl TokenFilter for PatternCaptureFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(PatternCaptureFilterStream {
+            tail: token_stream,
+            filter: self.clone(),
+            output: TokenBuffer::default(),
+        })
+    }
+}
+
+pub struct PatternCaptureFilterStream<'a> {
+    tail: BoxTokenStream<'a>,
+    filter: PatternCaptureFilter,
+    output: TokenBuffer,
+}
+
+impl<'a> TokenStream for PatternCaptureFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        if self.output.is_empty() {
+            if !self.tail.advance() {
+                return false;
+            }
+            self.filter.capture(self.tail.token(), &mut self.output);
+        }
+        self.output.advance()
+    }
+
+    fn token(&self) -> &Token {
+        self.output.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.output.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PatternCaptureFilter;
+    use crate::tokenizer::tests::{assert_token, token_stream_helper};
+    use crate::tokenizer::{LowerCaser, RegexTokenizer, SimpleTokenizer, TextAnalyzer};
+
+    #[test]
+    fn test_pattern_capture_filter() {
+        let analyzer = TextAnalyzer::from(RegexTokenizer::split(" +").unwrap())
+            .filter(PatternCaptureFilter::new(&["([A-Z]{3})-([0-9]+)", "[0-9]+"], true).unwrap());
+        let tokens = token_stream_helper(&analyzer, "sku ABC-0042");
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "sku", 0, 3);
+        assert_token(&tokens[1], 1, "ABC-0042", 4, 12);
+        assert_token(&tokens[2], 1, "ABC", 4, 7);
+        assert_token(&tokens[3], 1, "0042", 8, 12);
+    }
+
+    #[test]
+    fn test_pattern_capture_filter_no_preserve_original() {
+        let analyzer = TextAnalyzer::from(SimpleTokenizer)
+            .filter(LowerCaser)
+            .filter(PatternCaptureFilter::new(&["(foo)(bar)?", "(ba.)"], false).unwrap());
+        let tokens = token_stream_helper(&analyzer, "FOOBAR foo baz qux");
+        assert_eq!(tokens.len(), 5);
+        assert_token(&tokens[0], 0, "foo", 0, 3);
+        assert_token(&tokens[1], 0, "bar", 3, 6);
+        assert_token(&tokens[2], 1, "foo", 7, 10);
+        assert_token(&tokens[3], 2, "baz", 11, 14);
+        assert_token(&tokens[4], 3, "qux", 15, 18);
+    }
+
+    #[test]
+    fn test_pattern_capture_filter_invalid_pattern() {
+        assert!(PatternCaptureFilter::new(&["a", "("], true).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+fn is_one_of(c: Option<char>, chars: &str) -> bool {
+    c.map(|c| chars.contains(c)).unwrap_or(false)
+}
+
+// Maps the letters of the German alphabet to the letters they are coded as.
+fn normalize_letter(c: char) -> char {
+    match c {
+        'Ä' => 'A',
+        'Ö' => 'O',
+        'Ü' => 'U',
+        'ß' => 'S',
+        _ => c,
+    }
+}
+
+/// Returns the Cologne phonetics code (Kölner Phonetik) of `word`
+/// (e.g. `Müller-Lüdenscheidt` → `65752682`).
+///
+/// Characters that are not letters are ignored.
+pub(crate) fn cologne(word: &str) -> String {
+    let letters: Vec<char> = word
+        .chars()
+        .flat_map(char::to_uppercase)
+        .map(normalize_letter)
+        .filter(char::is_ascii_uppercase)
+        .collect();
+    let mut code = String::new();
+    let mut last_code: Option<char> = None;
+    let mut previous: Option<char> = None;
+    for (i, &c) in letters.iter().enumerate() {
+        let next = letters.get(i + 1).cloned();
+        let codes: &[char] = match c {
+            'A' | 'E' | 'I' | 'J' | 'O' | 'U' | 'Y' => &['0'],
+            'H' => &[],
+            'B' => &['1'],
+            'P' if next != Some('H') => &['1'],
+            'D' | 'T' if !is_one_of(next, "CSZ") => &['2'],
+            'F' | 'P' | 'V' | 'W' => &['3'],
+            'G' | 'K' | 'Q' => &['4'],
+            'X' if !is_one_of(previous, "CKQ") => &['4', '8'],
+            'C' if i == 0 && is_one_of(next, "AHKLOQRUX") => &['4'],
+            'C' if i > 0 && !is_one_of(previous, "SZ") && is_one_of(next, "AHKOQUX") => &['4'],
+            'C' | 'D' | 'S' | 'T' | 'X' | 'Z' => &['8'],
+            'L' => &['5'],
+            'M' | 'N' => &['6'],
+            'R' => &['7'],
+            _ => &[],
+        };
+        if codes.is_empty() {
+            // `H` separates letters with the same code.
+            last_code = None;
+        }
+        for &letter_code in codes {
+            // Repeated codes are coded once, and `0` is only kept at the beginning.
+            if Some(letter_code) != last_code && (letter_code != '0' || code.is_empty()) {
+                code.push(letter_code);
+            }
+            last_code = Some(letter_code);
+        }
+        // `X` is coded as `KS`.
+        previous = if codes.len() == 2 { Some('S') } else { Some(c) };
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cologne;
+
+    #[test]
+    fn test_cologne() {
+        assert_eq!(cologne("Müller-Lüdenscheidt"), "65752682");
+        assert_eq!(cologne("Wikipedia"), "3412");
+        assert_eq!(cologne("Breschnew"), "17863");
+        assert_eq!(cologne("Meyer"), "67");
+        assert_eq!(cologne("Maier"), "67");
+        assert_eq!(cologne("Müller"), cologne("Mueller"));
+        assert_eq!(cologne("Christian"), "47826");
+        assert_eq!(cologne("Xaver"), "4837");
+        assert_eq!(cologne("Aachen"), "046");
+        assert_eq!(cologne("Hexe"), "048");
+        assert_eq!(cologne("42"), "");
+    }
+}
@@ -0,0 +1,691 @@
+// Implementation of the Double Metaphone algorithm by Lawrence Philips,
+// following the rules of the Apache Commons Codec implementation.
+
+const VOWELS: &str = "AEIOUY";
+
+struct DoubleMetaphone {
+    value: Vec<char>,
+    slavo_germanic: bool,
+    max_code_len: usize,
+    primary: String,
+    alternate: String,
+}
+
+fn push_truncated(code: &mut String, value: &str, max_code_len: usize) {
+    let num_chars = max_code_len.saturating_sub(code.len()).min(value.len());
+    code.push_str(&value[..num_chars]);
+}
+
+impl DoubleMetaphone {
+    fn len(&self) -> isize {
+        self.value.len() as isize
+    }
+
+    // Returns the character at `index`, or `'\0'` if `index` is out of bounds.
+    fn char_at(&self, index: isize) -> char {
+        if index < 0 || index >= self.len() {
+            return '\0';
+        }
+        self.value[index as usize]
+    }
+
+    fn is_vowel_at(&self, index: isize) -> bool {
+        VOWELS.contains(self.char_at(index))
+    }
+
+    // Returns true if the `len` characters starting at `start` are equal to one of `criteria`.
+    fn contains(&self, start: isize, len: isize, criteria: &[&str]) -> bool {
+        if start < 0 || start + len > self.len() {
+            return false;
+        }
+        let target: String = self.value[start as usize..(start + len) as usize]
+            .iter()
+            .collect();
+        criteria.contains(&target.as_str())
+    }
+
+    fn is_complete(&self) -> bool {
+        self.primary.len() >= self.max_code_len && self.alternate.len() >= self.max_code_len
+    }
+
+    fn append(&mut self, primary: &str, alternate: &str) {
+        push_truncated(&mut self.primary, primary, self.max_code_len);
+        push_truncated(&mut self.alternate, alternate, self.max_code_len);
+    }
+
+    fn append_both(&mut self, code: &str) {
+        self.append(code, code);
+    }
+
+    // Skips the next character if it is equal to `c`.
+    fn skip_double(&self, index: isize, c: char) -> isize {
+        if self.char_at(index + 1) == c {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn encode(mut self) -> (String, String) {
+        let mut index = if self.contains(0, 2, &["GN", "KN", "PN", "WR", "PS"]) {
+            1
+        } else {
+            0
+        };
+        while !self.is_complete() && index < self.len() {
+            index = match self.char_at(index) {
+                'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                    if index == 0 {
+                        self.append_both("A");
+                    }
+                    index + 1
+                }
+                'B' => {
+                    self.append_both("P");
+                    self.skip_double(index, 'B')
+                }
+                'Ç' => {
+                    self.append_both("S");
+                    index + 1
+                }
+                'C' => self.handle_c(index),
+                'D' => self.handle_d(index),
+                'F' => {
+                    self.append_both("F");
+                    self.skip_double(index, 'F')
+                }
+                'G' => self.handle_g(index),
+                'H' => self.handle_h(index),
+                'J' => self.handle_j(index),
+                'K' => {
+                    self.append_both("K");
+                    self.skip_double(index, 'K')
+                }
+                'L' => self.handle_l(index),
+                'M' => {
+                    self.append_both("M");
+                    if self.condition_m0(index) {
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+                'N' => {
+                    self.append_both("N");
+                    self.skip_double(index, 'N')
+                }
+                'Ñ' => {
+                    self.append_both("N");
+                    index + 1
+                }
+                'P' => self.handle_p(index),
+                'Q' => {
+                    self.append_both("K");
+                    self.skip_double(index, 'Q')
+                }
+                'R' => self.handle_r(index),
+                'S' => self.handle_s(index),
+                'T' => self.handle_t(index),
+                'V' => {
+                    self.append_both("F");
+                    self.skip_double(index, 'V')
+                }
+                'W' => self.handle_w(index),
+                'X' => self.handle_x(index),
+                'Z' => self.handle_z(index),
+                _ => index + 1,
+            };
+        }
+        (self.primary, self.alternate)
+    }
+
+    fn handle_c(&mut self, index: isize) -> isize {
+        if self.condition_c0(index) {
+            self.append_both("K");
+            index + 2
+        } else if index == 0 && self.contains(index, 6, &["CAESAR"]) {
+            self.append_both("S");
+            index + 2
+        } else if self.contains(index, 2, &["CH"]) {
+            self.handle_ch(index)
+        } else if self.contains(index, 2, &["CZ"]) && !self.contains(index - 2, 4, &["WICZ"]) {
+            // "Czerny"
+            self.append("S", "X");
+            index + 2
+        } else if self.contains(index + 1, 3, &["CIA"]) {
+            // "focaccia"
+            self.append_both("X");
+            index + 3
+        } else if self.contains(index, 2, &["CC"]) && !(index == 1 && self.char_at(0) == 'M') {
+            // Double "cc", but not "McClelland"
+            self.handle_cc(index)
+        } else if self.contains(index, 2, &["CK", "CG", "CQ"]) {
+            self.append_both("K");
+            index + 2
+        } else if self.contains(index, 2, &["CI", "CE", "CY"]) {
+            // Italian vs. English
+            if self.contains(index, 3, &["CIO", "CIE", "CIA"]) {
+                self.append("S", "X");
+            } else {
+                self.append_both("S");
+            }
+            index + 2
+        } else {
+            self.append_both("K");
+            if self.contains(index + 1, 2, &[" C", " Q", " G"]) {
+                // "Mac Caffrey", "Mac Gregor"
+                index + 3
+            } else if self.contains(index + 1, 1, &["C", "K", "Q"])
+                && !self.contains(index + 1, 2, &["CE", "CI"])
+            {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn handle_cc(&mut self, index: isize) -> isize {
+        if self.contains(index + 2, 1, &["I", "E", "H"]) && !self.contains(index + 2, 2, &["HU"]) {
+            // "bellocchio", but not "bacchus"
+            if (index == 1 && self.char_at(index - 1) == 'A')
+                || self.contains(index - 1, 5, &["UCCEE", "UCCES"])
+            {
+                // "accident", "accede", "succeed"
+                self.append_both("KS");
+            } else {
+                // "bacci", "bertucci", other Italian
+                self.append_both("X");
+            }
+            index + 3
+        } else {
+            // Pierce's rule
+            self.append_both("K");
+            index + 2
+        }
+    }
+
+    fn handle_ch(&mut self, index: isize) -> isize {
+        if index > 0 && self.contains(index, 4, &["CHAE"]) {
+            // "Michael"
+            self.append("K", "X");
+        } else if self.condition_ch0(index) || self.condition_ch1(index) {
+            // Greek roots ("chemistry", "chorus"...), Germanic
+            self.append_both("K");
+        } else if index > 0 {
+            if self.contains(0, 2, &["MC"]) {
+                self.append_both("K");
+            } else {
+                self.append("X", "K");
+            }
+        } else {
+            self.append_both("X");
+        }
+        index + 2
+    }
+
+    fn handle_d(&mut self, index: isize) -> isize {
+        if self.contains(index, 2, &["DG"]) {
+            if self.contains(index + 2, 1, &["I", "E", "Y"]) {
+                // "edge"
+                self.append_both("J");
+                index + 3
+            } else {
+                // "Edgar"
+                self.append_both("TK");
+                index + 2
+            }
+        } else if self.contains(index, 2, &["DT", "DD"]) {
+            self.append_both("T");
+            index + 2
+        } else {
+            self.append_both("T");
+            index + 1
+        }
+    }
+
+    fn handle_g(&mut self, index: isize) -> isize {
+        let next = self.char_at(index + 1);
+        if next == 'H' {
+            self.handle_gh(index)
+        } else if next == 'N' {
+            if index == 1 && self.is_vowel_at(0) && !self.slavo_germanic {
+                self.append("KN", "N");
+            } else if !self.contains(index + 2, 2, &["EY"]) && !self.slavo_germanic {
+                self.append("N", "KN");
+            } else {
+                self.append_both("KN");
+            }
+            index + 2
+        } else if self.contains(index + 1, 2, &["LI"]) && !self.slavo_germanic {
+            self.append("KL", "L");
+            index + 2
+        } else if index == 0
+            && (next == 'Y'
+                || self.contains(
+                    index + 1,
+                    2,
+                    &[
+                        "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+                    ],
+                ))
+        {
+            // -ges-, -gep-, -gel-, -gie- at beginning
+            self.append("K", "J");
+            index + 2
+        } else if (self.contains(index + 1, 2, &["ER"]) || next == 'Y')
+            && !self.contains(0, 6, &["DANGER", "RANGER", "MANGER"])
+            && !self.contains(index - 1, 1, &["E", "I"])
+            && !self.contains(index - 1, 3, &["RGY", "OGY"])
+        {
+            // -ger-, -gy-
+            self.append("K", "J");
+            index + 2
+        } else if self.contains(index + 1, 1, &["E", "I", "Y"])
+            || self.contains(index - 1, 4, &["AGGI", "OGGI"])
+        {
+            // Italian "biaggi"
+            if self.contains(0, 4, &["VAN ", "VON "])
+                || self.contains(0, 3, &["SCH"])
+                || self.contains(index + 1, 2, &["ET"])
+            {
+                // Obvious Germanic
+                self.append_both("K");
+            } else if self.contains(index + 1, 3, &["IER"]) {
+                self.append_both("J");
+            } else {
+                self.append("J", "K");
+            }
+            index + 2
+        } else if next == 'G' {
+            self.append_both("K");
+            index + 2
+        } else {
+            self.append_both("K");
+            index + 1
+        }
+    }
+
+    fn handle_gh(&mut self, index: isize) -> isize {
+        if index > 0 && !self.is_vowel_at(index - 1) {
+            self.append_both("K");
+        } else if index == 0 {
+            if self.char_at(index + 2) == 'I' {
+                self.append_both("J");
+            } else {
+                self.append_both("K");
+            }
+        } else if (index > 1 && self.contains(index - 2, 1, &["B", "H", "D"]))
+            || (index > 2 && self.contains(index - 3, 1, &["B", "H", "D"]))
+            || (index > 3 && self.contains(index - 4, 1, &["B", "H"]))
+        {
+            // Parker's rule, "hugh"
+        } else if index > 2
+            && self.char_at(index - 1) == 'U'
+            && self.contains(index - 3, 1, &["C", "G", "L", "R", "T"])
+        {
+            // "laugh", "McLaughlin", "cough", "gough", "rough", "tough"
+            self.append_both("F");
+        } else if index > 0 && self.char_at(index - 1) != 'I' {
+            self.append_both("K");
+        }
+        index + 2
+    }
+
+    fn handle_h(&mut self, index: isize) -> isize {
+        // Only kept if first and before a vowel, or between two vowels.
+        if (index == 0 || self.is_vowel_at(index - 1)) && self.is_vowel_at(index + 1) {
+            self.append_both("H");
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_j(&mut self, index: isize) -> isize {
+        if self.contains(index, 4, &["JOSE"]) || self.contains(0, 4, &["SAN "]) {
+            // Obvious Spanish, "Jose", "San Jacinto"
+            if (index == 0 && self.char_at(index + 4) == ' ')
+                || self.len() == 4
+                || self.contains(0, 4, &["SAN "])
+            {
+                self.append_both("H");
+            } else {
+                self.append("J", "H");
+            }
+            return index + 1;
+        }
+        if index == 0 {
+            self.append("J", "A");
+        } else if self.is_vowel_at(index - 1)
+            && !self.slavo_germanic
+            && (self.char_at(index + 1) == 'A' || self.char_at(index + 1) == 'O')
+        {
+            self.append("J", "H");
+        } else if index == self.len() - 1 {
+            self.append("J", "");
+        } else if !self.contains(index + 1, 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+            && !self.contains(index - 1, 1, &["S", "K", "L"])
+        {
+            self.append_both("J");
+        }
+        self.skip_double(index, 'J')
+    }
+
+    fn handle_l(&mut self, index: isize) -> isize {
+        if self.char_at(index + 1) == 'L' {
+            if self.condition_l0(index) {
+                self.append("L", "");
+            } else {
+                self.append_both("L");
+            }
+            index + 2
+        } else {
+            self.append_both("L");
+            index + 1
+        }
+    }
+
+    fn handle_p(&mut self, index: isize) -> isize {
+        if self.char_at(index + 1) == 'H' {
+            self.append_both("F");
+            index + 2
+        } else {
+            self.append_both("P");
+            if self.contains(index + 1, 1, &["P", "B"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn handle_r(&mut self, index: isize) -> isize {
+        if index == self.len() - 1
+            && !self.slavo_germanic
+            && self.contains(index - 2, 2, &["IE"])
+            && !self.contains(index - 4, 2, &["ME", "MA"])
+        {
+            // French, e.g. "Rogier"
+            self.append("", "R");
+        } else {
+            self.append_both("R");
+        }
+        self.skip_double(index, 'R')
+    }
+
+    fn handle_s(&mut self, index: isize) -> isize {
+        if self.contains(index - 1, 3, &["ISL", "YSL"]) {
+            // "island", "isle", "carlisle", "carlysle"
+            index + 1
+        } else if index == 0 && self.contains(index, 5, &["SUGAR"]) {
+            self.append("X", "S");
+            index + 1
+        } else if self.contains(index, 2, &["SH"]) {
+            if self.contains(index + 1, 4, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                // Germanic
+                self.append_both("S");
+            } else {
+                self.append_both("X");
+            }
+            index + 2
+        } else if self.contains(index, 3, &["SIO", "SIA"]) || self.contains(index, 4, &["SIAN"]) {
+            // Italian and Armenian
+            if self.slavo_germanic {
+                self.append_both("S");
+            } else {
+                self.append("S", "X");
+            }
+            index + 3
+        } else if (index == 0 && self.contains(index + 1, 1, &["M", "N", "L", "W"]))
+            || self.contains(index + 1, 1, &["Z"])
+        {
+            // "smith" matches "schmidt", "snider" matches "schneider"
+            self.append("S", "X");
+            self.skip_double(index, 'Z')
+        } else if self.contains(index, 2, &["SC"]) {
+            self.handle_sc(index)
+        } else {
+            if index == self.len() - 1 && self.contains(index - 2, 2, &["AI", "OI"]) {
+                // French, e.g. "resnais", "artois"
+                self.append("", "S");
+            } else {
+                self.append_both("S");
+            }
+            if self.contains(index + 1, 1, &["S", "Z"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn handle_sc(&mut self, index: isize) -> isize {
+        if self.char_at(index + 2) == 'H' {
+            // Schlesinger's rule
+            if self.contains(index + 3, 2, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                // Dutch origin, e.g. "school", "schooner"
+                if self.contains(index + 3, 2, &["ER", "EN"]) {
+                    // "schermerhorn", "schenker"
+                    self.append("X", "SK");
+                } else {
+                    self.append_both("SK");
+                }
+            } else if index == 0 && !self.is_vowel_at(3) && self.char_at(3) != 'W' {
+                self.append("X", "S");
+            } else {
+                self.append_both("X");
+            }
+        } else if self.contains(index + 2, 1, &["I", "E", "Y"]) {
+            self.append_both("S");
+        } else {
+            self.append_both("SK");
+        }
+        index + 3
+    }
+
+    fn handle_t(&mut self, index: isize) -> isize {
+        if self.contains(index, 4, &["TION"]) || self.contains(index, 3, &["TIA", "TCH"]) {
+            self.append_both("X");
+            index + 3
+        } else if self.contains(index, 2, &["TH"]) || self.contains(index, 3, &["TTH"]) {
+            if self.contains(index + 2, 2, &["OM", "AM"])
+                || self.contains(0, 4, &["VAN ", "VON "])
+                || self.contains(0, 3, &["SCH"])
+            {
+                // "thomas", "thames", or Germanic
+                self.append_both("T");
+            } else {
+                self.append("0", "T");
+            }
+            index + 2
+        } else {
+            self.append_both("T");
+            if self.contains(index + 1, 1, &["T", "D"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn handle_w(&mut self, index: isize) -> isize {
+        if self.contains(index, 2, &["WR"]) {
+            self.append_both("R");
+            index + 2
+        } else if index == 0 && (self.is_vowel_at(index + 1) || self.contains(index, 2, &["WH"])) {
+            if self.is_vowel_at(index + 1) {
+                // "Wasserman" matches "Vasserman"
+                self.append("A", "F");
+            } else {
+                // "Uomo" matches "Womo"
+                self.append_both("A");
+            }
+            index + 1
+        } else if (index == self.len() - 1 && self.is_vowel_at(index - 1))
+            || self.contains(index - 1, 5, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+            || self.contains(0, 3, &["SCH"])
+        {
+            // "Arnow" matches "Arnoff"
+            self.append("", "F");
+            index + 1
+        } else if self.contains(index, 4, &["WICZ", "WITZ"]) {
+            // Polish, e.g. "filipowicz"
+            self.append("TS", "FX");
+            index + 4
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_x(&mut self, index: isize) -> isize {
+        if index == 0 {
+            self.append_both("S");
+            return index + 1;
+        }
+        let is_french_ending = index == self.len() - 1
+            && (self.contains(index - 3, 3, &["IAU", "EAU"])
+                || self.contains(index - 2, 2, &["AU", "OU"]));
+        if !is_french_ending {
+            // Not French, e.g. "breaux"
+            self.append_both("KS");
+        }
+        if self.contains(index + 1, 1, &["C", "X"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn handle_z(&mut self, index: isize) -> isize {
+        if self.char_at(index + 1) == 'H' {
+            // Chinese pinyin, e.g. "zhao"
+            self.append_both("J");
+            return index + 2;
+        }
+        if self.contains(index + 1, 2, &["ZO", "ZI", "ZA"])
+            || (self.slavo_germanic && index > 0 && self.char_at(index - 1) != 'T')
+        {
+            self.append("S", "TS");
+        } else {
+            self.append_both("S");
+        }
+        self.skip_double(index, 'Z')
+    }
+
+    fn condition_c0(&self, index: isize) -> bool {
+        if self.contains(index, 4, &["CHIA"]) {
+            return true;
+        }
+        if index <= 1 || self.is_vowel_at(index - 2) || !self.contains(index - 1, 3, &["ACH"]) {
+            return false;
+        }
+        let c = self.char_at(index + 2);
+        (c != 'I' && c != 'E') || self.contains(index - 2, 6, &["BACHER", "MACHER"])
+    }
+
+    fn condition_ch0(&self, index: isize) -> bool {
+        index == 0
+            && (self.contains(index + 1, 5, &["HARAC", "HARIS"])
+                || self.contains(index + 1, 3, &["HOR", "HYM", "HIA", "HEM"]))
+            && !self.contains(0, 5, &["CHORE"])
+    }
+
+    fn condition_ch1(&self, index: isize) -> bool {
+        self.contains(0, 4, &["VAN ", "VON "])
+            || self.contains(0, 3, &["SCH"])
+            || self.contains(index - 2, 6, &["ORCHES", "ARCHIT", "ORCHID"])
+            || self.contains(index + 2, 1, &["T", "S"])
+            || ((self.contains(index - 1, 1, &["A", "O", "U", "E"]) || index == 0)
+                && (self.contains(
+                    index + 2,
+                    1,
+                    &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "],
+                ) || index + 1 == self.len() - 1))
+    }
+
+    fn condition_l0(&self, index: isize) -> bool {
+        if index == self.len() - 3 && self.contains(index - 1, 4, &["ILLO", "ILLA", "ALLE"]) {
+            return true;
+        }
+        (self.contains(self.len() - 2, 2, &["AS", "OS"])
+            || self.contains(self.len() - 1, 1, &["A", "O"]))
+            && self.contains(index - 1, 4, &["ALLE"])
+    }
+
+    fn condition_m0(&self, index: isize) -> bool {
+        if self.char_at(index + 1) == 'M' {
+            return true;
+        }
+        self.contains(index - 1, 3, &["UMB"])
+            && (index + 1 == self.len() - 1 || self.contains(index + 2, 2, &["ER"]))
+    }
+}
+
+/// Returns the primary and alternate Double Metaphone codes of `word`
+/// (e.g. `Smith` → `SM0`, `XMT`), truncated to `max_code_len` characters.
+pub(crate) fn double_metaphone(word: &str, max_code_len: usize) -> (String, String) {
+    let value: Vec<char> = word.trim().chars().flat_map(char::to_uppercase).collect();
+    let value_str: String = value.iter().collect();
+    let slavo_germanic = value_str.contains('W')
+        || value_str.contains('K')
+        || value_str.contains("CZ")
+        || value_str.contains("WITZ");
+    DoubleMetaphone {
+        value,
+        slavo_germanic,
+        max_code_len,
+        primary: String::new(),
+        alternate: String::new(),
+    }
+    .encode()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::double_metaphone;
+
+    fn codes(word: &str) -> (String, String) {
+        double_metaphone(word, 4)
+    }
+
+    fn assert_codes(word: &str, primary: &str, alternate: &str) {
+        assert_eq!(
+            codes(word),
+            (primary.to_string(), alternate.to_string()),
+            "{}",
+            word
+        );
+    }
+
+    #[test]
+    fn test_double_metaphone() {
+        assert_codes("Smith", "SM0", "XMT");
+        assert_codes("Schmidt", "XMT", "SMT");
+        assert_codes("Thompson", "TMPS", "TMPS");
+        assert_codes("Michael", "MKL", "MXL");
+        assert_codes("Jose", "HS", "HS");
+        assert_codes("Caesar", "SSR", "SSR");
+        assert_codes("knight", "NT", "NT");
+        assert_codes("Phillips", "FLPS", "FLPS");
+        assert_codes("Catherine", "K0RN", "KTRN");
+        assert_codes("Katherine", "K0RN", "KTRN");
+        assert_codes("Jon", "JN", "AN");
+        assert_codes("Arnow", "ARN", "ARNF");
+        assert_codes("laugh", "LF", "LF");
+        assert_codes("Xavier", "SF", "SFR");
+        assert_codes("", "", "");
+    }
+
+    #[test]
+    fn test_double_metaphone_max_code_len() {
+        assert_eq!(
+            double_metaphone("Christopherson", 6),
+            ("KRSTFR".to_string(), "KRSTFR".to_string())
+        );
+        assert_eq!(
+            double_metaphone("Christopherson", 4),
+            ("KRST".to_string(), "KRST".to_string())
+        );
+    }
+}
@@ -0,0 +1,6 @@
+mod cologne;
+mod double_metaphone;
+mod phonetic_filter;
+mod soundex;
+
+pub use self::phonetic_filter::{PhoneticAlgorithm, PhoneticFilter};
@@ -0,0 +1,202 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let tokenizer = TextAnalyzer::from(SimpleTokenizer)
+//!   .filter(PhoneticFilter::new(PhoneticAlgorithm::Soundex));
+//!
+//! let mut stream = tokenizer.token_stream("Robert");
+//! assert_eq!(stream.next().unwrap().text, "Robert");
+//! assert_eq!(stream.next().unwrap().text, "R163");
+//! assert!(stream.next().is_none());
+//! ```
+use super::cologne::cologne;
+use super::double_metaphone::double_metaphone;
+use super::soundex::soundex;
+use crate::tokenizer::{BoxTokenStream, Token, TokenBuffer, TokenFilter, TokenStream};
+use serde::{Deserialize, Serialize};
+
+// Maximum length of the Double Metaphone codes.
+const DOUBLE_METAPHONE_MAX_CODE_LEN: usize = 4;
+
+/// Available phonetic algorithms.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Copy, Clone)]
+pub enum PhoneticAlgorithm {
+    /// American Soundex, suited to English names (`Robert` → `R163`).
+    Soundex,
+    /// Double Metaphone, which takes into account the spelling of names of many
+    /// origins, and emits an alternate code for ambiguous names (`Smith` → `SM0`, `XMT`).
+    DoubleMetaphone,
+    /// Cologne phonetics (Kölner Phonetik), suited to German (`Meyer` → `67`).
+    Cologne,
+}
+
+impl PhoneticAlgorithm {
+    // Returns the codes of `word`, without duplicates.
+    fn encode(self, word: &str) -> Vec<String> {
+        let codes = match self {
+            PhoneticAlgorithm::Soundex => vec![soundex(word)],
+            PhoneticAlgorithm::DoubleMetaphone => {
+                let (primary, alternate) = double_metaphone(word, DOUBLE_METAPHONE_MAX_CODE_LEN);
+                if primary == alternate {
+                    vec![primary]
+                } else {
+                    vec![primary, alternate]
+                }
+            }
+            PhoneticAlgorithm::Cologne => vec![cologne(word)],
+        };
+        codes.into_iter().filter(|code| !code.is_empty()).collect()
+    }
+}
+
+/// `PhoneticFilter` encodes tokens with a phonetic algorithm, so that
+/// words that sound alike, such as `Meyer` and `Maier`, produce the same term.
+///
+/// By default, the codes are injected next to the original token, at the same position.
+/// Tokens that can't be encoded (e.g. numbers) are emitted unchanged.
+#[derive(Clone)]
+pub struct PhoneticFilter {
+    algorithm: PhoneticAlgorithm,
+    inject: bool,
+}
+
+impl PhoneticFilter {
+    /// Creates a `PhoneticFilter` using the given phonetic algorithm.
+    pub fn new(algorithm: PhoneticAlgorithm) -> PhoneticFilter {
+        PhoneticFilter {
+            algorithm,
+            inject: true,
+        }
+    }
+
+    /// Sets whether the original token should be emitted before its codes.
+    /// If false, tokens are replaced by their codes. Defaults to true.
+    pub fn set_inject(mut self, inject: bool) -> PhoneticFilter {
+        self.inject = inject;
+        self
+    }
+}
+
+impl TokenFilter for PhoneticFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(PhoneticFilterStream {
+            tail: token_stream,
+            filter: self.clone(),
+            output: TokenBuffer::default(),
+        })
+    }
+}
+
+pub struct PhoneticFilterStream<'a> {
+    tail: BoxTokenStream<'a>,
+    filter: PhoneticFilter,
+    output: TokenBuffer,
+}
+
+impl<'a> TokenStream for PhoneticFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        if self.output.is_empty() {
+            if !self.tail.advance() {
+                return false;
+            }
+            let token = self.tail.token();
+            let codes = self.filter.algorithm.encode(&token.text);
+            if codes.is_empty() || self.filter.inject {
+                self.output.push(token.clone());
+            }
+            for code in codes {
+                if code != token.text {
+                    self.output.push(Token {
+                        text: code,
+                        ..token.clone()
+                    });
+                }
+            }
+        }
+        self.output.advance()
+    }
+
+    fn token(&self) -> &Token {
+        self.output.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.output.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PhoneticAlgorithm, PhoneticFilter};
+    use crate::collector::Count;
+    use crate::query::QueryParser;
+    use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions};
+    use crate::tokenizer::tests::{assert_token, token_stream_helper};
+    use crate::tokenizer::{LowerCaser, SimpleTokenizer, TextAnalyzer};
+    use crate::Index;
+
+    fn phonetic_analyzer(phonetic_filter: PhoneticFilter) -> TextAnalyzer {
+        TextAnalyzer::from(SimpleTokenizer).filter(phonetic_filter)
+    }
+
+    #[test]
+    fn test_phonetic_filter_inject() {
+        let tokens = token_stream_helper(
+            &phonetic_analyzer(PhoneticFilter::new(PhoneticAlgorithm::DoubleMetaphone)),
+            "John Smith 42",
+        );
+        assert_eq!(tokens.len(), 7);
+        assert_token(&tokens[0], 0, "John", 0, 4);
+        assert_token(&tokens[1], 0, "JN", 0, 4);
+        assert_token(&tokens[2], 0, "AN", 0, 4);
+        assert_token(&tokens[3], 1, "Smith", 5, 10);
+        assert_token(&tokens[4], 1, "SM0", 5, 10);
+        assert_token(&tokens[5], 1, "XMT", 5, 10);
+        // "42" has no code: it is emitted unchanged.
+        assert_token(&tokens[6], 2, "42", 11, 13);
+    }
+
+    #[test]
+    fn test_phonetic_filter_replace() {
+        let tokens = token_stream_helper(
+            &phonetic_analyzer(PhoneticFilter::new(PhoneticAlgorithm::Cologne).set_inject(false)),
+            "Meyer Maier 42",
+        );
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "67", 0, 5);
+        assert_token(&tokens[1], 1, "67", 6, 11);
+        assert_token(&tokens[2], 2, "42", 12, 14);
+    }
+
+    #[test]
+    fn test_phonetic_filter_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("names")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+        let name = schema_builder.add_text_field("name", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        index.tokenizers().register(
+            "names",
+            TextAnalyzer::from(SimpleTokenizer)
+                .filter(LowerCaser)
+                .filter(PhoneticFilter::new(PhoneticAlgorithm::DoubleMetaphone)),
+        );
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(name=>"Catherine Schmidt"));
+        index_writer.add_document(doc!(name=>"Kathryn Smith"));
+        index_writer.add_document(doc!(name=>"John Doe"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![name]);
+        let count =
+            |query: &str| searcher.search(&query_parser.parse_query(query).unwrap(), &Count);
+        assert_eq!(count("Katherine")?, 2);
+        assert_eq!(count("Smyth")?, 2);
+        assert_eq!(count("Jon")?, 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+const SOUNDEX_LEN: usize = 4;
+
+// Returns the Soundex code of an uppercase ASCII letter, `'0'` for vowels,
+// or `None` for `H` and `W`, which do not separate consonants with the same code.
+fn letter_code(letter: u8) -> Option<u8> {
+    let code = match letter {
+        b'B' | b'F' | b'P' | b'V' => b'1',
+        b'C' | b'G' | b'J' | b'K' | b'Q' | b'S' | b'X' | b'Z' => b'2',
+        b'D' | b'T' => b'3',
+        b'L' => b'4',
+        b'M' | b'N' => b'5',
+        b'R' => b'6',
+        b'H' | b'W' => return None,
+        _ => b'0',
+    };
+    Some(code)
+}
+
+/// Returns the American Soundex code of `word` (e.g. `Robert` → `R163`).
+///
+/// Characters that are not ASCII letters are ignored. Returns an empty string
+/// if `word` contains no ASCII letter.
+pub(crate) fn soundex(word: &str) -> String {
+    let mut letters = word
+        .bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|letter| letter.to_ascii_uppercase());
+    let first_letter = match letters.next() {
+        Some(first_letter) => first_letter,
+        None => return String::new(),
+    };
+    let mut code = vec![first_letter];
+    let mut last_code = letter_code(first_letter);
+    for letter in letters {
+        if code.len() == SOUNDEX_LEN {
+            break;
+        }
+        let letter_code = match letter_code(letter) {
+            Some(letter_code) => letter_code,
+            None => continue,
+        };
+        if letter_code != b'0' && Some(letter_code) != last_code {
+            code.push(letter_code);
+        }
+        last_code = Some(letter_code);
+    }
+    code.resize(SOUNDEX_LEN, b'0');
+    String::from_utf8(code).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::soundex;
+
+    #[test]
+    fn test_soundex() {
+        assert_eq!(soundex("Robert"), "R163");
+        assert_eq!(soundex("Rupert"), "R163");
+        assert_eq!(soundex("Rubin"), "R150");
+        assert_eq!(soundex("Ashcraft"), "A261");
+        assert_eq!(soundex("Tymczak"), "T522");
+        assert_eq!(soundex("Pfister"), "P236");
+        assert_eq!(soundex("Honeyman"), "H555");
+        assert_eq!(soundex("o'hara"), "O600");
+        assert_eq!(soundex("Lee"), "L000");
+        assert_eq!(soundex("42"), "");
+    }
+}
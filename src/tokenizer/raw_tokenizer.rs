@@ -1,4 +1,4 @@
-use super::{Token, TokenStream, Tokenizer};
+use super::{Token, TokenStream, TokenType, Tokenizer};
 use crate::tokenizer::BoxTokenStream;
 
 /// For each value of the field, emit a single unprocessed token.
@@ -18,6 +18,7 @@ impl Tokenizer for RawTokenizer {
             position: 0,
             text: text.to_string(),
             position_length: 1,
+            token_type: TokenType::Word,
         };
         RawTokenStream {
             token,
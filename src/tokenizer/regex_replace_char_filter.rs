@@ -0,0 +1,92 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! // Joins the digits of phone numbers.
+//! let char_filter = RegexReplaceCharFilter::new("([0-9]{2})[ .]", "$1").unwrap();
+//! let tokenizer = TextAnalyzer::from(SimpleTokenizer)
+//!   .char_filter(char_filter);
+//!
+//! let mut stream = tokenizer.token_stream("call 01.23.45.67.89");
+//! assert_eq!(stream.next().unwrap().text, "call");
+//! let token = stream.next().unwrap();
+//! assert_eq!(token.text, "0123456789");
+//! assert_eq!((token.offset_from, token.offset_to), (5, 19));
+//! assert!(stream.next().is_none());
+//! ```
+use super::{CharFilter, OffsetCorrections};
+use crate::TantivyError;
+use regex::Regex;
+
+/// `RegexReplaceCharFilter` replaces the matches of a regular expression.
+///
+/// The replacement may refer to the capture groups of the regular expression,
+/// with the syntax of [`Regex::replace`](https://docs.rs/regex/1/regex/struct.Regex.html#method.replace)
+/// (`$1`, `${name}`...).
+#[derive(Clone)]
+pub struct RegexReplaceCharFilter {
+    regex: Regex,
+    replacement: String,
+}
+
+impl RegexReplaceCharFilter {
+    /// Creates a `RegexReplaceCharFilter` given a regular expression and its replacement.
+    ///
+    /// Returns an error if the regular expression is invalid.
+    pub fn new(pattern: &str, replacement: &str) -> crate::Result<RegexReplaceCharFilter> {
+        let regex =
+            Regex::new(pattern).map_err(|_| TantivyError::InvalidArgument(pattern.to_string()))?;
+        Ok(RegexReplaceCharFilter {
+            regex,
+            replacement: replacement.to_string(),
+        })
+    }
+}
+
+impl CharFilter for RegexReplaceCharFilter {
+    fn filter(&self, text: &str, offset_corrections: &mut OffsetCorrections) -> String {
+        let mut output = String::with_capacity(text.len());
+        // Offset of the first byte of `text` that is not in `output` yet.
+        let mut copied = 0;
+        for captures in self.regex.captures_iter(text) {
+            let regex_match = captures.get(0).unwrap();
+            output.push_str(&text[copied..regex_match.start()]);
+            let output_start = output.len();
+            captures.expand(&self.replacement, &mut output);
+            if output_start < output.len() || !regex_match.range().is_empty() {
+                offset_corrections.add_replacement(output_start..output.len(), regex_match.range());
+            }
+            copied = regex_match.end();
+        }
+        output.push_str(&text[copied..]);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RegexReplaceCharFilter;
+    use crate::tokenizer::{CharFilter, OffsetCorrections};
+
+    #[test]
+    fn test_regex_replace_char_filter() {
+        let char_filter = RegexReplaceCharFilter::new(
+            "(?P<first>[a-z]+)-(?P<second>[a-z]+)",
+            "${second}${first}",
+        )
+        .unwrap();
+        let mut offset_corrections = OffsetCorrections::default();
+        let filtered = char_filter.filter("x ab-cd y", &mut offset_corrections);
+        assert_eq!(filtered, "x cdab y");
+        assert_eq!(offset_corrections.correct_start(2), 2);
+        assert_eq!(offset_corrections.correct_end(6), 7);
+        assert_eq!(offset_corrections.correct_start(7), 8);
+
+        let char_filter = RegexReplaceCharFilter::new("x*", "").unwrap();
+        assert_eq!(
+            char_filter.filter("axxb", &mut OffsetCorrections::default()),
+            "ab"
+        );
+        assert!(RegexReplaceCharFilter::new("(", "").is_err());
+    }
+}
@@ -0,0 +1,191 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! // Emits the SKUs found in the text.
+//! let tokenizer = RegexTokenizer::new("[A-Z]{3}-[0-9]{4}").unwrap();
+//! let mut stream = tokenizer.token_stream("Order ABC-1234 and XYZ-0042");
+//! assert_eq!(stream.next().unwrap().text, "ABC-1234");
+//! assert_eq!(stream.next().unwrap().text, "XYZ-0042");
+//! assert!(stream.next().is_none());
+//!
+//! // Splits a log line on pipes.
+//! let tokenizer = RegexTokenizer::split(" *\\| *").unwrap();
+//! let mut stream = tokenizer.token_stream("INFO | server | started");
+//! assert_eq!(stream.next().unwrap().text, "INFO");
+//! assert_eq!(stream.next().unwrap().text, "server");
+//! assert_eq!(stream.next().unwrap().text, "started");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{BoxTokenStream, Token, TokenStream, Tokenizer};
+use crate::TantivyError;
+use regex::Regex;
+
+/// Tokenize the text using a regular expression.
+///
+/// Depending on how it was created, the tokenizer either emits the matches of the
+/// regular expression as tokens ([`RegexTokenizer::new`](#method.new)), or splits the
+/// text on its matches ([`RegexTokenizer::split`](#method.split)).
+/// Empty tokens are never emitted.
+#[derive(Clone)]
+pub struct RegexTokenizer {
+    regex: Regex,
+    split: bool,
+}
+
+impl RegexTokenizer {
+    /// Creates a `RegexTokenizer` emitting the matches of `pattern` as tokens.
+    ///
+    /// Returns an error if the regular expression is invalid.
+    pub fn new(pattern: &str) -> crate::Result<RegexTokenizer> {
+        RegexTokenizer::with_mode(pattern, false)
+    }
+
+    /// Creates a `RegexTokenizer` splitting the text on the matches of `pattern`.
+    ///
+    /// Returns an error if the regular expression is invalid.
+    pub fn split(pattern: &str) -> crate::Result<RegexTokenizer> {
+        RegexTokenizer::with_mode(pattern, true)
+    }
+
+    fn with_mode(pattern: &str, split: bool) -> crate::Result<RegexTokenizer> {
+        let regex =
+            Regex::new(pattern).map_err(|_| TantivyError::InvalidArgument(pattern.to_string()))?;
+        Ok(RegexTokenizer { regex, split })
+    }
+}
+
+pub struct RegexTokenStream<'a> {
+    regex: Regex,
+    split: bool,
+    text: &'a str,
+    offset: usize,
+    token: Token,
+}
+
+impl Tokenizer for RegexTokenizer {
+    fn token_stream<'a>(&self, text: &'a str) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(RegexTokenStream {
+            regex: self.regex.clone(),
+            split: self.split,
+            text,
+            offset: 0,
+            token: Token::default(),
+        })
+    }
+}
+
+impl<'a> RegexTokenStream<'a> {
+    // Returns the range of the next token, and the offset from which
+    // the following token should be searched.
+    fn next_token_range(&self) -> Option<(usize, usize, usize)> {
+        let mut search_offset = self.offset;
+        loop {
+            let regex_match = if search_offset <= self.text.len() {
+                self.regex.find_at(self.text, search_offset)
+            } else {
+                None
+            };
+            if self.split {
+                let (token_end, next_offset) = match regex_match {
+                    Some(regex_match) if regex_match.start() == regex_match.end() => {
+                        // Empty matches do not split the text.
+                        search_offset = next_char_offset(self.text, regex_match.end());
+                        continue;
+                    }
+                    Some(regex_match) => (regex_match.start(), regex_match.end()),
+                    None if self.offset < self.text.len() => (self.text.len(), self.text.len()),
+                    None => return None,
+                };
+                return Some((self.offset, token_end, next_offset));
+            }
+            let regex_match = regex_match?;
+            if regex_match.start() < regex_match.end() {
+                return Some((regex_match.start(), regex_match.end(), regex_match.end()));
+            }
+            search_offset = next_char_offset(self.text, regex_match.end());
+        }
+    }
+}
+
+// Returns the offset of the character following the one at `offset`,
+// or an offset past the end of the text if `offset` is the end of the text.
+fn next_char_offset(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .chars()
+        .next()
+        .map(|c| offset + c.len_utf8())
+        .unwrap_or(offset + 1)
+}
+
+impl<'a> TokenStream for RegexTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        self.token.text.clear();
+        while let Some((offset_from, offset_to, next_offset)) = self.next_token_range() {
+            self.offset = next_offset;
+            if offset_from < offset_to {
+                self.token.position = self.token.position.wrapping_add(1);
+                self.token.offset_from = offset_from;
+                self.token.offset_to = offset_to;
+                self.token.text.push_str(&self.text[offset_from..offset_to]);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RegexTokenizer;
+    use crate::tokenizer::tests::{assert_token, token_stream_helper};
+    use crate::tokenizer::TextAnalyzer;
+
+    #[test]
+    fn test_regex_tokenizer() {
+        let analyzer = TextAnalyzer::from(RegexTokenizer::new("[a-zA-Z_][a-zA-Z0-9_]*").unwrap());
+        let tokens = token_stream_helper(&analyzer, "let x2 = foo_bar(1);");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "let", 0, 3);
+        assert_token(&tokens[1], 1, "x2", 4, 6);
+        assert_token(&tokens[2], 2, "foo_bar", 9, 16);
+
+        // Empty matches are skipped.
+        let analyzer = TextAnalyzer::from(RegexTokenizer::new("[0-9]*").unwrap());
+        let tokens = token_stream_helper(&analyzer, "é12 3");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "12", 2, 4);
+        assert_token(&tokens[1], 1, "3", 5, 6);
+    }
+
+    #[test]
+    fn test_regex_tokenizer_split() {
+        let analyzer = TextAnalyzer::from(RegexTokenizer::split("[,;] *").unwrap());
+        let tokens = token_stream_helper(&analyzer, "a, b;;c, ");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "a", 0, 1);
+        assert_token(&tokens[1], 1, "b", 3, 4);
+        assert_token(&tokens[2], 2, "c", 6, 7);
+
+        let analyzer = TextAnalyzer::from(RegexTokenizer::split("x*").unwrap());
+        let tokens = token_stream_helper(&analyzer, "éaxxb");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "éa", 0, 3);
+        assert_token(&tokens[1], 1, "b", 5, 6);
+        assert!(token_stream_helper(&analyzer, "").is_empty());
+    }
+
+    #[test]
+    fn test_regex_tokenizer_invalid_pattern() {
+        assert!(RegexTokenizer::new("[").is_err());
+        assert!(RegexTokenizer::split("(").is_err());
+    }
+}
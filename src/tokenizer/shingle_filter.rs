@@ -0,0 +1,274 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let tokenizer = TextAnalyzer::from(SimpleTokenizer)
+//!   .filter(ShingleFilter::new(2, 2).set_output_unigrams(false));
+//!
+//! let mut stream = tokenizer.token_stream("new york city");
+//! assert_eq!(stream.next().unwrap().text, "new york");
+//! assert_eq!(stream.next().unwrap().text, "york city");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{BoxTokenStream, Token, TokenBuffer, TokenFilter, TokenStream, TokenType};
+use std::collections::VecDeque;
+
+/// `ShingleFilter` combines adjacent tokens into shingles, i.e. word n-grams.
+///
+/// For instance, the 2-shingles of `new york city` are `new york`
+/// and `york city`. Shingles are emitted at the position of their first token,
+/// after the original token if unigrams are emitted as well, and their
+/// `position_length` is the number of positions they span.
+///
+/// When tokens have been removed before this filter (e.g. by the
+/// [`StopWordFilter`](./struct.StopWordFilter.html)), the resulting position gaps are
+/// filled with a filler token (`_` by default), so that shingles do not join
+/// words that were not adjacent in the original text.
+#[derive(Clone)]
+pub struct ShingleFilter {
+    min_shingle_size: usize,
+    max_shingle_size: usize,
+    output_unigrams: bool,
+    separator: String,
+    filler_token: String,
+}
+
+impl Default for ShingleFilter {
+    /// Creates a `ShingleFilter` emitting the unigrams and 2-shingles.
+    fn default() -> ShingleFilter {
+        ShingleFilter::new(2, 2)
+    }
+}
+
+impl ShingleFilter {
+    /// Creates a `ShingleFilter` emitting shingles of `min_shingle_size`
+    /// to `max_shingle_size` tokens, as well as the original tokens.
+    ///
+    /// # Panics
+    ///
+    /// If `min_shingle_size` is lower than 2 or greater than `max_shingle_size`.
+    pub fn new(min_shingle_size: usize, max_shingle_size: usize) -> ShingleFilter {
+        assert!(min_shingle_size >= 2, "min_shingle_size must be at least 2");
+        assert!(
+            min_shingle_size <= max_shingle_size,
+            "min_shingle_size must not be greater than max_shingle_size"
+        );
+        ShingleFilter {
+            min_shingle_size,
+            max_shingle_size,
+            output_unigrams: true,
+            separator: " ".to_string(),
+            filler_token: "_".to_string(),
+        }
+    }
+
+    /// Sets whether the original tokens should be emitted. Defaults to true.
+    pub fn set_output_unigrams(mut self, output_unigrams: bool) -> ShingleFilter {
+        self.output_unigrams = output_unigrams;
+        self
+    }
+
+    /// Sets the string used to join the tokens of a shingle. Defaults to a space.
+    pub fn set_separator(mut self, separator: &str) -> ShingleFilter {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Sets the token used to fill position gaps. Defaults to `_`.
+    pub fn set_filler_token(mut self, filler_token: &str) -> ShingleFilter {
+        self.filler_token = filler_token.to_string();
+        self
+    }
+}
+
+impl TokenFilter for ShingleFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(ShingleFilterStream {
+            tail: token_stream,
+            filter: self.clone(),
+            window: VecDeque::new(),
+            output: TokenBuffer::default(),
+            tail_position: usize::MAX,
+        })
+    }
+}
+
+// A token of the shingle window, or a filler if `token` is `None`.
+struct WindowItem {
+    position: usize,
+    token: Option<Token>,
+}
+
+pub struct ShingleFilterStream<'a> {
+    tail: BoxTokenStream<'a>,
+    filter: ShingleFilter,
+    // Tokens that may be part of the next shingles.
+    window: VecDeque<WindowItem>,
+    output: TokenBuffer,
+    // Position of the last token read from the tail.
+    tail_position: usize,
+}
+
+impl<'a> ShingleFilterStream<'a> {
+    // Reads tokens from the tail until the window holds the tokens
+    // of the longest shingle.
+    fn fill_window(&mut self) {
+        while self.window.len() < self.filter.max_shingle_size && self.tail.advance() {
+            let token = self.tail.token();
+            let gap = token
+                .position
+                .wrapping_sub(self.tail_position)
+                .wrapping_sub(1);
+            // Fillers further than the longest shingle can't be part of any shingle.
+            let num_fillers = gap.min(self.filter.max_shingle_size - 1);
+            for i in (1..=num_fillers).rev() {
+                self.window.push_back(WindowItem {
+                    position: token.position - i,
+                    token: None,
+                });
+            }
+            self.tail_position = token.position;
+            self.window.push_back(WindowItem {
+                position: token.position,
+                token: Some(token.clone()),
+            });
+        }
+    }
+
+    // Pushes the tokens starting with the first token of the window to the output.
+    fn process_window(&mut self) {
+        let first_item = &self.window[0];
+        if self.filter.output_unigrams {
+            if let Some(token) = &first_item.token {
+                self.output.push(token.clone());
+            }
+        }
+        let max_shingle_size = self.filter.max_shingle_size.min(self.window.len());
+        for shingle_size in self.filter.min_shingle_size..=max_shingle_size {
+            let items = self.window.iter().take(shingle_size);
+            let mut tokens = items.clone().filter_map(|item| item.token.as_ref());
+            let first_token = match tokens.next() {
+                Some(first_token) => first_token,
+                // Shingles made of fillers only are skipped.
+                None => continue,
+            };
+            let last_token = tokens.next_back().unwrap_or(first_token);
+            let mut text = String::new();
+            for (i, item) in items.enumerate() {
+                if i > 0 {
+                    text.push_str(&self.filter.separator);
+                }
+                match &item.token {
+                    Some(token) => text.push_str(&token.text),
+                    None => text.push_str(&self.filter.filler_token),
+                }
+            }
+            self.output.push(Token {
+                offset_from: first_token.offset_from,
+                offset_to: last_token.offset_to,
+                position: first_item.position,
+                text,
+                position_length: shingle_size,
+                token_type: TokenType::Word,
+            });
+        }
+        self.window.pop_front();
+    }
+}
+
+impl<'a> TokenStream for ShingleFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        while self.output.is_empty() {
+            self.fill_window();
+            if self.window.is_empty() {
+                return false;
+            }
+            self.process_window();
+        }
+        self.output.advance()
+    }
+
+    fn token(&self) -> &Token {
+        self.output.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.output.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShingleFilter;
+    use crate::tokenizer::tests::{assert_token, token_stream_helper};
+    use crate::tokenizer::{SimpleTokenizer, StopWordFilter, TextAnalyzer};
+
+    fn shingle_analyzer(shingle_filter: ShingleFilter) -> TextAnalyzer {
+        TextAnalyzer::from(SimpleTokenizer)
+            .filter(StopWordFilter::remove(vec!["the".to_string()]))
+            .filter(shingle_filter)
+    }
+
+    #[test]
+    fn test_shingle_filter() {
+        let tokens =
+            token_stream_helper(&shingle_analyzer(ShingleFilter::default()), "new york city");
+        assert_eq!(tokens.len(), 5);
+        assert_token(&tokens[0], 0, "new", 0, 3);
+        assert_token(&tokens[1], 0, "new york", 0, 8);
+        assert_eq!(tokens[1].position_length, 2);
+        assert_token(&tokens[2], 1, "york", 4, 8);
+        assert_token(&tokens[3], 1, "york city", 4, 13);
+        assert_token(&tokens[4], 2, "city", 9, 13);
+        assert_eq!(tokens[4].position_length, 1);
+    }
+
+    #[test]
+    fn test_shingle_filter_sizes() {
+        let shingle_filter = ShingleFilter::new(2, 3)
+            .set_output_unigrams(false)
+            .set_separator("_");
+        let tokens = token_stream_helper(&shingle_analyzer(shingle_filter), "a b c d");
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(texts, vec!["a_b", "a_b_c", "b_c", "b_c_d", "c_d"]);
+        assert_token(&tokens[1], 0, "a_b_c", 0, 5);
+        assert_eq!(tokens[1].position_length, 3);
+        assert_token(&tokens[4], 2, "c_d", 4, 7);
+
+        let tokens = token_stream_helper(&shingle_analyzer(ShingleFilter::new(3, 3)), "a");
+        assert_eq!(tokens.len(), 1);
+        assert_token(&tokens[0], 0, "a", 0, 1);
+        assert!(token_stream_helper(
+            &shingle_analyzer(ShingleFilter::new(3, 3).set_output_unigrams(false)),
+            "a b"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_shingle_filter_position_gaps() {
+        let tokens = token_stream_helper(
+            &shingle_analyzer(ShingleFilter::default()),
+            "divide the sentence",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(texts, vec!["divide", "divide _", "_ sentence", "sentence"]);
+        assert_token(&tokens[1], 0, "divide _", 0, 6);
+        assert_token(&tokens[2], 1, "_ sentence", 11, 19);
+        assert_token(&tokens[3], 2, "sentence", 11, 19);
+
+        let shingle_filter = ShingleFilter::new(3, 3)
+            .set_output_unigrams(false)
+            .set_filler_token("");
+        let tokens = token_stream_helper(&shingle_analyzer(shingle_filter), "the the new the york");
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(texts, vec!["  new", " new ", "new  york"]);
+        assert_token(&tokens[2], 2, "new  york", 8, 20);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_shingle_filter_invalid_sizes() {
+        ShingleFilter::new(3, 2);
+    }
+}
@@ -0,0 +1,575 @@
+use super::{BoxTokenStream, Token, TokenStream, TokenType, Tokenizer};
+
+/// Tokenize the text following the word boundary rules of the
+/// [Unicode Standard Annex #29](https://unicode.org/reports/tr29/).
+///
+/// Contrary to the `SimpleTokenizer`, words containing apostrophes or
+/// periods (`don't`, `U.S.A`), numbers with separators (`3.14`, `1,000`),
+/// email addresses and URLs are emitted as single tokens.
+///
+/// Han ideographs and Hiragana characters are emitted as one token per character,
+/// while runs of southeast asian scripts that do not separate words with spaces
+/// (Thai, Lao, Khmer, Myanmar...) are emitted as a single token.
+///
+/// Each token is tagged with its [`TokenType`](./enum.TokenType.html).
+#[derive(Clone)]
+pub struct StandardTokenizer;
+
+pub struct StandardTokenStream<'a> {
+    text: &'a str,
+    offset: usize,
+    token: Token,
+}
+
+impl Tokenizer for StandardTokenizer {
+    fn token_stream<'a>(&self, text: &'a str) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(StandardTokenStream {
+            text,
+            offset: 0,
+            token: Token::default(),
+        })
+    }
+}
+
+impl<'a> TokenStream for StandardTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        self.token.text.clear();
+        while let Some(c) = self.text[self.offset..].chars().next() {
+            let offset_from = self.offset;
+            if !word_break(c).may_start_word() {
+                self.offset += c.len_utf8();
+                continue;
+            }
+            let (offset_to, token_type_opt) =
+                if let Some(offset_to) = match_url(self.text, offset_from) {
+                    (offset_to, Some(TokenType::Url))
+                } else if let Some(offset_to) = match_email(self.text, offset_from) {
+                    (offset_to, Some(TokenType::Email))
+                } else {
+                    word_end(self.text, offset_from)
+                };
+            self.offset = offset_to;
+            if let Some(token_type) = token_type_opt {
+                self.token.position = self.token.position.wrapping_add(1);
+                self.token.offset_from = offset_from;
+                self.token.offset_to = offset_to;
+                self.token.token_type = token_type;
+                self.token.text.push_str(&self.text[offset_from..offset_to]);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+/// Word break property of a character, as defined in UAX #29.
+///
+/// `Ideographic`, `Hiragana` and `SoutheastAsian` are not word break properties
+/// per se (the corresponding characters are `Other`), but they are needed
+/// to emit these characters as tokens.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum WordBreak {
+    ALetter,
+    HebrewLetter,
+    Numeric,
+    Katakana,
+    ExtendNumLet,
+    MidLetter,
+    MidNum,
+    MidNumLet,
+    SingleQuote,
+    DoubleQuote,
+    Extend,
+    Format,
+    Ideographic,
+    Hiragana,
+    SoutheastAsian,
+    Other,
+}
+
+impl WordBreak {
+    fn is_ahletter(self) -> bool {
+        self == WordBreak::ALetter || self == WordBreak::HebrewLetter
+    }
+
+    // Returns true iff `WB4` applies: the character is ignored
+    // and attached to the previous character.
+    fn is_ignorable(self) -> bool {
+        self == WordBreak::Extend || self == WordBreak::Format
+    }
+
+    fn may_start_word(self) -> bool {
+        matches!(
+            self,
+            WordBreak::ALetter
+                | WordBreak::HebrewLetter
+                | WordBreak::Numeric
+                | WordBreak::Katakana
+                | WordBreak::ExtendNumLet
+                | WordBreak::Ideographic
+                | WordBreak::Hiragana
+                | WordBreak::SoutheastAsian
+        )
+    }
+}
+
+fn word_break(c: char) -> WordBreak {
+    match c {
+        '"' => WordBreak::DoubleQuote,
+        '\'' => WordBreak::SingleQuote,
+        '.' | '\u{2018}' | '\u{2019}' | '\u{2024}' | '\u{FE52}' | '\u{FF07}' | '\u{FF0E}' => {
+            WordBreak::MidNumLet
+        }
+        ':' | '\u{00B7}' | '\u{0387}' | '\u{055F}' | '\u{05F4}' | '\u{2027}' | '\u{FE13}'
+        | '\u{FE55}' | '\u{FF1A}' => WordBreak::MidLetter,
+        ',' | ';' | '\u{037E}' | '\u{0589}' | '\u{060C}' | '\u{060D}' | '\u{066C}' | '\u{07F8}'
+        | '\u{2044}' | '\u{FE10}' | '\u{FE14}' | '\u{FE50}' | '\u{FE54}' | '\u{FF0C}'
+        | '\u{FF1B}' => WordBreak::MidNum,
+        '_'
+        | '\u{202F}'
+        | '\u{203F}'
+        | '\u{2040}'
+        | '\u{2054}'
+        | '\u{FE33}'
+        | '\u{FE34}'
+        | '\u{FE4D}'..='\u{FE4F}'
+        | '\u{FF3F}' => WordBreak::ExtendNumLet,
+        '\u{00AD}'
+        | '\u{0600}'..='\u{0605}'
+        | '\u{061C}'
+        | '\u{06DD}'
+        | '\u{070F}'
+        | '\u{200B}'..='\u{200F}'
+        | '\u{202A}'..='\u{202E}'
+        | '\u{2060}'..='\u{2064}'
+        | '\u{2066}'..='\u{206F}'
+        | '\u{FEFF}'
+        | '\u{FFF9}'..='\u{FFFB}' => WordBreak::Format,
+        '\u{0300}'..='\u{036F}'
+        | '\u{0483}'..='\u{0489}'
+        | '\u{0591}'..='\u{05BD}'
+        | '\u{05BF}'
+        | '\u{05C1}'..='\u{05C2}'
+        | '\u{05C4}'..='\u{05C5}'
+        | '\u{05C7}'
+        | '\u{0610}'..='\u{061A}'
+        | '\u{064B}'..='\u{065F}'
+        | '\u{0670}'
+        | '\u{06D6}'..='\u{06DC}'
+        | '\u{06DF}'..='\u{06E4}'
+        | '\u{06E7}'..='\u{06E8}'
+        | '\u{06EA}'..='\u{06ED}'
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{3099}'..='\u{309A}'
+        | '\u{FB1E}'
+        | '\u{FE00}'..='\u{FE0F}'
+        | '\u{FE20}'..='\u{FE2F}'
+        | '\u{1F3FB}'..='\u{1F3FF}'
+        | '\u{E0020}'..='\u{E007F}'
+        | '\u{E0100}'..='\u{E01EF}' => WordBreak::Extend,
+        '\u{3031}'..='\u{3035}'
+        | '\u{309B}'..='\u{309C}'
+        | '\u{30A0}'..='\u{30FA}'
+        | '\u{30FC}'..='\u{30FF}'
+        | '\u{31F0}'..='\u{31FF}'
+        | '\u{32D0}'..='\u{32FE}'
+        | '\u{3300}'..='\u{3357}'
+        | '\u{FF66}'..='\u{FF9D}' => WordBreak::Katakana,
+        '\u{3041}'..='\u{3096}' | '\u{309D}'..='\u{309F}' => WordBreak::Hiragana,
+        '\u{2E80}'..='\u{2FDF}'
+        | '\u{3005}'
+        | '\u{3007}'
+        | '\u{3021}'..='\u{3029}'
+        | '\u{3038}'..='\u{303B}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2FA1F}'
+        | '\u{30000}'..='\u{3134F}' => WordBreak::Ideographic,
+        '\u{05D0}'..='\u{05EA}'
+        | '\u{05EF}'..='\u{05F2}'
+        | '\u{FB1D}'
+        | '\u{FB1F}'..='\u{FB28}'
+        | '\u{FB2A}'..='\u{FB4F}' => WordBreak::HebrewLetter,
+        _ if c.is_numeric() => WordBreak::Numeric,
+        '\u{0E00}'..='\u{0EFF}'
+        | '\u{1000}'..='\u{109F}'
+        | '\u{1780}'..='\u{17FF}'
+        | '\u{1950}'..='\u{19DF}'
+        | '\u{19E0}'..='\u{19FF}'
+        | '\u{1A20}'..='\u{1AAF}'
+        | '\u{A9E0}'..='\u{A9FF}'
+        | '\u{AA60}'..='\u{AADF}' => WordBreak::SoutheastAsian,
+        _ if c.is_alphabetic() => WordBreak::ALetter,
+        _ => WordBreak::Other,
+    }
+}
+
+fn is_hangul(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{11FF}'
+            | '\u{3130}'..='\u{318F}'
+            | '\u{A960}'..='\u{A97F}'
+            | '\u{AC00}'..='\u{D7FF}'
+            | '\u{FFA0}'..='\u{FFDC}'
+    )
+}
+
+// Reads the character starting at `offset`, as well as the
+// `Extend` and `Format` characters following it (WB4).
+//
+// Returns the word break property of the character, the character
+// itself and the offset of the end of the sequence.
+fn next_char(text: &str, offset: usize) -> Option<(WordBreak, char, usize)> {
+    let mut chars = text[offset..].char_indices();
+    let (_, c) = chars.next()?;
+    let end = chars
+        .find(|&(_, next_c)| !word_break(next_c).is_ignorable())
+        .map(|(next_offset, _)| offset + next_offset)
+        .unwrap_or_else(|| text.len());
+    Some((word_break(c), c, end))
+}
+
+// Searches for the end of the word starting at `offset`,
+// by applying the rules WB5 to WB13b.
+//
+// Returns the end of the word, and its token type if
+// the word should be emitted as a token.
+fn word_end(text: &str, offset: usize) -> (usize, Option<TokenType>) {
+    let (first_word_break, first_char, mut end) =
+        next_char(text, offset).expect("word_end should only be called on a non-empty text");
+    let mut prev = first_word_break;
+    let mut main_char = first_char;
+    let mut main_word_break = first_word_break;
+    let mut has_letter = prev.is_ahletter();
+    while let Some((next, next_c, next_end)) = next_char(text, end) {
+        let join = match (prev, next) {
+            // WB5, WB8, WB9, WB10
+            (WordBreak::ALetter, WordBreak::ALetter)
+            | (WordBreak::ALetter, WordBreak::HebrewLetter)
+            | (WordBreak::HebrewLetter, WordBreak::ALetter)
+            | (WordBreak::HebrewLetter, WordBreak::HebrewLetter)
+            | (WordBreak::ALetter, WordBreak::Numeric)
+            | (WordBreak::HebrewLetter, WordBreak::Numeric)
+            | (WordBreak::Numeric, WordBreak::ALetter)
+            | (WordBreak::Numeric, WordBreak::HebrewLetter)
+            | (WordBreak::Numeric, WordBreak::Numeric) => true,
+            // WB13
+            (WordBreak::Katakana, WordBreak::Katakana) => true,
+            // WB13a
+            (WordBreak::ALetter, WordBreak::ExtendNumLet)
+            | (WordBreak::HebrewLetter, WordBreak::ExtendNumLet)
+            | (WordBreak::Numeric, WordBreak::ExtendNumLet)
+            | (WordBreak::Katakana, WordBreak::ExtendNumLet)
+            | (WordBreak::ExtendNumLet, WordBreak::ExtendNumLet) => true,
+            // WB13b
+            (WordBreak::ExtendNumLet, WordBreak::ALetter)
+            | (WordBreak::ExtendNumLet, WordBreak::HebrewLetter)
+            | (WordBreak::ExtendNumLet, WordBreak::Numeric)
+            | (WordBreak::ExtendNumLet, WordBreak::Katakana) => true,
+            // Southeast asian scripts are not segmented into words.
+            (WordBreak::SoutheastAsian, WordBreak::SoutheastAsian) => true,
+            // WB6, WB7, WB7a, WB7b, WB7c
+            (letter, WordBreak::MidLetter)
+            | (letter, WordBreak::MidNumLet)
+            | (letter, WordBreak::SingleQuote)
+            | (letter, WordBreak::DoubleQuote)
+                if letter.is_ahletter() =>
+            {
+                match next_char(text, next_end) {
+                    Some((after, after_c, after_end))
+                        if after.is_ahletter()
+                            && (next != WordBreak::DoubleQuote
+                                || (letter == WordBreak::HebrewLetter
+                                    && after == WordBreak::HebrewLetter)) =>
+                    {
+                        prev = after;
+                        end = after_end;
+                        if main_word_break == WordBreak::ExtendNumLet {
+                            main_char = after_c;
+                            main_word_break = after;
+                        }
+                        continue;
+                    }
+                    _ => letter == WordBreak::HebrewLetter && next == WordBreak::SingleQuote,
+                }
+            }
+            // WB11, WB12
+            (WordBreak::Numeric, WordBreak::MidNum)
+            | (WordBreak::Numeric, WordBreak::MidNumLet)
+            | (WordBreak::Numeric, WordBreak::SingleQuote) => match next_char(text, next_end) {
+                Some((WordBreak::Numeric, _, after_end)) => {
+                    end = after_end;
+                    continue;
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+        if !join {
+            break;
+        }
+        has_letter |= next.is_ahletter();
+        if main_word_break == WordBreak::ExtendNumLet {
+            main_char = next_c;
+            main_word_break = next;
+        }
+        prev = next;
+        end = next_end;
+    }
+    let token_type_opt = match main_word_break {
+        WordBreak::Katakana => Some(TokenType::Katakana),
+        WordBreak::Ideographic => Some(TokenType::Ideographic),
+        WordBreak::Hiragana => Some(TokenType::Hiragana),
+        WordBreak::SoutheastAsian => Some(TokenType::SoutheastAsian),
+        WordBreak::ALetter if is_hangul(main_char) => Some(TokenType::Hangul),
+        WordBreak::ALetter | WordBreak::HebrewLetter | WordBreak::Numeric => {
+            if has_letter {
+                Some(TokenType::Word)
+            } else {
+                Some(TokenType::Number)
+            }
+        }
+        _ => None,
+    };
+    (end, token_type_opt)
+}
+
+const URL_PREFIXES: [&str; 9] = [
+    "http://", "https://", "ftp://", "ftps://", "sftp://", "file://", "ws://", "wss://", "www.",
+];
+
+fn starts_with_ignore_ascii_case(text: &str, prefix: &str) -> bool {
+    text.len() >= prefix.len()
+        && text.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+}
+
+fn is_url_terminator(c: char) -> bool {
+    c.is_whitespace() || c.is_control() || "<>\"`{}|\\^".contains(c)
+}
+
+// Matches a URL starting at `offset`, and returns its end.
+//
+// URLs are recognized by their scheme (`http://`, `https://`, ...) or
+// by a `www.` prefix, and end at the first whitespace. Trailing
+// punctuation is not considered as part of the URL.
+fn match_url(text: &str, offset: usize) -> Option<usize> {
+    let rest = &text[offset..];
+    let prefix = URL_PREFIXES
+        .iter()
+        .find(|prefix| starts_with_ignore_ascii_case(rest, prefix))?;
+    let body = &rest[prefix.len()..];
+    if !body.chars().next()?.is_alphanumeric() {
+        return None;
+    }
+    let body_len = body.find(is_url_terminator).unwrap_or(body.len());
+    let mut url = &rest[..prefix.len() + body_len];
+    while let Some(last_char) = url.chars().last() {
+        let is_trailing_punctuation = match last_char {
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '*' | ']' => true,
+            ')' => url.matches('(').count() < url.matches(')').count(),
+            _ => false,
+        };
+        if !is_trailing_punctuation {
+            break;
+        }
+        url = &url[..url.len() - last_char.len_utf8()];
+    }
+    Some(offset + url.len())
+}
+
+// Maximum length of the local part of an email address, as defined in RFC 5321.
+const MAX_EMAIL_LOCAL_PART_LEN: usize = 64;
+
+fn is_email_local_part_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-/=?^_`{|}~.".contains(&b)
+}
+
+// Matches an email address starting at `offset`, and returns its end.
+fn match_email(text: &str, offset: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let local_part_len = bytes[offset..]
+        .iter()
+        .take(MAX_EMAIL_LOCAL_PART_LEN + 1)
+        .take_while(|&&b| is_email_local_part_byte(b))
+        .count();
+    let at = offset + local_part_len;
+    if local_part_len == 0
+        || local_part_len > MAX_EMAIL_LOCAL_PART_LEN
+        || bytes.get(at) != Some(&b'@')
+        || bytes[at - 1] == b'.'
+    {
+        return None;
+    }
+    match_domain(bytes, at + 1)
+}
+
+// Matches a domain name made of at least two labels starting at `offset`,
+// and returns its end. The last label has to be alphabetic.
+fn match_domain(bytes: &[u8], offset: usize) -> Option<usize> {
+    let mut domain_end = None;
+    let mut num_labels = 0;
+    let mut label_start = offset;
+    loop {
+        let label_len = bytes[label_start..]
+            .iter()
+            .take_while(|&&b| b.is_ascii_alphanumeric() || b == b'-')
+            .count();
+        let label = &bytes[label_start..label_start + label_len];
+        if label.is_empty() || label[0] == b'-' || label[label_len - 1] == b'-' {
+            break;
+        }
+        num_labels += 1;
+        let label_end = label_start + label_len;
+        if num_labels >= 2 && label_len >= 2 && label.iter().all(u8::is_ascii_alphabetic) {
+            domain_end = Some(label_end);
+        }
+        if bytes.get(label_end) != Some(&b'.') {
+            break;
+        }
+        label_start = label_end + 1;
+    }
+    domain_end
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::tests::{assert_token, token_stream_helper};
+    use crate::tokenizer::{StandardTokenizer, TextAnalyzer, TokenType};
+
+    fn token_texts(text: &str) -> Vec<String> {
+        token_stream_helper(&TextAnalyzer::from(StandardTokenizer), text)
+            .into_iter()
+            .map(|token| token.text)
+            .collect()
+    }
+
+    fn token_types(text: &str) -> Vec<TokenType> {
+        token_stream_helper(&TextAnalyzer::from(StandardTokenizer), text)
+            .into_iter()
+            .map(|token| token.token_type)
+            .collect()
+    }
+
+    #[test]
+    fn test_standard_tokenizer() {
+        let tokens = token_stream_helper(
+            &TextAnalyzer::from(StandardTokenizer),
+            "Hello, happy tax payer!",
+        );
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "Hello", 0, 5);
+        assert_token(&tokens[1], 1, "happy", 7, 12);
+        assert_token(&tokens[2], 2, "tax", 13, 16);
+        assert_token(&tokens[3], 3, "payer", 17, 22);
+        assert!(token_stream_helper(&TextAnalyzer::from(StandardTokenizer), "").is_empty());
+        assert!(
+            token_stream_helper(&TextAnalyzer::from(StandardTokenizer), " ,;!? -- ").is_empty()
+        );
+    }
+
+    #[test]
+    fn test_standard_tokenizer_word_boundaries() {
+        assert_eq!(
+            token_texts("Don't stop, U.S.A. rocks: l'amour"),
+            vec!["Don't", "stop", "U.S.A", "rocks", "l'amour"]
+        );
+        assert_eq!(
+            token_texts("'quoted' \"words\" foo_bar __init__"),
+            vec!["quoted", "words", "foo_bar", "__init__"]
+        );
+        assert_eq!(
+            token_texts("3.14 1,000,000 2nd v1.2 42."),
+            vec!["3.14", "1,000,000", "2nd", "v1.2", "42"]
+        );
+        assert_eq!(
+            token_types("3.14 1,000,000 2nd v1.2"),
+            vec![
+                TokenType::Number,
+                TokenType::Number,
+                TokenType::Word,
+                TokenType::Word
+            ]
+        );
+        // combining marks are kept within words
+        assert_eq!(token_texts("cafe\u{301} ok"), vec!["cafe\u{301}", "ok"]);
+        // Hebrew letters accept a trailing geresh
+        assert_eq!(token_texts("צה'ל"), vec!["צה'ל"]);
+    }
+
+    #[test]
+    fn test_standard_tokenizer_email_and_url() {
+        let tokens = token_stream_helper(&TextAnalyzer::from(StandardTokenizer), "Mail john.doe+tag@mail.example.com, or see https://tantivy.dev/a_(b)?c=d. (www.example.com)");
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Mail",
+                "john.doe+tag@mail.example.com",
+                "or",
+                "see",
+                "https://tantivy.dev/a_(b)?c=d",
+                "www.example.com"
+            ]
+        );
+        assert_eq!(tokens[1].token_type, TokenType::Email);
+        assert_eq!(tokens[4].token_type, TokenType::Url);
+        assert_eq!(tokens[5].token_type, TokenType::Url);
+        assert_token(&tokens[5], 5, "www.example.com", 75, 90);
+        assert_eq!(
+            token_texts("foo@bar @baz.com"),
+            vec!["foo", "bar", "baz.com"]
+        );
+        assert_eq!(token_texts("http:// x"), vec!["http", "x"]);
+    }
+
+    #[test]
+    fn test_standard_tokenizer_cjk_and_southeast_asian() {
+        let tokens = token_stream_helper(
+            &TextAnalyzer::from(StandardTokenizer),
+            "東京タワーに行く 서울에서 ภาษาไทย ok",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "東",
+                "京",
+                "タワー",
+                "に",
+                "行",
+                "く",
+                "서울에서",
+                "ภาษาไทย",
+                "ok"
+            ]
+        );
+        let token_types: Vec<TokenType> = tokens.iter().map(|token| token.token_type).collect();
+        assert_eq!(
+            token_types,
+            vec![
+                TokenType::Ideographic,
+                TokenType::Ideographic,
+                TokenType::Katakana,
+                TokenType::Hiragana,
+                TokenType::Ideographic,
+                TokenType::Hiragana,
+                TokenType::Hangul,
+                TokenType::SoutheastAsian,
+                TokenType::Word
+            ]
+        );
+        assert_token(&tokens[2], 2, "タワー", 6, 15);
+    }
+}
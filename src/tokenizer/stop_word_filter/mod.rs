@@ -10,11 +10,14 @@
 //! assert_eq!(stream.next().unwrap().text, "crafty");
 //! assert!(stream.next().is_none());
 //! ```
-use super::{Token, TokenFilter, TokenStream};
+mod stopwords;
+
+use super::{Language, Token, TokenFilter, TokenStream};
 use crate::tokenizer::BoxTokenStream;
 use fnv::FnvHasher;
 use std::collections::HashSet;
 use std::hash::BuildHasherDefault;
+use std::sync::Arc;
 
 // configure our hashers for SPEED
 type StopWordHasher = BuildHasherDefault<FnvHasher>;
@@ -23,7 +26,7 @@ type StopWordHashSet = HashSet<String, StopWordHasher>;
 /// `TokenFilter` that removes stop words from a token stream
 #[derive(Clone)]
 pub struct StopWordFilter {
-    words: StopWordHashSet,
+    words: Arc<StopWordHashSet>,
 }
 
 impl StopWordFilter {
@@ -35,7 +38,23 @@ impl StopWordFilter {
             set.insert(word);
         }
 
-        StopWordFilter { words: set }
+        StopWordFilter {
+            words: Arc::new(set),
+        }
+    }
+
+    /// Creates a `StopWordFilter` removing the stop words of the given language.
+    ///
+    /// The stop words are in their case folded form (e.g. `dass` rather than `daß`),
+    /// so the filter is meant to be applied after the
+    /// [`UnicodeNormalizationFilter`](./struct.UnicodeNormalizationFilter.html).
+    pub fn new(language: Language) -> StopWordFilter {
+        StopWordFilter::remove(
+            stop_words(language)
+                .iter()
+                .map(|&word| word.to_string())
+                .collect(),
+        )
     }
 
     fn english() -> StopWordFilter {
@@ -49,8 +68,32 @@ impl StopWordFilter {
     }
 }
 
+/// Returns the built-in stop words of the given language.
+pub(crate) fn stop_words(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::Arabic => stopwords::ARABIC,
+        Language::Danish => stopwords::DANISH,
+        Language::Dutch => stopwords::DUTCH,
+        Language::English => stopwords::ENGLISH,
+        Language::Finnish => stopwords::FINNISH,
+        Language::French => stopwords::FRENCH,
+        Language::German => stopwords::GERMAN,
+        Language::Greek => stopwords::GREEK,
+        Language::Hungarian => stopwords::HUNGARIAN,
+        Language::Italian => stopwords::ITALIAN,
+        Language::Norwegian => stopwords::NORWEGIAN,
+        Language::Portuguese => stopwords::PORTUGUESE,
+        Language::Romanian => stopwords::ROMANIAN,
+        Language::Russian => stopwords::RUSSIAN,
+        Language::Spanish => stopwords::SPANISH,
+        Language::Swedish => stopwords::SWEDISH,
+        Language::Tamil => stopwords::TAMIL,
+        Language::Turkish => stopwords::TURKISH,
+    }
+}
+
 pub struct StopWordFilterStream<'a> {
-    words: StopWordHashSet,
+    words: Arc<StopWordHashSet>,
     tail: BoxTokenStream<'a>,
 }
 
@@ -0,0 +1,502 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let synonym_map = SynonymMap::from_solr("ny => new york", true).unwrap();
+//! let tokenizer = TextAnalyzer::from(SimpleTokenizer)
+//!   .filter(LowerCaser)
+//!   .filter(SynonymFilter::new(synonym_map));
+//!
+//! let mut stream = tokenizer.token_stream("NY city");
+//! let token = stream.next().unwrap();
+//! assert_eq!((token.text.as_str(), token.position), ("new", 0));
+//! let token = stream.next().unwrap();
+//! assert_eq!((token.text.as_str(), token.position), ("york", 1));
+//! let token = stream.next().unwrap();
+//! assert_eq!((token.text.as_str(), token.position), ("city", 2));
+//! assert!(stream.next().is_none());
+//! ```
+use super::{BoxTokenStream, Token, TokenBuffer, TokenFilter, TokenStream, TokenType};
+use crate::TantivyError;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+/// A `SynonymMap` stores the synonym rules used by the
+/// [`SynonymFilter`](./struct.SynonymFilter.html).
+///
+/// A rule maps a sequence of words to the sequences of words that
+/// should be emitted in its place. Words are matched against the text of the
+/// tokens, so the rules should be written in the form produced by the
+/// preceding filters (e.g. lowercased).
+#[derive(Clone, Debug, Default)]
+pub struct SynonymMap {
+    // The words of the inputs and outputs are joined by a space.
+    rules: HashMap<String, Vec<Vec<String>>>,
+    max_input_len: usize,
+}
+
+fn split_words(text: &str) -> Vec<String> {
+    text.split_whitespace().map(ToString::to_string).collect()
+}
+
+impl SynonymMap {
+    /// Creates an empty `SynonymMap`.
+    pub fn new() -> SynonymMap {
+        SynonymMap::default()
+    }
+
+    /// Adds a rule replacing `input` by each of the `outputs`.
+    ///
+    /// The input is kept in the token stream only if it is part of the outputs.
+    /// Adding several rules for the same input merges their outputs.
+    pub fn add_rule(&mut self, input: &str, outputs: &[&str]) {
+        let input_words = split_words(input);
+        if input_words.is_empty() {
+            return;
+        }
+        self.max_input_len = self.max_input_len.max(input_words.len());
+        let rule_outputs = self.rules.entry(input_words.join(" ")).or_default();
+        for output in outputs {
+            let output_words = split_words(output);
+            if !output_words.is_empty() && !rule_outputs.contains(&output_words) {
+                rule_outputs.push(output_words);
+            }
+        }
+    }
+
+    /// Adds a list of equivalent synonyms.
+    ///
+    /// If `expand` is true, each synonym is replaced by all of the synonyms.
+    /// Otherwise, each synonym is replaced by the first synonym of the list.
+    pub fn add_synonyms(&mut self, synonyms: &[&str], expand: bool) {
+        let outputs = if expand { synonyms } else { &synonyms[..1] };
+        for synonym in synonyms {
+            self.add_rule(synonym, outputs);
+        }
+    }
+
+    /// Parses synonym rules in the format used by Solr.
+    ///
+    /// Each line is either:
+    /// - a comma-separated list of equivalent synonyms (`ny, new york, big apple`),
+    ///   added as with [`add_synonyms`](#method.add_synonyms).
+    /// - an explicit mapping (`i-pod, i pod => ipod`), replacing each of the
+    ///   synonyms on the left by all of the synonyms on the right.
+    ///
+    /// Lines starting with `#` are comments. Commas, `=` and backslashes
+    /// can be escaped with a backslash.
+    pub fn from_solr(rules: &str, expand: bool) -> crate::Result<SynonymMap> {
+        let mut synonym_map = SynonymMap::new();
+        for (line_num, line) in rules.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid_rule = || {
+                TantivyError::InvalidArgument(format!(
+                    "Invalid synonym rule on line {}: {:?}",
+                    line_num + 1,
+                    line
+                ))
+            };
+            let sides = split_unescaped(line, "=>");
+            let synonym_lists = sides
+                .iter()
+                .map(|side| split_unescaped(side, ","))
+                .map(|synonyms| {
+                    synonyms
+                        .iter()
+                        .map(|synonym| unescape(synonym.trim()))
+                        .filter(|synonym| !synonym.is_empty())
+                        .collect::<Vec<String>>()
+                })
+                .collect::<Vec<Vec<String>>>();
+            if synonym_lists.iter().any(Vec::is_empty) {
+                return Err(invalid_rule());
+            }
+            match &synonym_lists[..] {
+                [synonyms] => {
+                    let synonyms: Vec<&str> = synonyms.iter().map(String::as_str).collect();
+                    synonym_map.add_synonyms(&synonyms, expand);
+                }
+                [inputs, outputs] => {
+                    let outputs: Vec<&str> = outputs.iter().map(String::as_str).collect();
+                    for input in inputs {
+                        synonym_map.add_rule(input, &outputs);
+                    }
+                }
+                _ => return Err(invalid_rule()),
+            }
+        }
+        Ok(synonym_map)
+    }
+
+    /// Parses synonym rules in the prolog format of WordNet (`wn_s.pl`).
+    ///
+    /// Each line describes a word of a synset (`s(100001740,1,'entity',n,1,11).`),
+    /// and the words of a same synset are added as equivalent synonyms.
+    /// The lines of a same synset must be consecutive.
+    pub fn from_wordnet(rules: &str, expand: bool) -> crate::Result<SynonymMap> {
+        let mut synonym_map = SynonymMap::new();
+        let mut synset_id: Option<&str> = None;
+        let mut synset: Vec<String> = Vec::new();
+        let mut add_synset = |synset: &mut Vec<String>| {
+            let synonyms: Vec<&str> = synset.iter().map(String::as_str).collect();
+            if !synonyms.is_empty() {
+                synonym_map.add_synonyms(&synonyms, expand);
+            }
+            synset.clear();
+        };
+        for (line_num, line) in rules.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (id, word) = parse_wordnet_line(line).ok_or_else(|| {
+                TantivyError::InvalidArgument(format!(
+                    "Invalid WordNet synonym on line {}: {:?}",
+                    line_num + 1,
+                    line
+                ))
+            })?;
+            if synset_id != Some(id) {
+                add_synset(&mut synset);
+                synset_id = Some(id);
+            }
+            synset.push(word);
+        }
+        add_synset(&mut synset);
+        Ok(synonym_map)
+    }
+
+    fn outputs(&self, input: &str) -> Option<&[Vec<String>]> {
+        self.rules.get(input).map(Vec::as_slice)
+    }
+}
+
+// Splits `text` on the occurrences of `separator` that are not escaped by a backslash.
+fn split_unescaped<'a>(text: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (offset, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if text[offset..].starts_with(separator) && offset >= start {
+            parts.push(&text[start..offset]);
+            start = offset + separator.len();
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut escaped = false;
+    for c in text.chars() {
+        if c == '\\' && !escaped {
+            escaped = true;
+        } else {
+            unescaped.push(c);
+            escaped = false;
+        }
+    }
+    unescaped
+}
+
+// Parses a line such as `s(100001740,1,'entity',n,1,11).`,
+// and returns the synset id and the word.
+fn parse_wordnet_line(line: &str) -> Option<(&str, String)> {
+    let args = line.strip_prefix("s(")?;
+    let (synset_id, args) = args.split_at(args.find(',')?);
+    let args = &args[1..];
+    let args = &args[args.find(",'")? + 2..];
+    let mut word = String::new();
+    let mut chars = args.chars();
+    loop {
+        match chars.next()? {
+            '\'' => {
+                // Quotes are escaped by doubling them.
+                if chars.as_str().starts_with('\'') {
+                    chars.next();
+                    word.push('\'');
+                } else {
+                    break;
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if synset_id.is_empty() || word.trim().is_empty() {
+        return None;
+    }
+    Some((synset_id, word))
+}
+
+/// `SynonymFilter` injects synonyms in the token stream, given a
+/// [`SynonymMap`](./struct.SynonymMap.html).
+///
+/// Synonyms are emitted at the same position as the words they replace.
+/// When a synonym and the words it replaces do not have the same number of words
+/// (e.g. `ny => new york`), the last word of the shortest one spans several positions,
+/// as recorded by its `position_length`, and the following tokens are shifted
+/// so that the longest one fits. The resulting tokens form a graph of positions,
+/// each path of which is one of the variants of the text.
+///
+/// The [`QueryParser`](../query/struct.QueryParser.html) searches each of the
+/// paths of the graph when the filter is used at query time.
+/// Multi-word inputs are only matched on consecutive tokens, and the longest
+/// input matching at a given position is used.
+#[derive(Clone)]
+pub struct SynonymFilter {
+    synonym_map: Arc<SynonymMap>,
+}
+
+impl SynonymFilter {
+    /// Creates a `SynonymFilter` given a `SynonymMap`.
+    pub fn new(synonym_map: SynonymMap) -> SynonymFilter {
+        SynonymFilter {
+            synonym_map: Arc::new(synonym_map),
+        }
+    }
+}
+
+impl TokenFilter for SynonymFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(SynonymFilterStream {
+            tail: token_stream,
+            synonym_map: self.synonym_map.clone(),
+            input: VecDeque::new(),
+            output: TokenBuffer::default(),
+            tail_position: usize::MAX,
+            position: usize::MAX,
+            key: String::new(),
+        })
+    }
+}
+
+pub struct SynonymFilterStream<'a> {
+    tail: BoxTokenStream<'a>,
+    synonym_map: Arc<SynonymMap>,
+    // Tokens read from the tail, but not processed yet.
+    input: VecDeque<Token>,
+    output: TokenBuffer,
+    // Position of the last token processed from the tail.
+    tail_position: usize,
+    // Last position of the tokens produced.
+    position: usize,
+    // Buffer used to look up the rules.
+    key: String,
+}
+
+impl<'a> SynonymFilterStream<'a> {
+    // Reads tokens from the tail, until there are `len` tokens in the input.
+    fn fill_input(&mut self, len: usize) {
+        while self.input.len() < len && self.tail.advance() {
+            self.input.push_back(self.tail.token().clone());
+        }
+    }
+
+    // Returns the number of tokens of the longest input matching a rule,
+    // and the outputs of this rule.
+    fn longest_match(&mut self) -> Option<(usize, Vec<Vec<String>>)> {
+        self.key.clear();
+        let mut longest_match = None;
+        for (i, token) in self.input.iter().enumerate() {
+            if i > 0 {
+                if token.position != self.input[i - 1].position.wrapping_add(1) {
+                    break;
+                }
+                self.key.push(' ');
+            }
+            self.key.push_str(&token.text);
+            if let Some(outputs) = self.synonym_map.outputs(&self.key) {
+                longest_match = Some((i + 1, outputs));
+            }
+        }
+        longest_match.map(|(len, outputs)| (len, outputs.to_vec()))
+    }
+
+    fn process_input(&mut self) {
+        self.fill_input(self.synonym_map.max_input_len.max(1));
+        let (input_len, outputs) = match self.longest_match() {
+            Some(longest_match) => longest_match,
+            None => {
+                let mut token = self.input.pop_front().unwrap();
+                let position_increment = token.position.wrapping_sub(self.tail_position);
+                self.tail_position = token.position;
+                self.position = self.position.wrapping_add(position_increment);
+                token.position = self.position;
+                self.output.push(token);
+                return;
+            }
+        };
+        let input_tokens: Vec<Token> = self.input.drain(..input_len).collect();
+        let position_increment = input_tokens[0].position.wrapping_sub(self.tail_position);
+        let position = self.position.wrapping_add(position_increment);
+        self.tail_position = input_tokens[input_len - 1].position;
+        // The input is only emitted if it is one of the outputs: the graph spans
+        // the positions of the longest output, so that the positions of the
+        // following tokens do not depend on a replaced input.
+        let graph_len = outputs.iter().map(Vec::len).max().unwrap_or(input_len);
+        let offset_from = input_tokens[0].offset_from;
+        let offset_to = input_tokens[input_len - 1].offset_to;
+        let mut tokens = Vec::new();
+        for output in &outputs {
+            let is_input = output.len() == input_len
+                && output
+                    .iter()
+                    .zip(&input_tokens)
+                    .all(|(word, token)| *word == token.text);
+            if is_input {
+                for (i, input_token) in input_tokens.iter().enumerate() {
+                    let mut token = input_token.clone();
+                    token.position = position + i;
+                    tokens.push(token);
+                }
+            } else {
+                for (i, word) in output.iter().enumerate() {
+                    tokens.push(Token {
+                        offset_from,
+                        offset_to,
+                        position: position + i,
+                        text: word.clone(),
+                        position_length: 1,
+                        token_type: TokenType::Word,
+                    });
+                }
+            }
+            // The last word spans the positions remaining in the graph.
+            tokens.last_mut().unwrap().position_length = graph_len - output.len() + 1;
+        }
+        tokens.sort_by_key(|token| token.position);
+        self.output.extend(tokens);
+        self.position = position + graph_len - 1;
+    }
+}
+
+impl<'a> TokenStream for SynonymFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        if self.output.is_empty() {
+            self.fill_input(1);
+            if self.input.is_empty() {
+                return false;
+            }
+            self.process_input();
+        }
+        self.output.advance()
+    }
+
+    fn token(&self) -> &Token {
+        self.output.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.output.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SynonymFilter, SynonymMap};
+    use crate::tokenizer::tests::{assert_token, token_stream_helper};
+    use crate::tokenizer::{LowerCaser, SimpleTokenizer, TextAnalyzer};
+
+    fn synonym_analyzer(synonym_map: &SynonymMap) -> TextAnalyzer {
+        TextAnalyzer::from(SimpleTokenizer)
+            .filter(LowerCaser)
+            .filter(SynonymFilter::new(synonym_map.clone()))
+    }
+
+    #[test]
+    fn test_synonym_filter_single_word() {
+        let synonym_map = SynonymMap::from_solr("quick, fast", true).unwrap();
+        let tokens = token_stream_helper(&synonym_analyzer(&synonym_map), "The quick fox");
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "the", 0, 3);
+        assert_token(&tokens[1], 1, "quick", 4, 9);
+        assert_token(&tokens[2], 1, "fast", 4, 9);
+        assert_token(&tokens[3], 2, "fox", 10, 13);
+        assert!(tokens.iter().all(|token| token.position_length == 1));
+    }
+
+    #[test]
+    fn test_synonym_filter_multi_words() {
+        let synonym_map = SynonymMap::from_solr("ny, new york", true).unwrap();
+        let tokens = token_stream_helper(&synonym_analyzer(&synonym_map), "ny city");
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "ny", 0, 2);
+        assert_eq!(tokens[0].position_length, 2);
+        assert_token(&tokens[1], 0, "new", 0, 2);
+        assert_eq!(tokens[1].position_length, 1);
+        assert_token(&tokens[2], 1, "york", 0, 2);
+        assert_eq!(tokens[2].position_length, 1);
+        assert_token(&tokens[3], 2, "city", 3, 7);
+
+        let tokens = token_stream_helper(&synonym_analyzer(&synonym_map), "new york city");
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "ny", 0, 8);
+        assert_eq!(tokens[0].position_length, 2);
+        assert_token(&tokens[1], 0, "new", 0, 3);
+        assert_token(&tokens[2], 1, "york", 4, 8);
+        assert_token(&tokens[3], 2, "city", 9, 13);
+
+        // `new` alone is not a synonym.
+        let tokens = token_stream_helper(&synonym_analyzer(&synonym_map), "new city");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[1], 1, "city", 4, 8);
+    }
+
+    #[test]
+    fn test_synonym_filter_no_expand() {
+        let synonym_map = SynonymMap::from_solr("ny, new york\nfoo => bar, baz", false).unwrap();
+        let tokens = token_stream_helper(&synonym_analyzer(&synonym_map), "new york foo");
+        assert_eq!(tokens.len(), 3);
+        // `new york` is replaced by `ny`, which spans a single position.
+        assert_token(&tokens[0], 0, "ny", 0, 8);
+        assert_eq!(tokens[0].position_length, 1);
+        assert_token(&tokens[1], 1, "bar", 9, 12);
+        assert_token(&tokens[2], 1, "baz", 9, 12);
+        let tokens = token_stream_helper(&synonym_analyzer(&synonym_map), "ny foo");
+        assert_token(&tokens[0], 0, "ny", 0, 2);
+        assert_token(&tokens[1], 1, "bar", 3, 6);
+    }
+
+    #[test]
+    fn test_synonym_map_from_solr() {
+        let rules = "# comment\n\n  a\\,b, c  \nd, e => f\ng => h => i";
+        assert!(SynonymMap::from_solr(rules, true).is_err());
+        let synonym_map = SynonymMap::from_solr(&rules[..rules.len() - 12], true).unwrap();
+        assert_eq!(
+            synonym_map.outputs("a,b").unwrap(),
+            &[vec!["a,b".to_string()], vec!["c".to_string()]]
+        );
+        assert_eq!(synonym_map.outputs("e").unwrap(), &[vec!["f".to_string()]]);
+        assert!(synonym_map.outputs("f").is_none());
+        assert!(SynonymMap::from_solr("a, b =>", true).is_err());
+    }
+
+    #[test]
+    fn test_synonym_map_from_wordnet() {
+        let rules = "s(100001740,1,'entity',n,1,11).\n\
+                     s(100002137,1,'abstraction',n,6,0).\n\
+                     s(100002137,2,'abstract entity',n,1,0).\n\
+                     s(100003000,1,'o''clock',n,1,0).";
+        let synonym_map = SynonymMap::from_wordnet(rules, true).unwrap();
+        assert_eq!(
+            synonym_map.outputs("abstract entity").unwrap(),
+            &[
+                vec!["abstraction".to_string()],
+                vec!["abstract".to_string(), "entity".to_string()]
+            ]
+        );
+        assert_eq!(
+            synonym_map.outputs("o'clock").unwrap(),
+            &[vec!["o'clock".to_string()]]
+        );
+        assert!(SynonymMap::from_wordnet("s(1,1,entity).", true).is_err());
+    }
+}
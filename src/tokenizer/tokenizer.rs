@@ -1,10 +1,47 @@
-use crate::tokenizer::TokenStreamChain;
+use crate::tokenizer::{
+    BoxCharFilter, OffsetCorrections, PreTokenizedStream, PreTokenizedString, TokenStreamChain,
+};
 use serde::{Deserialize, Serialize};
 /// The tokenizer module contains all of the tools used to process
 /// text in `tantivy`.
 use std::borrow::{Borrow, BorrowMut};
 use std::ops::{Deref, DerefMut};
 
+/// Lexical type of a token, as detected by the tokenizer.
+///
+/// Most tokenizers only emit `Word` tokens. The
+/// [`StandardTokenizer`](./struct.StandardTokenizer.html) detects the other types.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenType {
+    /// Alphanumeric word.
+    #[default]
+    Word,
+    /// Number, possibly including separators (e.g. `3.14` or `1,000`).
+    Number,
+    /// Email address.
+    Email,
+    /// URL.
+    Url,
+    /// Single Han ideograph.
+    Ideographic,
+    /// Single Hiragana character.
+    Hiragana,
+    /// Katakana word.
+    Katakana,
+    /// Hangul word.
+    Hangul,
+    /// Run of characters of a southeast asian script (Thai, Lao, Khmer, Myanmar...)
+    /// that does not separate words with spaces.
+    SoutheastAsian,
+}
+
+impl TokenType {
+    fn is_word(&self) -> bool {
+        *self == TokenType::Word
+    }
+}
+
 /// Token
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Token {
@@ -20,7 +57,13 @@ pub struct Token {
     /// Actual text content of the token.
     pub text: String,
     /// Is the length expressed in term of number of original tokens.
+    ///
+    /// It is greater than 1 for tokens spanning several positions of a
+    /// token graph, such as multi-word synonyms (see `SynonymFilter`).
     pub position_length: usize,
+    /// Lexical type of the token.
+    #[serde(default, skip_serializing_if = "TokenType::is_word")]
+    pub token_type: TokenType,
 }
 
 impl Default for Token {
@@ -31,6 +74,7 @@ impl Default for Token {
             position: usize::max_value(),
             text: String::with_capacity(200),
             position_length: 1,
+            token_type: TokenType::Word,
         }
     }
 }
@@ -38,7 +82,9 @@ impl Default for Token {
 /// `TextAnalyzer` tokenizes an input text into tokens and modifies the resulting `TokenStream`.
 ///
 /// It simply wraps a `Tokenizer` and a list of `TokenFilter` that are applied sequentially.
+/// `CharFilter`s can also be added to rewrite the text before it gets tokenized.
 pub struct TextAnalyzer {
+    char_filters: Vec<BoxCharFilter>,
     tokenizer: Box<dyn Tokenizer>,
     token_filters: Vec<BoxTokenFilter>,
 }
@@ -56,11 +102,34 @@ impl TextAnalyzer {
     /// `TextAnalyzer::from(tokenizer)`.
     pub fn new<T: Tokenizer>(tokenizer: T, token_filters: Vec<BoxTokenFilter>) -> TextAnalyzer {
         TextAnalyzer {
+            char_filters: Vec::new(),
             tokenizer: Box::new(tokenizer),
             token_filters,
         }
     }
 
+    /// Appends a char filter to the current tokenizer.
+    ///
+    /// Char filters rewrite the text before it gets tokenized. The offsets of the
+    /// resulting tokens still point to the original text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tantivy::tokenizer::*;
+    ///
+    /// let tokenizer = TextAnalyzer::from(SimpleTokenizer)
+    ///     .char_filter(HtmlStripCharFilter)
+    ///     .filter(LowerCaser);
+    /// let mut token_stream = tokenizer.token_stream("<b>Hello</b> world");
+    /// let token = token_stream.next().unwrap();
+    /// assert_eq!((token.text.as_str(), token.offset_from, token.offset_to), ("hello", 3, 8));
+    /// ```
+    pub fn char_filter<F: Into<BoxCharFilter>>(mut self, char_filter: F) -> Self {
+        self.char_filters.push(char_filter.into());
+        self
+    }
+
     /// Appends a token filter to the current tokenizer.
     ///
     /// The method consumes the current `TokenStream` and returns a
@@ -109,7 +178,11 @@ impl TextAnalyzer {
 
     /// Creates a token stream for a given `str`.
     pub fn token_stream<'a>(&self, text: &'a str) -> BoxTokenStream<'a> {
-        let mut token_stream = self.tokenizer.token_stream(text);
+        let mut token_stream = if self.char_filters.is_empty() {
+            self.tokenizer.token_stream(text)
+        } else {
+            BoxTokenStream::from(self.char_filtered_token_stream(text))
+        };
         for token_filter in &self.token_filters {
             token_stream = token_filter.transform(token_stream);
         }
@@ -117,9 +190,45 @@ impl TextAnalyzer {
     }
 }
 
+impl TextAnalyzer {
+    // Applies the char filters, and tokenizes the resulting text.
+    //
+    // The token stream can't borrow the rewritten text, so
+    // the tokens are collected upfront.
+    fn char_filtered_token_stream(&self, text: &str) -> PreTokenizedStream {
+        let mut filtered_text = text.to_string();
+        let mut offset_corrections = Vec::with_capacity(self.char_filters.len());
+        for char_filter in &self.char_filters {
+            let mut char_filter_corrections = OffsetCorrections::default();
+            filtered_text = char_filter.filter(&filtered_text, &mut char_filter_corrections);
+            offset_corrections.push(char_filter_corrections);
+        }
+        let mut tokens = Vec::new();
+        self.tokenizer
+            .token_stream(&filtered_text)
+            .process(&mut |token| {
+                let mut token = token.clone();
+                for char_filter_corrections in offset_corrections.iter().rev() {
+                    token.offset_from = char_filter_corrections.correct_start(token.offset_from);
+                    token.offset_to = char_filter_corrections.correct_end(token.offset_to);
+                }
+                tokens.push(token);
+            });
+        PreTokenizedStream::from(PreTokenizedString {
+            text: text.to_string(),
+            tokens,
+        })
+    }
+}
+
 impl Clone for TextAnalyzer {
     fn clone(&self) -> Self {
         TextAnalyzer {
+            char_filters: self
+                .char_filters
+                .iter()
+                .map(|char_filter| char_filter.box_clone())
+                .collect(),
             tokenizer: self.tokenizer.box_clone(),
             token_filters: self
                 .token_filters
@@ -312,7 +421,7 @@ impl<T: TokenFilter + Clone> TokenFilterClone for T {
 
 #[cfg(test)]
 mod test {
-    use super::Token;
+    use super::{Token, TokenType};
 
     #[test]
     fn clone() {
@@ -322,6 +431,7 @@ mod test {
             offset_to: 3,
             text: "abc".to_string(),
             position_length: 1,
+            token_type: TokenType::Word,
         };
         let t2 = t1.clone();
 
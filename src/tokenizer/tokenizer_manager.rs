@@ -1,10 +1,15 @@
 use crate::tokenizer::stemmer::Language;
 use crate::tokenizer::tokenizer::TextAnalyzer;
+use crate::tokenizer::CjkBigramFilter;
+use crate::tokenizer::ElisionFilter;
 use crate::tokenizer::LowerCaser;
 use crate::tokenizer::RawTokenizer;
 use crate::tokenizer::RemoveLongFilter;
 use crate::tokenizer::SimpleTokenizer;
+use crate::tokenizer::StandardTokenizer;
 use crate::tokenizer::Stemmer;
+use crate::tokenizer::StopWordFilter;
+use crate::tokenizer::UnicodeNormalizationFilter;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
@@ -17,9 +22,17 @@ use std::sync::{Arc, RwLock};
 ///  * `default` : Chops the text on according to whitespace and
 ///  punctuation, removes tokens that are too long, and lowercases
 ///  tokens
+///  * `standard` : Like `default`, but chops the text following the Unicode
+///    word boundary rules. Words such as `don't`, numbers such as `3.14`,
+///    email addresses and URLs are kept as single tokens.
+///  * `cjk` (or `ja`) : Like `standard`, but splits Chinese, Japanese and Korean
+///    texts into overlapping bigrams of characters.
 ///  * `en_stem` : Like `default`, but also applies stemming on the
 ///  resulting tokens. Stemming can improve the recall of your
 ///  search engine.
+///  * `fr_stem`, `de_stem`, `es_stem`... : Like `standard`, but also applies
+///    Unicode normalization and case folding, elision removal, stop word removal
+///    and stemming for the language, see [`language_analyzer`](#method.language_analyzer).
 #[derive(Clone)]
 pub struct TokenizerManager {
     tokenizers: Arc<RwLock<HashMap<String, TextAnalyzer>>>,
@@ -46,12 +59,55 @@ impl TokenizerManager {
             .get(tokenizer_name)
             .cloned()
     }
+
+    /// Creates the analyzer registered for the given language under the name
+    /// `<ISO 639-1 code>_stem` (e.g. `fr_stem`).
+    ///
+    /// It chops the text following the Unicode word boundary rules, removes
+    /// tokens that are too long, applies NFKC normalization and case folding,
+    /// removes elided articles (French and Italian), removes the built-in stop words
+    /// of the language, and applies stemming.
+    pub fn language_analyzer(language: Language) -> TextAnalyzer {
+        let mut analyzer = TextAnalyzer::from(StandardTokenizer)
+            .filter(RemoveLongFilter::limit(40))
+            .filter(UnicodeNormalizationFilter::default());
+        if let Some(elision_filter) = ElisionFilter::new(language) {
+            analyzer = analyzer.filter(elision_filter);
+        }
+        analyzer
+            .filter(StopWordFilter::new(language))
+            .filter(Stemmer::new(language))
+    }
 }
 
+// Languages having an analyzer registered by default, with their ISO 639-1 codes.
+// English is registered as `en_stem`, without stop words.
+const LANGUAGE_CODES: [(Language, &str); 17] = [
+    (Language::Arabic, "ar"),
+    (Language::Danish, "da"),
+    (Language::Dutch, "nl"),
+    (Language::Finnish, "fi"),
+    (Language::French, "fr"),
+    (Language::German, "de"),
+    (Language::Greek, "el"),
+    (Language::Hungarian, "hu"),
+    (Language::Italian, "it"),
+    (Language::Norwegian, "no"),
+    (Language::Portuguese, "pt"),
+    (Language::Romanian, "ro"),
+    (Language::Russian, "ru"),
+    (Language::Spanish, "es"),
+    (Language::Swedish, "sv"),
+    (Language::Tamil, "ta"),
+    (Language::Turkish, "tr"),
+];
+
 impl Default for TokenizerManager {
     /// Creates an `TokenizerManager` prepopulated with
     /// the default pre-configured tokenizers of `tantivy`.
     /// - simple
+    /// - standard
+    /// - cjk
     /// - en_stem
     /// - ja
     fn default() -> TokenizerManager {
@@ -65,6 +121,18 @@ impl Default for TokenizerManager {
                 .filter(RemoveLongFilter::limit(40))
                 .filter(LowerCaser),
         );
+        manager.register(
+            "standard",
+            TextAnalyzer::from(StandardTokenizer)
+                .filter(RemoveLongFilter::limit(40))
+                .filter(LowerCaser),
+        );
+        let cjk_tokenizer = TextAnalyzer::from(StandardTokenizer)
+            .filter(RemoveLongFilter::limit(40))
+            .filter(LowerCaser)
+            .filter(CjkBigramFilter::default());
+        manager.register("cjk", cjk_tokenizer.clone());
+        manager.register("ja", cjk_tokenizer);
         manager.register(
             "en_stem",
             TextAnalyzer::from(SimpleTokenizer)
@@ -72,6 +140,12 @@ impl Default for TokenizerManager {
                 .filter(LowerCaser)
                 .filter(Stemmer::new(Language::English)),
         );
+        for &(language, code) in LANGUAGE_CODES.iter() {
+            manager.register(
+                &format!("{}_stem", code),
+                TokenizerManager::language_analyzer(language),
+            );
+        }
         manager
     }
 }
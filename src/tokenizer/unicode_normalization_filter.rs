@@ -0,0 +1,210 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let tokenizer = TextAnalyzer::from(StandardTokenizer)
+//!   .filter(UnicodeNormalizationFilter::default());
+//!
+//! // The first "é" is written as "e" followed by a combining acute accent.
+//! let mut stream = tokenizer.token_stream("STRASSE Straße Cafe\u{301} café");
+//! assert_eq!(stream.next().unwrap().text, "strasse");
+//! assert_eq!(stream.next().unwrap().text, "strasse");
+//! assert_eq!(stream.next().unwrap().text, "café");
+//! assert_eq!(stream.next().unwrap().text, "café");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{BoxTokenStream, Token, TokenFilter, TokenStream};
+use serde::{Deserialize, Serialize};
+use std::mem;
+use unicode_normalization::UnicodeNormalization;
+
+/// Unicode normalization forms.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Copy, Clone)]
+pub enum NormalizationForm {
+    /// Canonical composition: canonically equivalent strings, such as `é` written
+    /// as one character or as `e` followed by a combining accent, are made identical.
+    Nfc,
+    /// Compatibility composition: on top of `Nfc`, compatibility characters are
+    /// replaced by their usual equivalent (`ﬁ` → `fi`, `²` → `2`, full-width `Ａ` → `A`...).
+    Nfkc,
+}
+
+/// `UnicodeNormalizationFilter` applies a Unicode normalization form to the tokens,
+/// and by default full Unicode case folding.
+///
+/// Case folding is more thorough than lowercasing: for instance,
+/// German `ß` is folded to `ss`, and Greek final sigma `ς` to `σ`.
+/// Contrary to the [`AsciiFoldingFilter`](./struct.AsciiFoldingFilter.html),
+/// accents and non-Latin characters are preserved.
+#[derive(Clone)]
+pub struct UnicodeNormalizationFilter {
+    normalization_form: NormalizationForm,
+    case_folding: bool,
+}
+
+impl Default for UnicodeNormalizationFilter {
+    /// Creates a `UnicodeNormalizationFilter` applying NFKC normalization and case folding.
+    fn default() -> UnicodeNormalizationFilter {
+        UnicodeNormalizationFilter::new(NormalizationForm::Nfkc)
+    }
+}
+
+impl UnicodeNormalizationFilter {
+    /// Creates a `UnicodeNormalizationFilter` applying the given normalization form
+    /// and case folding.
+    pub fn new(normalization_form: NormalizationForm) -> UnicodeNormalizationFilter {
+        UnicodeNormalizationFilter {
+            normalization_form,
+            case_folding: true,
+        }
+    }
+
+    /// Sets whether case folding should be applied. Defaults to true.
+    pub fn set_case_folding(mut self, case_folding: bool) -> UnicodeNormalizationFilter {
+        self.case_folding = case_folding;
+        self
+    }
+
+    // Writes the normalized version of `text` into `output`.
+    fn normalize(&self, text: &str, output: &mut String) {
+        output.clear();
+        if !self.case_folding {
+            match self.normalization_form {
+                NormalizationForm::Nfc => output.extend(text.nfc()),
+                NormalizationForm::Nfkc => output.extend(text.nfkc()),
+            }
+            return;
+        }
+        // Case folding is applied to the decomposed text, so that accented
+        // characters are folded the same way whether they were composed or not.
+        let mut folded = String::with_capacity(text.len());
+        match self.normalization_form {
+            NormalizationForm::Nfc => text.nfd().for_each(|c| fold_case(c, &mut folded)),
+            NormalizationForm::Nfkc => text.nfkd().for_each(|c| fold_case(c, &mut folded)),
+        }
+        match self.normalization_form {
+            NormalizationForm::Nfc => output.extend(folded.nfc()),
+            NormalizationForm::Nfkc => output.extend(folded.nfkc()),
+        }
+    }
+}
+
+fn is_cherokee(c: char) -> bool {
+    matches!(c, '\u{13A0}'..='\u{13FF}' | '\u{AB70}'..='\u{ABBF}')
+}
+
+// Writes the full case folding of `c` into `output`.
+fn fold_case(c: char, output: &mut String) {
+    if c.is_ascii() {
+        output.push(c.to_ascii_lowercase());
+    } else if is_cherokee(c) {
+        // Cherokee is folded to uppercase, for compatibility with earlier
+        // versions of Unicode where only uppercase letters existed.
+        output.extend(c.to_uppercase());
+    } else if c == 'ı' {
+        // Turkish dotless i has no case folding, while its uppercase `I` is folded to `i`.
+        output.push(c);
+    } else {
+        // Folds characters whose lowercase is not their case folding,
+        // such as `ß` (`SS` → `ss`) and `ς` (`Σ` → `σ`).
+        for lowercase in c.to_lowercase() {
+            for uppercase in lowercase.to_uppercase() {
+                output.extend(uppercase.to_lowercase());
+            }
+        }
+    }
+}
+
+impl TokenFilter for UnicodeNormalizationFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(UnicodeNormalizationFilterStream {
+            tail: token_stream,
+            filter: self.clone(),
+            buffer: String::with_capacity(100),
+        })
+    }
+}
+
+pub struct UnicodeNormalizationFilterStream<'a> {
+    tail: BoxTokenStream<'a>,
+    filter: UnicodeNormalizationFilter,
+    buffer: String,
+}
+
+impl<'a> TokenStream for UnicodeNormalizationFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        if self.tail.token().text.is_ascii() {
+            // ASCII text is already normalized.
+            if self.filter.case_folding {
+                self.tail.token_mut().text.make_ascii_lowercase();
+            }
+        } else {
+            self.filter
+                .normalize(&self.tail.token().text, &mut self.buffer);
+            mem::swap(&mut self.tail.token_mut().text, &mut self.buffer);
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NormalizationForm, UnicodeNormalizationFilter};
+    use crate::tokenizer::{RawTokenizer, TextAnalyzer, Token};
+
+    fn normalize(filter: UnicodeNormalizationFilter, text: &str) -> String {
+        let analyzer = TextAnalyzer::from(RawTokenizer).filter(filter);
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.text.clone());
+        };
+        token_stream.process(&mut add_token);
+        assert_eq!(tokens.len(), 1);
+        tokens.pop().unwrap()
+    }
+
+    #[test]
+    fn test_unicode_normalization_filter_case_folding() {
+        let nfc = || UnicodeNormalizationFilter::new(NormalizationForm::Nfc);
+        assert_eq!(normalize(nfc(), "Straße"), "strasse");
+        assert_eq!(normalize(nfc(), "STRAẞE"), "strasse");
+        assert_eq!(normalize(nfc(), "ΟΔΥΣΣΕΥΣ"), "οδυσσευσ");
+        assert_eq!(normalize(nfc(), "Οδυσσεύς"), "οδυσσεύσ");
+        assert_eq!(normalize(nfc(), "ﬁ"), "fi");
+        assert_eq!(normalize(nfc(), "İstanbul"), "i\u{307}stanbul");
+        assert_eq!(normalize(nfc(), "ıi"), "ıi");
+        assert_eq!(normalize(nfc(), "\u{abb3}\u{13e3}"), "\u{13e3}\u{13e3}");
+        assert_eq!(normalize(nfc(), "ΐ"), normalize(nfc(), "Ϊ́"));
+        assert_eq!(normalize(nfc(), "ABC"), "abc");
+    }
+
+    #[test]
+    fn test_unicode_normalization_filter_forms() {
+        let nfc = || UnicodeNormalizationFilter::new(NormalizationForm::Nfc);
+        let nfkc = || UnicodeNormalizationFilter::new(NormalizationForm::Nfkc);
+        // "é" composed, and decomposed.
+        assert_eq!(normalize(nfc(), "caf\u{e9}"), "caf\u{e9}");
+        assert_eq!(normalize(nfc(), "cafe\u{301}"), "caf\u{e9}");
+        assert_eq!(
+            normalize(nfc().set_case_folding(false), "CAFE\u{301}"),
+            "CAF\u{c9}"
+        );
+        assert_eq!(normalize(nfc(), "x²"), "x²");
+        assert_eq!(normalize(nfkc(), "x²"), "x2");
+        assert_eq!(normalize(nfkc(), "ＡＢＣ"), "abc");
+        assert_eq!(normalize(nfkc().set_case_folding(false), "ＡＢＣ"), "ABC");
+        assert_eq!(normalize(nfkc().set_case_folding(false), "ABC"), "ABC");
+    }
+}
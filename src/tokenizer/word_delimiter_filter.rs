@@ -0,0 +1,445 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let tokenizer = TextAnalyzer::from(SimpleTokenizer)
+//!   .filter(WordDelimiterFilter::default().set_catenate_all(true))
+//!   .filter(LowerCaser);
+//!
+//! let mut stream = tokenizer.token_stream("PowerShot500");
+//! assert_eq!(stream.next().unwrap().text, "powershot500");
+//! assert_eq!(stream.next().unwrap().text, "power");
+//! assert_eq!(stream.next().unwrap().text, "shot");
+//! assert_eq!(stream.next().unwrap().text, "500");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{
+    sub_token_offsets, BoxTokenStream, Token, TokenBuffer, TokenFilter, TokenStream, TokenType,
+};
+use std::cmp::Reverse;
+use std::ops::Range;
+
+/// `WordDelimiterFilter` splits tokens into subwords, on case changes
+/// (`PowerShot` → `Power`, `Shot`), letter/digit transitions (`SD500` → `SD`, `500`),
+/// and on the characters that are neither letters nor digits (`wi-fi` → `wi`, `fi`).
+///
+/// The parts of a token are emitted at consecutive positions, and the positions
+/// of the following tokens are shifted accordingly.
+/// The original token and the concatenations of parts are emitted at the position
+/// of their first part, with a `position_length` equal to the number of parts they span,
+/// so that phrase queries match both the parts and the concatenations.
+///
+/// Tokens that do not need to be split are emitted unchanged.
+/// This filter should be applied before the [`LowerCaser`](./struct.LowerCaser.html),
+/// which would hide the case changes.
+#[derive(Clone)]
+pub struct WordDelimiterFilter {
+    generate_word_parts: bool,
+    generate_number_parts: bool,
+    catenate_words: bool,
+    catenate_numbers: bool,
+    catenate_all: bool,
+    preserve_original: bool,
+    split_on_case_change: bool,
+    split_on_numerics: bool,
+}
+
+impl Default for WordDelimiterFilter {
+    /// Creates a `WordDelimiterFilter` emitting the word and number parts only.
+    fn default() -> WordDelimiterFilter {
+        WordDelimiterFilter {
+            generate_word_parts: true,
+            generate_number_parts: true,
+            catenate_words: false,
+            catenate_numbers: false,
+            catenate_all: false,
+            preserve_original: false,
+            split_on_case_change: true,
+            split_on_numerics: true,
+        }
+    }
+}
+
+impl WordDelimiterFilter {
+    /// Sets whether the parts made of letters should be emitted. Defaults to true.
+    pub fn set_generate_word_parts(mut self, generate_word_parts: bool) -> WordDelimiterFilter {
+        self.generate_word_parts = generate_word_parts;
+        self
+    }
+
+    /// Sets whether the parts made of digits should be emitted. Defaults to true.
+    pub fn set_generate_number_parts(mut self, generate_number_parts: bool) -> WordDelimiterFilter {
+        self.generate_number_parts = generate_number_parts;
+        self
+    }
+
+    /// Sets whether runs of consecutive word parts should be concatenated
+    /// (`wi-fi-4000` → `wifi`). Defaults to false.
+    pub fn set_catenate_words(mut self, catenate_words: bool) -> WordDelimiterFilter {
+        self.catenate_words = catenate_words;
+        self
+    }
+
+    /// Sets whether runs of consecutive number parts should be concatenated
+    /// (`500-42` → `50042`). Defaults to false.
+    pub fn set_catenate_numbers(mut self, catenate_numbers: bool) -> WordDelimiterFilter {
+        self.catenate_numbers = catenate_numbers;
+        self
+    }
+
+    /// Sets whether all of the parts should be concatenated
+    /// (`wi-fi-4000` → `wifi4000`). Defaults to false.
+    pub fn set_catenate_all(mut self, catenate_all: bool) -> WordDelimiterFilter {
+        self.catenate_all = catenate_all;
+        self
+    }
+
+    /// Sets whether the original token should be emitted as well. Defaults to false.
+    pub fn set_preserve_original(mut self, preserve_original: bool) -> WordDelimiterFilter {
+        self.preserve_original = preserve_original;
+        self
+    }
+
+    /// Sets whether tokens should be split on case changes. Defaults to true.
+    pub fn set_split_on_case_change(mut self, split_on_case_change: bool) -> WordDelimiterFilter {
+        self.split_on_case_change = split_on_case_change;
+        self
+    }
+
+    /// Sets whether tokens should be split on letter/digit transitions. Defaults to true.
+    pub fn set_split_on_numerics(mut self, split_on_numerics: bool) -> WordDelimiterFilter {
+        self.split_on_numerics = split_on_numerics;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum CharClass {
+    Lower,
+    Upper,
+    Digit,
+    Delimiter,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_numeric() {
+        CharClass::Digit
+    } else if c.is_uppercase() {
+        CharClass::Upper
+    } else if c.is_alphabetic() {
+        CharClass::Lower
+    } else {
+        CharClass::Delimiter
+    }
+}
+
+// A subword of a token.
+struct Part {
+    range: Range<usize>,
+    is_number: bool,
+}
+
+impl WordDelimiterFilter {
+    fn is_part_boundary(&self, previous: CharClass, current: CharClass, next: CharClass) -> bool {
+        if self.split_on_numerics && (previous == CharClass::Digit) != (current == CharClass::Digit)
+        {
+            return true;
+        }
+        // `PowerShot` is split before `S`, and `HTTPServer` before `S`.
+        self.split_on_case_change
+            && current == CharClass::Upper
+            && (previous == CharClass::Lower
+                || (previous == CharClass::Upper && next == CharClass::Lower))
+    }
+
+    fn split(&self, text: &str) -> Vec<Part> {
+        let chars: Vec<(usize, CharClass)> = text
+            .char_indices()
+            .map(|(offset, c)| (offset, char_class(c)))
+            .collect();
+        let mut parts = Vec::new();
+        let mut part_start: Option<usize> = None;
+        let mut previous = CharClass::Delimiter;
+        for (i, &(offset, current)) in chars.iter().enumerate() {
+            let next = chars
+                .get(i + 1)
+                .map(|&(_, next)| next)
+                .unwrap_or(CharClass::Delimiter);
+            if let Some(start) = part_start {
+                if current == CharClass::Delimiter || self.is_part_boundary(previous, current, next)
+                {
+                    parts.push(Part {
+                        range: start..offset,
+                        is_number: previous == CharClass::Digit,
+                    });
+                    part_start = None;
+                }
+            }
+            if current != CharClass::Delimiter && part_start.is_none() {
+                part_start = Some(offset);
+            }
+            previous = current;
+        }
+        if let Some(start) = part_start {
+            parts.push(Part {
+                range: start..text.len(),
+                is_number: previous == CharClass::Digit,
+            });
+        }
+        parts
+    }
+
+    // Pushes the tokens produced from `token`, whose first part is at position
+    // `position`, to `output`. Returns the number of parts of the token.
+    fn process(&self, token: &Token, position: usize, output: &mut TokenBuffer) -> usize {
+        let parts = self.split(&token.text);
+        if parts.len() == 1 && parts[0].range == (0..token.text.len()) {
+            output.push(Token {
+                position,
+                ..token.clone()
+            });
+            return 1;
+        }
+        let make_token = |parts: &[Part], first_part: usize, token_type: TokenType| {
+            let first_range = &parts[0].range;
+            let last_range = &parts[parts.len() - 1].range;
+            let (offset_from, offset_to) =
+                sub_token_offsets(token, first_range.start, last_range.end);
+            let text = parts
+                .iter()
+                .map(|part| &token.text[part.range.clone()])
+                .collect();
+            Token {
+                offset_from,
+                offset_to,
+                position: position + first_part,
+                text,
+                position_length: parts.len(),
+                token_type,
+            }
+        };
+        let mut tokens = Vec::new();
+        if self.preserve_original {
+            tokens.push(Token {
+                position,
+                position_length: parts.len().max(1),
+                ..token.clone()
+            });
+        }
+        if self.catenate_all && parts.len() > 1 {
+            tokens.push(make_token(&parts, 0, TokenType::Word));
+        }
+        let mut run_start = 0;
+        for run_end in 1..=parts.len() {
+            if run_end < parts.len() && parts[run_end].is_number == parts[run_start].is_number {
+                continue;
+            }
+            let run = &parts[run_start..run_end];
+            let is_number = run[0].is_number;
+            let catenate = if is_number {
+                self.catenate_numbers
+            } else {
+                self.catenate_words
+            };
+            if catenate && run.len() > 1 {
+                let token_type = if is_number {
+                    TokenType::Number
+                } else {
+                    TokenType::Word
+                };
+                tokens.push(make_token(run, run_start, token_type));
+            }
+            run_start = run_end;
+        }
+        for (i, part) in parts.iter().enumerate() {
+            if part.is_number && self.generate_number_parts {
+                tokens.push(make_token(&parts[i..=i], i, TokenType::Number));
+            } else if !part.is_number && self.generate_word_parts {
+                tokens.push(make_token(&parts[i..=i], i, TokenType::Word));
+            }
+        }
+        // Tokens are emitted by position, the longest ones first.
+        tokens.sort_by_key(|token| (token.position, Reverse(token.position_length)));
+        for (i, token) in tokens.iter().enumerate() {
+            let is_duplicate = tokens[..i].iter().any(|previous_token| {
+                previous_token.position == token.position
+                    && previous_token.position_length == token.position_length
+                    && previous_token.text == token.text
+            });
+            if !is_duplicate {
+                output.push(token.clone());
+            }
+        }
+        parts.len()
+    }
+}
+
+impl TokenFilter for WordDelimiterFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(WordDelimiterFilterStream {
+            tail: token_stream,
+            filter: self.clone(),
+            output: TokenBuffer::default(),
+            tail_position: usize::MAX,
+            position_shift: 0,
+            pending_position_shift: 0,
+        })
+    }
+}
+
+pub struct WordDelimiterFilterStream<'a> {
+    tail: BoxTokenStream<'a>,
+    filter: WordDelimiterFilter,
+    output: TokenBuffer,
+    // Position of the last token read from the tail.
+    tail_position: usize,
+    // Number of positions added by the tokens split so far.
+    position_shift: usize,
+    // Number of positions added by the tokens at `tail_position`.
+    pending_position_shift: usize,
+}
+
+impl<'a> TokenStream for WordDelimiterFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        while self.output.is_empty() {
+            if !self.tail.advance() {
+                return false;
+            }
+            let token = self.tail.token();
+            if token.position != self.tail_position {
+                self.position_shift += self.pending_position_shift;
+                self.pending_position_shift = 0;
+                self.tail_position = token.position;
+            }
+            let num_parts = self.filter.process(
+                token,
+                token.position + self.position_shift,
+                &mut self.output,
+            );
+            self.pending_position_shift =
+                self.pending_position_shift.max(num_parts.saturating_sub(1));
+        }
+        self.output.advance()
+    }
+
+    fn token(&self) -> &Token {
+        self.output.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.output.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WordDelimiterFilter;
+    use crate::collector::Count;
+    use crate::query::QueryParser;
+    use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions};
+    use crate::tokenizer::tests::{assert_token, token_stream_helper};
+    use crate::tokenizer::{LowerCaser, RegexTokenizer, SimpleTokenizer, TextAnalyzer, TokenType};
+    use crate::Index;
+
+    fn word_delimiter_analyzer(word_delimiter_filter: WordDelimiterFilter) -> TextAnalyzer {
+        TextAnalyzer::from(RegexTokenizer::split(" +").unwrap()).filter(word_delimiter_filter)
+    }
+
+    #[test]
+    fn test_word_delimiter_filter_split() {
+        let analyzer = word_delimiter_analyzer(WordDelimiterFilter::default());
+        let tokens = token_stream_helper(&analyzer, "PowerShot500 wi-fi HTTPServer ok --");
+        assert_eq!(tokens.len(), 8);
+        assert_token(&tokens[0], 0, "Power", 0, 5);
+        assert_token(&tokens[1], 1, "Shot", 5, 9);
+        assert_token(&tokens[2], 2, "500", 9, 12);
+        assert_eq!(tokens[2].token_type, TokenType::Number);
+        assert_token(&tokens[3], 3, "wi", 13, 15);
+        assert_token(&tokens[4], 4, "fi", 16, 18);
+        assert_token(&tokens[5], 5, "HTTP", 19, 23);
+        assert_token(&tokens[6], 6, "Server", 23, 29);
+        assert_token(&tokens[7], 7, "ok", 30, 32);
+
+        let analyzer = word_delimiter_analyzer(
+            WordDelimiterFilter::default()
+                .set_split_on_case_change(false)
+                .set_split_on_numerics(false),
+        );
+        let tokens = token_stream_helper(&analyzer, "PowerShot500 wi-fi");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "PowerShot500", 0, 12);
+        assert_token(&tokens[1], 1, "wi", 13, 15);
+        assert_token(&tokens[2], 2, "fi", 16, 18);
+    }
+
+    #[test]
+    fn test_word_delimiter_filter_catenate() {
+        let analyzer = word_delimiter_analyzer(
+            WordDelimiterFilter::default()
+                .set_catenate_words(true)
+                .set_catenate_numbers(true)
+                .set_catenate_all(true)
+                .set_preserve_original(true),
+        );
+        let tokens = token_stream_helper(&analyzer, "wi-fi-500-42 x");
+        let positions: Vec<(usize, usize, &str)> = tokens
+            .iter()
+            .map(|token| (token.position, token.position_length, token.text.as_str()))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                (0, 4, "wi-fi-500-42"),
+                (0, 4, "wifi50042"),
+                (0, 2, "wifi"),
+                (0, 1, "wi"),
+                (1, 1, "fi"),
+                (2, 2, "50042"),
+                (2, 1, "500"),
+                (3, 1, "42"),
+                (4, 1, "x"),
+            ]
+        );
+        assert_token(&tokens[1], 0, "wifi50042", 0, 12);
+        assert_token(&tokens[5], 2, "50042", 6, 12);
+
+        // The concatenation of all parts is not repeated when equal to the original token.
+        let tokens = token_stream_helper(&analyzer, "PowerShot");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "PowerShot", 0, 9);
+        assert_eq!(tokens[0].position_length, 2);
+        assert_token(&tokens[1], 0, "Power", 0, 5);
+        assert_token(&tokens[2], 1, "Shot", 5, 9);
+    }
+
+    #[test]
+    fn test_word_delimiter_filter_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field_indexing = TextFieldIndexing::default()
+            .set_tokenizer("code")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let text_options = TextOptions::default().set_indexing_options(text_field_indexing);
+        let title = schema_builder.add_text_field("title", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        index.tokenizers().register(
+            "code",
+            TextAnalyzer::from(SimpleTokenizer)
+                .filter(WordDelimiterFilter::default().set_catenate_all(true))
+                .filter(LowerCaser),
+        );
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title=>"Canon PowerShot500 camera"));
+        index_writer.add_document(doc!(title=>"power shot"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let count =
+            |query: &str| searcher.search(&query_parser.parse_query(query).unwrap(), &Count);
+        assert_eq!(count("powershot500")?, 1);
+        assert_eq!(count("PowerShot500")?, 1);
+        assert_eq!(count("\"power shot 500 camera\"")?, 1);
+        assert_eq!(count("\"canon powershot500\"")?, 1);
+        assert_eq!(count("\"power shot\"")?, 2);
+        Ok(())
+    }
+}